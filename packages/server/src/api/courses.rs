@@ -1,8 +1,23 @@
+use std::collections::HashMap;
+use std::hash::{DefaultHasher, Hash, Hasher};
 use std::sync::Arc;
+use std::time::{SystemTime, UNIX_EPOCH};
 
-use axum::{extract::Path, http::StatusCode, response::IntoResponse, Extension, Json};
+use axum::{
+    extract::{Path, Query},
+    http::{header, HeaderMap, HeaderValue, StatusCode},
+    response::{IntoResponse, Response},
+    Extension, Json,
+};
+use chrono::{DateTime, Utc};
+use serde::Serialize;
 
-use crate::disk_cache::DiskCourseCache;
+use crate::disk_cache::{CachedCourse, DiskCourseCache};
+use crate::error::AppError;
+use crate::sap::CourseDetails;
+
+// The most courses a single batch request may ask for, counting every semester of `ids`.
+const MAX_BATCH_SIZE: usize = 200;
 
 pub async fn get_semesters(Extension(cache): Extension<Arc<DiskCourseCache>>) -> impl IntoResponse {
     let semesters = cache.discover_semesters();
@@ -22,9 +37,213 @@ pub async fn get_course_index(
 pub async fn get_course(
     Extension(cache): Extension<Arc<DiskCourseCache>>,
     Path((year, semester, course_id)): Path<(String, String, String)>,
+    headers: HeaderMap,
 ) -> impl IntoResponse {
-    match cache.get_course(&year, &semester, &course_id).await {
-        Some(details) => Json(&*details).into_response(),
+    match cache.get_cached_course(&year, &semester, &course_id).await {
+        Some(cached) => {
+            let validators = Validators::of([(&year, &semester, &course_id, &cached)]);
+            validators.respond(&headers, || Json(&*cached.details).into_response())
+        }
         None => StatusCode::NOT_FOUND.into_response(),
     }
 }
+
+#[derive(Clone, Debug, Serialize)]
+pub struct BatchCourse<'a> {
+    pub year: &'a str,
+    pub semester: &'a str,
+    pub course: &'a CourseDetails,
+}
+
+struct BatchKey {
+    year: String,
+    semester: String,
+    course_id: String,
+}
+
+#[derive(Clone, Debug, Serialize)]
+pub struct BatchResponse<'a> {
+    pub courses: Vec<BatchCourse<'a>>,
+    // Requested courses which aren't on disk, as they were requested. Courses requested by id alone
+    // are only listed here when no semester has them.
+    pub not_found: Vec<String>,
+}
+
+// Serves many courses at once, for the planner. Query parameters:
+//   courses: comma separated "{year}/{semester}/{course_id}"
+//   ids:     comma separated course ids, returned from every semester which has them
+pub async fn get_courses_batch(
+    Extension(cache): Extension<Arc<DiskCourseCache>>,
+    Query(params): Query<HashMap<String, String>>,
+    headers: HeaderMap,
+) -> Result<Response, AppError> {
+    let split = |name: &str| {
+        params
+            .get(name)
+            .map(|value| {
+                value
+                    .split(',')
+                    .map(str::trim)
+                    .filter(|item| !item.is_empty())
+                    .map(str::to_string)
+                    .collect::<Vec<_>>()
+            })
+            .unwrap_or_default()
+    };
+
+    let mut keys = Vec::new();
+    for key in split("courses") {
+        let mut parts = key.split('/');
+        let (Some(year), Some(semester), Some(course_id), None) =
+            (parts.next(), parts.next(), parts.next(), parts.next())
+        else {
+            return Err(AppError::BadRequest(format!(
+                "Invalid course key {key:?}, expected year/semester/course_id"
+            )));
+        };
+        keys.push(BatchKey {
+            year: year.to_string(),
+            semester: semester.to_string(),
+            course_id: course_id.to_string(),
+        });
+    }
+    let ids = split("ids");
+    let semesters = if ids.is_empty() {
+        Vec::new()
+    } else {
+        cache.discover_semesters()
+    };
+    if keys.len() + ids.len() * semesters.len() > MAX_BATCH_SIZE {
+        return Err(AppError::BadRequest(format!(
+            "At most {MAX_BATCH_SIZE} courses can be requested at once"
+        )));
+    }
+
+    let mut found = Vec::new();
+    let mut not_found = Vec::new();
+    for key in keys {
+        match cache
+            .get_cached_course(&key.year, &key.semester, &key.course_id)
+            .await
+        {
+            Some(cached) => found.push((key, cached)),
+            None => not_found.push(format!("{}/{}/{}", key.year, key.semester, key.course_id)),
+        }
+    }
+    for course_id in ids {
+        let mut any = false;
+        // Newest semester first, as discovered
+        for semester in &semesters {
+            let key = BatchKey {
+                year: semester.year.clone(),
+                semester: semester.semester.clone(),
+                course_id: course_id.clone(),
+            };
+            if let Some(cached) = cache
+                .get_cached_course(&key.year, &key.semester, &key.course_id)
+                .await
+            {
+                found.push((key, cached));
+                any = true;
+            }
+        }
+        if !any {
+            not_found.push(course_id);
+        }
+    }
+
+    let validators = Validators::of(
+        found
+            .iter()
+            .map(|(key, cached)| (&key.year, &key.semester, &key.course_id, cached)),
+    );
+    Ok(validators.respond(&headers, || {
+        Json(BatchResponse {
+            courses: found
+                .iter()
+                .map(|(key, cached)| BatchCourse {
+                    year: &key.year,
+                    semester: &key.semester,
+                    course: &cached.details,
+                })
+                .collect(),
+            not_found,
+        })
+        .into_response()
+    }))
+}
+
+// Cache validators derived from the course files the response was built from: which files, and
+// when each was last written by the fetcher.
+struct Validators {
+    etag: String,
+    last_modified: Option<SystemTime>,
+}
+
+impl Validators {
+    fn of<'a>(
+        courses: impl IntoIterator<Item = (&'a String, &'a String, &'a String, &'a CachedCourse)>,
+    ) -> Self {
+        let mut hasher = DefaultHasher::new();
+        let mut last_modified = None;
+        for (year, semester, course_id, cached) in courses {
+            (year, semester, course_id).hash(&mut hasher);
+            let modified = cached
+                .modified
+                .duration_since(UNIX_EPOCH)
+                .unwrap_or_default();
+            modified.as_nanos().hash(&mut hasher);
+            last_modified = last_modified.max(Some(cached.modified));
+        }
+        Validators {
+            etag: format!("W/\"{:016x}\"", hasher.finish()),
+            last_modified,
+        }
+    }
+
+    fn not_modified(&self, headers: &HeaderMap) -> bool {
+        // If-None-Match takes precedence over If-Modified-Since when both are sent
+        if let Some(if_none_match) = headers.get(header::IF_NONE_MATCH) {
+            return if_none_match.to_str().is_ok_and(|tags| {
+                tags.split(',')
+                    .map(|tag| tag.trim().trim_start_matches("W/"))
+                    .any(|tag| tag == "*" || tag == self.etag.trim_start_matches("W/"))
+            });
+        }
+        let (Some(if_modified_since), Some(last_modified)) =
+            (headers.get(header::IF_MODIFIED_SINCE), self.last_modified)
+        else {
+            return false;
+        };
+        let Some(since) = if_modified_since
+            .to_str()
+            .ok()
+            .and_then(|since| DateTime::parse_from_rfc2822(since).ok())
+        else {
+            return false;
+        };
+        // HTTP dates have a resolution of a second
+        DateTime::<Utc>::from(last_modified).timestamp() <= since.timestamp()
+    }
+
+    fn respond(&self, headers: &HeaderMap, response: impl FnOnce() -> Response) -> Response {
+        let mut response = if self.not_modified(headers) {
+            StatusCode::NOT_MODIFIED.into_response()
+        } else {
+            response()
+        };
+        let response_headers = response.headers_mut();
+        if let Ok(etag) = HeaderValue::from_str(&self.etag) {
+            response_headers.insert(header::ETAG, etag);
+        }
+        if let Some(last_modified) = self.last_modified {
+            let http_date = DateTime::<Utc>::from(last_modified)
+                .format("%a, %d %b %Y %H:%M:%S GMT")
+                .to_string();
+            if let Ok(last_modified) = HeaderValue::from_str(&http_date) {
+                response_headers.insert(header::LAST_MODIFIED, last_modified);
+            }
+        }
+        response
+    }
+}
@@ -1,7 +1,11 @@
+use std::collections::HashSet;
 use std::str::FromStr;
+use std::sync::Arc;
 
+use crate::core::catalog_diff::CatalogDiff;
 use crate::core::catalog_validations;
 use crate::db::Db;
+use crate::disk_cache::DiskCourseCache;
 use crate::error::AppError;
 use crate::resources::catalog::Catalog;
 use crate::resources::{course::Course, user::User};
@@ -46,6 +50,14 @@ pub async fn delete_course(
         .map(|_| StatusCode::OK)
 }
 
+// Rereads the fetcher's output from disk, e.g. right after a fetch, without restarting the server.
+pub async fn reload_course_cache(
+    _: User,
+    Extension(course_cache): Extension<Arc<DiskCourseCache>>,
+) -> impl IntoResponse {
+    Json(course_cache.load_all().await)
+}
+
 /////////////////////////////////////////////////////////////////////////////
 // Catalog API
 /////////////////////////////////////////////////////////////////////////////
@@ -59,12 +71,42 @@ pub async fn get_catalog_by_id(
     db.get::<Catalog>(&obj_id).await.map(Json)
 }
 
+// Compares the catalog to another one, e.g. the previous year of the same track.
+pub async fn diff_catalogs(
+    _: User,
+    Path((id, other_id)): Path<(String, String)>,
+    Extension(db): Extension<Db>,
+) -> Result<impl IntoResponse, AppError> {
+    let parse =
+        |id: &str| bson::oid::ObjectId::from_str(id).map_err(|e| AppError::Bson(e.to_string()));
+    let from = db.get::<Catalog>(&parse(&id)?).await?;
+    let to = db.get::<Catalog>(&parse(&other_id)?).await?;
+    Ok(Json(CatalogDiff::between(&from, &to)))
+}
+
 pub async fn create_or_update_catalog(
     _: User,
     Path(_id): Path<String>,
     Extension(db): Extension<Db>,
+    Extension(course_cache): Extension<Arc<DiskCourseCache>>,
     Json(catalog): Json<Catalog>,
 ) -> Result<impl IntoResponse, AppError> {
-    catalog_validations::validate_catalog(&catalog)?;
+    let known_courses = course_cache
+        .get_all_courses()
+        .await
+        .into_keys()
+        .collect::<HashSet<_>>();
+    // The cache is empty until the fetcher ran, in which case there's nothing to check against
+    let known_courses = (!known_courses.is_empty()).then_some(known_courses);
+    catalog_validations::validate_catalog(&catalog, known_courses.as_ref())?;
+    if let Some(known_courses) = &known_courses {
+        for course_id in catalog_validations::unknown_courses(&catalog, known_courses) {
+            log::warn!(
+                target: "sogrim_server",
+                "Catalog \"{}\": course {course_id} is not in the course cache",
+                catalog.name
+            );
+        }
+    }
     db.create_or_update::<Catalog>(catalog).await.map(Json)
 }
@@ -1,23 +1,50 @@
-use std::{collections::HashMap, str::FromStr, sync::Arc};
+use std::{
+    collections::{HashMap, HashSet},
+    str::FromStr,
+    sync::Arc,
+};
 
-use axum::{extract::Query, response::IntoResponse, Extension, Json};
+use axum::{
+    body::Bytes,
+    extract::{Path, Query},
+    response::IntoResponse,
+    Extension, Json,
+};
 use bson::DateTime;
 use chrono::Datelike;
-use http::StatusCode;
+use http::{header, HeaderMap, HeaderName, StatusCode};
+use serde::{Deserialize, Serialize};
 
 use crate::{
-    core::{degree_status::DegreeStatus, parser_v2},
+    core::{
+        course_search::{self, SearchQuery},
+        degree_status::{simulation::PlannedCourse, trace::AssignmentTrace, DegreeStatus},
+        parser_v2::{self, ParseReport},
+        prerequisites::{self, Prerequisite},
+        timetable::{
+            self,
+            generator::{self, TimetablePreferences},
+            ics,
+        },
+        transcript_pdf,
+    },
     db::{Db, FilterOption},
     disk_cache::DiskCourseCache,
     error::AppError,
     middleware::jwt_decoder::Sub,
     resources::{
         catalog::{Catalog, DisplayCatalog},
-        course::Course,
-        user::{TimetableState, User, UserDetails, UserSettings},
+        course::{AcademicSemester, Course, CourseId, CourseStatus, Tag},
+        user::{
+            Notification, SecondaryCatalog, SecondaryRole, TimetableDraft, TimetableState, User,
+            UserDetails, UserField, UserSettings,
+        },
     },
+    sap::CourseDetails,
 };
 
+const X_TOTAL_COUNT: &str = "x-total-count";
+
 pub async fn get_catalogs(
     _: User, //TODO think about whether this is necessary
     Query(params): Query<HashMap<String, String>>,
@@ -38,6 +65,27 @@ pub async fn get_catalogs(
     ))
 }
 
+// The revision a save expects: the one the client last saw, from an `If-Match: "<revision>"`
+// header, or else the one the user was loaded at for this request. The save fails with 409
+// Conflict if the user was saved since, e.g. from another tab.
+fn expected_revision(headers: &HeaderMap, user: &User) -> Result<Option<u64>, AppError> {
+    let Some(value) = headers.get(header::IF_MATCH) else {
+        return Ok(Some(user.revision));
+    };
+    value
+        .to_str()
+        .ok()
+        .map(|tag| tag.trim().trim_start_matches("W/").trim_matches('"'))
+        .and_then(|revision| revision.parse().ok())
+        .map(Some)
+        .ok_or_else(|| AppError::BadRequest("Invalid If-Match header".into()))
+}
+
+// The user's revision as an entity tag, to send back in If-Match.
+fn revision_etag(user: &User) -> [(HeaderName, String); 1] {
+    [(header::ETAG, format!("\"{}\"", user.revision))]
+}
+
 //TODO: maybe this should be "PUT" because it will ALWAYS create a user if one doesn't exist?
 pub async fn login(
     Extension(db): Extension<Db>,
@@ -53,7 +101,10 @@ pub async fn login(
     // Normalize legacy semester formats if necessary. This is needed to ensure that old users with legacy semester formats don't break the degree status computation and other features that rely on normalized semesters.
     // We do this before the last seen update to avoid unnecessary writes for users who have already been normalized.
     if updated_user.normalize_legacy_semesters() {
-        updated_user = db.update::<User>(updated_user).await?;
+        let revision = updated_user.revision;
+        updated_user
+            .save(&db, &[UserField::Details], Some(revision))
+            .await?;
     }
 
     // Asynchronously update the user's last seen time
@@ -67,23 +118,27 @@ pub async fn login(
             }
         }
         user.last_seen = Some(DateTime::now());
-        db.update::<User>(user).await?;
+        user.save(&db, &[UserField::LastSeen], None).await?;
         Result::<(), AppError>::Ok(())
     });
     // Don't send the user's last seen time to the client
     updated_user.last_seen = None;
 
-    Ok(Json(updated_user))
+    Ok((revision_etag(&updated_user), Json(updated_user)))
 }
 
 pub async fn update_catalog(
     mut user: User,
     Extension(db): Extension<Db>,
+    headers: HeaderMap,
     catalog_id: String,
 ) -> Result<impl IntoResponse, AppError> {
     let obj_id = bson::oid::ObjectId::from_str(&catalog_id)?;
     let catalog = db.get::<Catalog>(&obj_id).await?;
     user.details.catalog = Some(DisplayCatalog::from(catalog));
+    user.details
+        .secondary_catalogs
+        .retain(|secondary| secondary.catalog.id != obj_id);
     user.details.modified = true;
 
     // Updating the catalog renders the current course types invalid in the new catalog's context,
@@ -98,58 +153,215 @@ pub async fn update_catalog(
             cs.additional_msg = None;
         });
 
-    let updated_user = db.update::<User>(user).await?;
-    Ok(Json(updated_user))
+    user.save(
+        &db,
+        &[UserField::Details],
+        expected_revision(&headers, &user)?,
+    )
+    .await?;
+    Ok((revision_etag(&user), Json(user)))
+}
+
+#[derive(Deserialize)]
+pub struct SecondaryCatalogPayload {
+    pub catalog_id: String,
+    #[serde(default)]
+    pub role: SecondaryRole,
+    #[serde(default)]
+    pub max_shared_credit: Option<f32>,
+}
+
+// Replaces the user's minors and second degrees. They're computed on the next degree status.
+pub async fn update_secondary_catalogs(
+    mut user: User,
+    Extension(db): Extension<Db>,
+    headers: HeaderMap,
+    Json(payload): Json<Vec<SecondaryCatalogPayload>>,
+) -> Result<impl IntoResponse, AppError> {
+    let mut secondary_catalogs: Vec<SecondaryCatalog> = Vec::with_capacity(payload.len());
+    for secondary in payload {
+        let obj_id = bson::oid::ObjectId::from_str(&secondary.catalog_id)?;
+        let is_primary = user
+            .details
+            .catalog
+            .as_ref()
+            .is_some_and(|catalog| catalog.id == obj_id);
+        if is_primary || secondary_catalogs.iter().any(|s| s.catalog.id == obj_id) {
+            return Err(AppError::BadRequest(format!(
+                "Catalog {} was chosen more than once",
+                secondary.catalog_id
+            )));
+        }
+        if secondary
+            .max_shared_credit
+            .is_some_and(|credit| credit < 0.0)
+        {
+            return Err(AppError::BadRequest(
+                "Shared credit cap can't be negative".into(),
+            ));
+        }
+        let catalog = db.get::<Catalog>(&obj_id).await?;
+        secondary_catalogs.push(SecondaryCatalog {
+            catalog: DisplayCatalog::from(catalog),
+            role: secondary.role,
+            max_shared_credit: secondary.max_shared_credit,
+            degree_status: DegreeStatus::default(),
+            shared_courses: Vec::new(),
+        });
+    }
+    user.details.secondary_catalogs = secondary_catalogs;
+    user.details.modified = true;
+
+    user.save(
+        &db,
+        &[UserField::Details],
+        expected_revision(&headers, &user)?,
+    )
+    .await?;
+    Ok((revision_etag(&user), Json(user)))
+}
+
+// Parses an optional numeric query param, rejecting values which aren't numbers.
+fn numeric_param<T: FromStr>(
+    params: &HashMap<String, String>,
+    key: &str,
+) -> Result<Option<T>, AppError> {
+    params
+        .get(key)
+        .map(|value| {
+            value
+                .parse()
+                .map_err(|_| AppError::BadRequest(format!("Invalid {key}")))
+        })
+        .transpose()
 }
 
+// Full-text course search. `q`, `name` and `number` are all searched as text and may be combined
+// with the faculty, credit, tag and semester filters. The response holds a single page of courses,
+// best match first, and the number of matches is in the X-Total-Count header.
 pub async fn get_courses_by_filter(
     _: User,
     Query(params): Query<HashMap<String, String>>,
     Extension(course_cache): Extension<Arc<DiskCourseCache>>,
 ) -> Result<impl IntoResponse, AppError> {
-    let all_courses = course_cache.get_all_courses().await;
-    let courses: Vec<Course> = match (params.get("name"), params.get("number")) {
-        (Some(name), None) => {
-            let pattern = name.to_lowercase();
-            all_courses
-                .into_values()
-                .filter(|c| c.name.to_lowercase().contains(&pattern))
-                .collect()
-        }
-        (None, Some(number)) => all_courses
-            .into_values()
-            .filter(|c| c.id.contains(number))
-            .collect(),
-        (Some(_), Some(_)) => return Err(AppError::BadRequest("Invalid query params".into())),
-        (None, None) => return Err(AppError::BadRequest("Missing query params".into())),
+    let text = ["q", "name", "number"]
+        .iter()
+        .filter_map(|key| params.get(*key))
+        .map(String::as_str)
+        .collect::<Vec<_>>()
+        .join(" ");
+    let tags = params
+        .get("tags")
+        .map(|tags| {
+            tags.split(',')
+                .map(|tag| {
+                    Tag::from_str(tag.trim())
+                        .map_err(|_| AppError::BadRequest(format!("Invalid tag {tag}")))
+                })
+                .collect::<Result<Vec<_>, _>>()
+        })
+        .transpose()?
+        .unwrap_or_default();
+    let semester = match (params.get("year"), params.get("semester")) {
+        (Some(year), Some(semester)) => Some(format!("{year}/{semester}")),
+        (None, None) => None,
+        _ => return Err(AppError::BadRequest("Invalid query params".into())),
+    };
+    let query = SearchQuery {
+        text,
+        faculty: params.get("faculty").cloned(),
+        min_credit: numeric_param(&params, "min_credit")?,
+        max_credit: numeric_param(&params, "max_credit")?,
+        tags,
+        semester,
+        offset: numeric_param(&params, "offset")?.unwrap_or_default(),
+        limit: numeric_param(&params, "limit")?
+            .unwrap_or(course_search::DEFAULT_PAGE_SIZE)
+            .min(course_search::MAX_PAGE_SIZE),
     };
-    Ok(Json(courses))
+    if query.is_empty() {
+        return Err(AppError::BadRequest("Missing query params".into()));
+    }
+
+    let results = course_cache.search(&query).await;
+    Ok((
+        [(X_TOTAL_COUNT, results.total.to_string())],
+        Json(results.courses),
+    ))
 }
 
 pub async fn add_courses(
     mut user: User,
     Extension(db): Extension<Db>,
+    headers: HeaderMap,
     data: String,
 ) -> Result<impl IntoResponse, AppError> {
+    let (course_statuses, report) = parser_v2::parse_with_report(&data);
     user.details.degree_status = DegreeStatus::default();
-    user.details.degree_status.course_statuses = parser_v2::parse_copy_paste_data(&data)?;
+    user.details.degree_status.course_statuses = course_statuses?;
+    user.details.parse_report = Some(report);
     user.details.modified = true;
-    let updated_user = db.update::<User>(user).await?;
-    Ok(Json(updated_user))
+    user.save(
+        &db,
+        &[UserField::Details],
+        expected_revision(&headers, &user)?,
+    )
+    .await?;
+    Ok((revision_etag(&user), Json(user)))
 }
 
-// here "modified" becomes false
-pub async fn compute_degree_status(
+// Same as `add_courses`, but from the grade sheet PDF itself rather than text copied out of it.
+pub async fn add_courses_from_pdf(
     mut user: User,
     Extension(db): Extension<Db>,
-    Extension(course_cache): Extension<Arc<DiskCourseCache>>,
+    headers: HeaderMap,
+    pdf: Bytes,
 ) -> Result<impl IntoResponse, AppError> {
-    let display_catalog = user
-        .details
-        .catalog
-        .as_ref()
-        .ok_or_else(|| AppError::InternalServer("No catalog chosen for user".into()))?;
+    let (course_statuses, report) =
+        tokio::task::spawn_blocking(move || transcript_pdf::parse_pdf(&pdf))
+            .await
+            .map_err(|e| AppError::InternalServer(e.to_string()))?;
+    user.details.degree_status = DegreeStatus::default();
+    user.details.degree_status.course_statuses = course_statuses?;
+    user.details.parse_report = Some(report);
+    user.details.modified = true;
+    user.save(
+        &db,
+        &[UserField::Details],
+        expected_revision(&headers, &user)?,
+    )
+    .await?;
+    Ok((revision_etag(&user), Json(user)))
+}
+
+#[derive(Serialize)]
+pub struct ParsePreview {
+    pub course_statuses: Vec<CourseStatus>,
+    pub report: ParseReport,
+    pub error: Option<String>,
+}
 
+// Parses the copy-paste data without saving anything, so the student can see what would be
+// imported and what would be dropped, even when the data can't be parsed at all.
+pub async fn preview_courses(_: User, data: String) -> Result<impl IntoResponse, AppError> {
+    let (course_statuses, report) = parser_v2::parse_with_report(&data);
+    let (course_statuses, error) = match course_statuses {
+        Ok(course_statuses) => (course_statuses, None),
+        Err(e) => (Vec::new(), Some(e.to_string())),
+    };
+    Ok(Json(ParsePreview {
+        course_statuses,
+        report,
+        error,
+    }))
+}
+
+// Fetches the chosen catalog, enriched with the courses of its recent siblings.
+// Also returns which sibling each merged course came from.
+async fn get_catalog_with_siblings(
+    db: &Db,
+    display_catalog: &DisplayCatalog,
+) -> Result<(Catalog, HashMap<CourseId, String>), AppError> {
     let catalog_id = display_catalog.id;
 
     // Extract track name from the display catalog.
@@ -190,7 +402,29 @@ pub async fn compute_degree_status(
         (db.get::<Catalog>(&catalog_id).await?, Vec::new())
     };
 
-    catalog.enrich_with_sibling_courses(&recent_siblings);
+    let sibling_courses = catalog.enrich_with_sibling_courses(&recent_siblings);
+    Ok((catalog, sibling_courses))
+}
+
+// here "modified" becomes false.
+// With `verbose=true` the response also explains why each course landed in its bank.
+pub async fn compute_degree_status(
+    mut user: User,
+    Query(params): Query<HashMap<String, String>>,
+    headers: HeaderMap,
+    Extension(db): Extension<Db>,
+    Extension(course_cache): Extension<Arc<DiskCourseCache>>,
+) -> Result<impl IntoResponse, AppError> {
+    let display_catalog = user
+        .details
+        .catalog
+        .as_ref()
+        .ok_or_else(|| AppError::InternalServer("No catalog chosen for user".into()))?;
+
+    let (catalog, sibling_courses) = get_catalog_with_siblings(&db, display_catalog).await?;
+    let verbose = params
+        .get("verbose")
+        .is_some_and(|verbose| verbose == "true");
 
     user.details.modified = false;
 
@@ -201,34 +435,217 @@ pub async fn compute_degree_status(
         course_list = user.details.degree_status.set_in_progress_to_complete();
     }
 
-    user.details.degree_status.compute(catalog, courses);
+    user.details.degree_status.trace = verbose.then(|| AssignmentTrace::new(sibling_courses));
+    user.details.degree_status.compute(catalog, courses.clone());
+
+    // Minors and second degrees are evaluated against the transcript the primary catalog just used
+    for secondary in user.details.secondary_catalogs.iter_mut() {
+        let (catalog, _) = get_catalog_with_siblings(&db, &secondary.catalog).await?;
+        let (degree_status, shared_courses) = DegreeStatus::compute_secondary(
+            &user.details.degree_status,
+            catalog,
+            courses.clone(),
+            secondary.max_shared_credit,
+        );
+        secondary.degree_status = degree_status;
+        secondary.shared_courses = shared_courses;
+        if user.details.compute_in_progress {
+            secondary
+                .degree_status
+                .set_to_in_progress(course_list.clone());
+        }
+    }
 
     if user.details.compute_in_progress {
         user.details.degree_status.set_to_in_progress(course_list);
     }
-    db.update::<User>(user.clone()).await?;
-    Ok(Json(user))
+    // The trace is only returned, never stored
+    let trace = user.details.degree_status.trace.take();
+    user.save(
+        &db,
+        &[UserField::Details],
+        expected_revision(&headers, &user)?,
+    )
+    .await?;
+    user.details.degree_status.trace = trace;
+    Ok((revision_etag(&user), Json(user)))
+}
+
+// Semester-by-semester credit and progress, in calendar order.
+pub async fn get_degree_timeline(user: User) -> Result<impl IntoResponse, AppError> {
+    let total_credit = user
+        .details
+        .catalog
+        .as_ref()
+        .map(|catalog| catalog.total_credit);
+    Ok(Json(user.details.degree_status.timeline(
+        total_credit,
+        &user.details.timeline_annotations,
+    )))
+}
+
+#[derive(Deserialize)]
+pub struct SimulationPayload {
+    pub planned_courses: Vec<PlannedCourse>,
+}
+
+// Runs the degree computation with hypothetical future courses. Nothing is saved.
+pub async fn simulate_degree_status(
+    user: User,
+    Extension(db): Extension<Db>,
+    Extension(course_cache): Extension<Arc<DiskCourseCache>>,
+    Json(payload): Json<SimulationPayload>,
+) -> Result<impl IntoResponse, AppError> {
+    let display_catalog = user
+        .details
+        .catalog
+        .as_ref()
+        .ok_or_else(|| AppError::InternalServer("No catalog chosen for user".into()))?;
+
+    let courses = course_cache.get_all_courses().await;
+    if let Some(unknown) = payload
+        .planned_courses
+        .iter()
+        .find(|planned| !courses.contains_key(&planned.course_id))
+    {
+        return Err(AppError::NotFound(format!("Course {}", unknown.course_id)));
+    }
+
+    let (catalog, _) = get_catalog_with_siblings(&db, display_catalog).await?;
+
+    let mut degree_status = user.details.degree_status.clone();
+    if user.details.compute_in_progress {
+        degree_status.set_in_progress_to_complete();
+    }
+
+    Ok(Json(degree_status.simulate(
+        &payload.planned_courses,
+        catalog,
+        courses,
+    )))
+}
+
+// Suggests courses that would complete the degree. In-progress courses are assumed to be completed.
+pub async fn get_degree_plan(
+    user: User,
+    Extension(db): Extension<Db>,
+    Extension(course_cache): Extension<Arc<DiskCourseCache>>,
+) -> Result<impl IntoResponse, AppError> {
+    let display_catalog = user
+        .details
+        .catalog
+        .as_ref()
+        .ok_or_else(|| AppError::InternalServer("No catalog chosen for user".into()))?;
+
+    let (catalog, _) = get_catalog_with_siblings(&db, display_catalog).await?;
+    let courses = course_cache.get_all_courses().await;
+
+    let mut degree_status = user.details.degree_status.clone();
+    degree_status.set_in_progress_to_complete();
+
+    Ok(Json(degree_status.plan(catalog, courses)))
+}
+
+#[derive(Serialize)]
+pub struct EligibleCourse {
+    pub course: Course,
+    pub course_bank_name: String,
+}
+
+// Lists the catalog courses offered in the given semester (the latest one on disk by default) whose
+// prerequisites the student meets. In-progress courses are assumed to be completed.
+pub async fn get_eligible_courses(
+    user: User,
+    Query(params): Query<HashMap<String, String>>,
+    Extension(db): Extension<Db>,
+    Extension(course_cache): Extension<Arc<DiskCourseCache>>,
+) -> Result<impl IntoResponse, AppError> {
+    let display_catalog = user
+        .details
+        .catalog
+        .as_ref()
+        .ok_or_else(|| AppError::InternalServer("No catalog chosen for user".into()))?;
+
+    let (year, semester) = match (params.get("year"), params.get("semester")) {
+        (Some(year), Some(semester)) => (year.clone(), semester.clone()),
+        (None, None) => course_cache
+            .discover_semesters()
+            .into_iter()
+            .next()
+            .map(|latest| (latest.year, latest.semester))
+            .ok_or_else(|| AppError::NotFound("Semester".into()))?,
+        _ => return Err(AppError::BadRequest("Invalid query params".into())),
+    };
+
+    let (catalog, _) = get_catalog_with_siblings(&db, display_catalog).await?;
+    let courses = course_cache.get_all_courses().await;
+
+    let mut degree_status = user.details.degree_status.clone();
+    degree_status.set_in_progress_to_complete();
+    let completed = prerequisites::completed_courses(&degree_status.course_statuses);
+
+    let mut eligible = Vec::new();
+    for (course_id, bank_name) in &catalog.course_to_bank {
+        if completed.contains(course_id) {
+            continue;
+        }
+        let Some(details) = course_cache.get_course(&year, &semester, course_id).await else {
+            // Not offered in this semester
+            continue;
+        };
+        let satisfied = match Prerequisite::parse(&details.prerequisites) {
+            Ok(prerequisite) => prerequisite.is_none_or(|p| p.is_satisfied_by(&completed)),
+            Err(e) => {
+                log::warn!("Skipping course {course_id}: {e}");
+                false
+            }
+        };
+        if satisfied {
+            eligible.push(EligibleCourse {
+                course: courses
+                    .get(course_id)
+                    .cloned()
+                    .unwrap_or_else(|| Course::from(details.as_ref())),
+                course_bank_name: bank_name.clone(),
+            });
+        }
+    }
+    eligible.sort_by(|c1, c2| c1.course.id.cmp(&c2.course.id));
+
+    Ok(Json(eligible))
 }
 
 // here "modified" is true
 pub async fn update_details(
     mut user: User,
     Extension(db): Extension<Db>,
+    headers: HeaderMap,
     Json(details): Json<UserDetails>,
 ) -> Result<impl IntoResponse, AppError> {
     user.details = details;
-    db.update::<User>(user).await?;
-    Ok(StatusCode::OK)
+    user.save(
+        &db,
+        &[UserField::Details],
+        expected_revision(&headers, &user)?,
+    )
+    .await?;
+    Ok((StatusCode::OK, revision_etag(&user)))
 }
 
 pub async fn update_settings(
     mut user: User,
     Extension(db): Extension<Db>,
+    headers: HeaderMap,
     Json(settings): Json<UserSettings>,
 ) -> Result<impl IntoResponse, AppError> {
     user.settings = settings;
-    db.update::<User>(user).await?;
-    Ok(StatusCode::OK)
+    user.save(
+        &db,
+        &[UserField::Settings],
+        expected_revision(&headers, &user)?,
+    )
+    .await?;
+    Ok((StatusCode::OK, revision_etag(&user)))
 }
 
 pub async fn get_timetable(user: User) -> Result<impl IntoResponse, AppError> {
@@ -238,9 +655,178 @@ pub async fn get_timetable(user: User) -> Result<impl IntoResponse, AppError> {
 pub async fn update_timetable(
     mut user: User,
     Extension(db): Extension<Db>,
+    headers: HeaderMap,
     Json(timetable): Json<TimetableState>,
 ) -> Result<impl IntoResponse, AppError> {
     user.timetable = timetable;
-    db.update::<User>(user).await?;
+    user.save(
+        &db,
+        &[UserField::Timetable],
+        expected_revision(&headers, &user)?,
+    )
+    .await?;
+    Ok((StatusCode::OK, revision_etag(&user)))
+}
+
+// Loads the details of the given courses in the given semester. Courses that aren't offered are skipped.
+async fn load_semester_courses(
+    course_cache: &DiskCourseCache,
+    semester: &AcademicSemester,
+    course_ids: &[String],
+) -> HashMap<String, Arc<CourseDetails>> {
+    let year = semester.start_year.to_string();
+    let mut courses = HashMap::new();
+    for course_id in course_ids {
+        if let Some(details) = course_cache
+            .get_course(&year, semester.season.sap_code(), course_id)
+            .await
+        {
+            courses.insert(course_id.clone(), details);
+        }
+    }
+    courses
+}
+
+// Checks a (possibly unsaved) draft for clashing time slots and exams which are too close together.
+pub async fn check_timetable_draft(
+    _: User,
+    Extension(course_cache): Extension<Arc<DiskCourseCache>>,
+    Json(draft): Json<TimetableDraft>,
+) -> Result<impl IntoResponse, AppError> {
+    let course_ids = draft
+        .courses
+        .iter()
+        .map(|selection| selection.course_id.clone())
+        .collect::<Vec<_>>();
+    let courses = load_semester_courses(&course_cache, &draft.semester, &course_ids).await;
+    Ok(Json(timetable::check_draft(&draft, &courses)))
+}
+
+#[derive(Deserialize)]
+pub struct GenerateTimetablePayload {
+    pub semester: AcademicSemester,
+    pub course_ids: Vec<String>,
+    #[serde(default)]
+    pub preferences: TimetablePreferences,
+    pub limit: Option<usize>,
+}
+
+// Suggests clash-free timetables for the given courses, best first. Nothing is saved.
+pub async fn generate_timetables(
+    _: User,
+    Extension(course_cache): Extension<Arc<DiskCourseCache>>,
+    Json(mut payload): Json<GenerateTimetablePayload>,
+) -> Result<impl IntoResponse, AppError> {
+    // A course asked for twice is taken once, keeping the order of the request
+    let mut seen = HashSet::new();
+    payload
+        .course_ids
+        .retain(|course_id| seen.insert(course_id.clone()));
+    if payload.course_ids.len() > generator::MAX_COURSES {
+        return Err(AppError::BadRequest(format!(
+            "Timetables can be generated for up to {} courses",
+            generator::MAX_COURSES
+        )));
+    }
+    let mut loaded =
+        load_semester_courses(&course_cache, &payload.semester, &payload.course_ids).await;
+    let courses = payload
+        .course_ids
+        .iter()
+        .map(|course_id| {
+            loaded
+                .remove(course_id)
+                .ok_or_else(|| AppError::NotFound(format!("Course {course_id}")))
+        })
+        .collect::<Result<Vec<_>, _>>()?;
+
+    let drafts = tokio::task::spawn_blocking(move || {
+        generator::generate(
+            &payload.semester,
+            &courses,
+            &payload.preferences,
+            payload.limit.unwrap_or(generator::DEFAULT_LIMIT),
+        )
+    })
+    .await
+    .map_err(|e| AppError::InternalServer(e.to_string()))?;
+    Ok(Json(drafts))
+}
+
+// Exports a saved draft as an iCalendar file, to be imported into any calendar app.
+pub async fn export_timetable_ics(
+    user: User,
+    Extension(course_cache): Extension<Arc<DiskCourseCache>>,
+    Path(draft_id): Path<String>,
+) -> Result<impl IntoResponse, AppError> {
+    let draft = user
+        .timetable
+        .drafts
+        .into_iter()
+        .find(|draft| draft.id == draft_id)
+        .ok_or_else(|| AppError::NotFound("Draft".into()))?;
+    let semester = course_cache
+        .get_semester(
+            &draft.semester.start_year.to_string(),
+            draft.semester.season.sap_code(),
+        )
+        .ok_or_else(|| AppError::NotFound("Semester dates".into()))?;
+    let course_ids = draft
+        .courses
+        .iter()
+        .map(|selection| selection.course_id.clone())
+        .collect::<Vec<_>>();
+    let courses = load_semester_courses(&course_cache, &draft.semester, &course_ids).await;
+    let calendar = ics::render(&draft, &courses, &semester, chrono::Utc::now())?;
+
+    Ok((
+        [
+            (
+                header::CONTENT_TYPE,
+                "text/calendar; charset=utf-8".to_string(),
+            ),
+            (
+                header::CONTENT_DISPOSITION,
+                format!("attachment; filename=\"timetable-{draft_id}.ics\""),
+            ),
+        ],
+        calendar,
+    ))
+}
+
+#[derive(Serialize)]
+pub struct NotificationsResponse {
+    pub unread: usize,
+    pub notifications: Vec<Notification>,
+}
+
+// The course changes affecting the student's published timetables, newest first.
+pub async fn get_notifications(user: User) -> Result<impl IntoResponse, AppError> {
+    Ok(Json(NotificationsResponse {
+        unread: user
+            .notifications
+            .iter()
+            .filter(|notification| !notification.read)
+            .count(),
+        notifications: user.notifications,
+    }))
+}
+
+pub async fn acknowledge_notification(
+    user: User,
+    Extension(db): Extension<Db>,
+    Path(notification_id): Path<String>,
+) -> Result<impl IntoResponse, AppError> {
+    if !user.mark_read(&db, Some(&notification_id)).await? {
+        return Err(AppError::NotFound("Notification".into()));
+    }
+    Ok(StatusCode::OK)
+}
+
+pub async fn acknowledge_all_notifications(
+    user: User,
+    Extension(db): Extension<Db>,
+) -> Result<impl IntoResponse, AppError> {
+    user.mark_read(&db, None).await?;
     Ok(StatusCode::OK)
 }
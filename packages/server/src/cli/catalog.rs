@@ -1,9 +1,13 @@
+use std::collections::HashSet;
 use std::path::Path;
 
-use crate::db::Db;
-use crate::resources::catalog::{Catalog, Faculty};
-use bson::{doc, oid::ObjectId};
-use futures_util::TryStreamExt;
+use crate::core::catalog_diff::CatalogDiff;
+use crate::core::catalog_validations::{unknown_courses, validate_catalog};
+use crate::db::{Db, FilterOption};
+use crate::resources::catalog::Catalog;
+use crate::resources::course::CourseId;
+use crate::sap::CourseIndexEntry;
+use bson::oid::ObjectId;
 
 /// Convert MongoDB Extended JSON patterns to plain JSON values.
 /// Handles `{"$numberLong": "123"}` → `123` recursively.
@@ -31,36 +35,40 @@ fn normalize_extended_json(value: &mut serde_json::Value) {
     }
 }
 
+fn read_catalog_file(path: &Path) -> Result<Catalog, anyhow::Error> {
+    let text = std::fs::read_to_string(path)
+        .map_err(|e| anyhow::anyhow!("Failed to read {}: {e}", path.display()))?;
+
+    // Parse JSON and normalize MongoDB Extended JSON patterns ($numberLong, $oid)
+    let mut value: serde_json::Value = serde_json::from_str(&text)
+        .map_err(|e| anyhow::anyhow!("Failed to parse {}: {e}", path.display()))?;
+    if let Some(obj) = value.as_object_mut() {
+        obj.remove("_id"); // Strip placeholder _id — the caller sets the real one
+    }
+    normalize_extended_json(&mut value);
+    serde_json::from_value(value)
+        .map_err(|e| anyhow::anyhow!("Failed to deserialize {}: {e}", path.display()))
+}
+
 pub async fn upsert(db: &Db, files: &[impl AsRef<Path>]) -> Result<(), anyhow::Error> {
     for path in files {
-        let path = path.as_ref();
-        let text = std::fs::read_to_string(path)
-            .map_err(|e| anyhow::anyhow!("Failed to read {}: {e}", path.display()))?;
-
-        // Parse JSON and normalize MongoDB Extended JSON patterns ($numberLong, $oid)
-        let mut value: serde_json::Value = serde_json::from_str(&text)
-            .map_err(|e| anyhow::anyhow!("Failed to parse {}: {e}", path.display()))?;
-        if let Some(obj) = value.as_object_mut() {
-            obj.remove("_id"); // Strip placeholder _id — we set the real one below
-        }
-        normalize_extended_json(&mut value);
-        let mut catalog: Catalog = serde_json::from_value(value)
-            .map_err(|e| anyhow::anyhow!("Failed to deserialize {}: {e}", path.display()))?;
+        let mut catalog = read_catalog_file(path.as_ref())?;
 
-        let collection = db.collection::<Catalog>();
-        let existing = collection
-            .find_one(doc! {"name": &catalog.name})
+        let existing = db
+            .get_filtered::<Catalog>(FilterOption::In, "name", vec![catalog.name.clone()])
             .await
-            .map_err(|e| anyhow::anyhow!("MongoDB query failed: {e}"))?;
+            .map_err(|e| anyhow::anyhow!("Query failed: {e}"))?
+            .into_iter()
+            .next();
 
         let is_update = existing.is_some();
         catalog.id = existing.map(|c| c.id).unwrap_or_else(ObjectId::new);
 
-        collection
-            .replace_one(doc! {"_id": catalog.id}, &catalog)
-            .upsert(true)
+        // Every field of a catalog is serialized, so setting them all replaces the old one
+        let catalog = db
+            .update::<Catalog>(catalog)
             .await
-            .map_err(|e| anyhow::anyhow!("MongoDB upsert failed: {e}"))?;
+            .map_err(|e| anyhow::anyhow!("Upsert failed: {e}"))?;
 
         let verb = if is_update { "Updated" } else { "Created" };
         eprintln!("  {verb} \"{}\" ({})", catalog.name, catalog.id);
@@ -68,31 +76,39 @@ pub async fn upsert(db: &Db, files: &[impl AsRef<Path>]) -> Result<(), anyhow::E
     Ok(())
 }
 
-/// Lightweight struct for listing catalogs (avoids deserializing all fields).
-#[derive(serde::Deserialize)]
-struct CatalogSummary {
-    #[serde(rename = "_id")]
-    id: ObjectId,
-    name: String,
-    #[serde(default)]
-    faculty: Faculty,
-    #[serde(default)]
-    total_credit: f64,
+/// Stores every catalog file in `dir`, keeping the ids of the ones exported from the database.
+/// Seeds the embedded database the tests use without SOGRIM_URI.
+pub async fn seed(db: &Db, dir: &Path) -> Result<usize, anyhow::Error> {
+    let mut seeded = 0;
+    for entry in std::fs::read_dir(dir)? {
+        let path = entry?.path();
+        if path.extension().and_then(|e| e.to_str()) != Some("json") {
+            continue;
+        }
+        let value: serde_json::Value = serde_json::from_str(&std::fs::read_to_string(&path)?)?;
+        if value.get("course_banks").is_none() {
+            continue; // not a catalog file
+        }
+        let mut catalog = read_catalog_file(&path)?;
+        catalog.id = value
+            .pointer("/_id/$oid")
+            .and_then(|id| id.as_str())
+            .and_then(|id| ObjectId::parse_str(id).ok())
+            .unwrap_or_default();
+        db.update::<Catalog>(catalog)
+            .await
+            .map_err(|e| anyhow::anyhow!("Upsert failed: {e}"))?;
+        seeded += 1;
+    }
+    Ok(seeded)
 }
 
 pub async fn list(db: &Db) -> Result<(), anyhow::Error> {
-    let catalogs: Vec<CatalogSummary> = db
-        .client()
-        .database(db.profile())
-        .collection::<CatalogSummary>("Catalogs")
-        .find(doc! {})
-        .projection(doc! {"name": 1, "faculty": 1, "total_credit": 1})
-        .sort(doc! {"name": 1})
+    let mut catalogs = db
+        .get_all::<Catalog>()
         .await
-        .map_err(|e| anyhow::anyhow!("MongoDB query failed: {e}"))?
-        .try_collect()
-        .await
-        .map_err(|e| anyhow::anyhow!("MongoDB cursor failed: {e}"))?;
+        .map_err(|e| anyhow::anyhow!("Query failed: {e}"))?;
+    catalogs.sort_by(|a, b| a.name.cmp(&b.name));
 
     if catalogs.is_empty() {
         println!("No catalogs found.");
@@ -121,26 +137,112 @@ pub async fn delete(db: &Db, id_hex: &str) -> Result<(), anyhow::Error> {
     let oid =
         ObjectId::parse_str(id_hex).map_err(|_| anyhow::anyhow!("Invalid ObjectId: {id_hex}"))?;
 
-    let result = db
-        .collection::<Catalog>()
-        .delete_one(doc! {"_id": oid})
+    let deleted = db
+        .delete_one::<Catalog>(oid)
         .await
-        .map_err(|e| anyhow::anyhow!("MongoDB delete failed: {e}"))?;
+        .map_err(|e| anyhow::anyhow!("Delete failed: {e}"))?;
 
-    if result.deleted_count == 0 {
+    if !deleted {
         anyhow::bail!("No catalog found with id {id_hex}");
     }
     eprintln!("Deleted catalog {id_hex}");
     Ok(())
 }
 
-pub async fn download(db: &Db, name: &str, output: &Path) -> Result<(), anyhow::Error> {
-    let catalog: Catalog = db
-        .collection::<Catalog>()
-        .find_one(doc! {"name": name})
+async fn find_by_name(db: &Db, name: &str) -> Result<Catalog, anyhow::Error> {
+    db.get_filtered::<Catalog>(FilterOption::In, "name", vec![name])
         .await
-        .map_err(|e| anyhow::anyhow!("MongoDB query failed: {e}"))?
-        .ok_or_else(|| anyhow::anyhow!("Catalog '{name}' not found"))?;
+        .map_err(|e| anyhow::anyhow!("Query failed: {e}"))?
+        .into_iter()
+        .next()
+        .ok_or_else(|| anyhow::anyhow!("Catalog '{name}' not found"))
+}
+
+/// A catalog in the database by name, or a local JSON file (e.g. one about to be upserted).
+async fn load(db: &Db, source: &str) -> Result<Catalog, anyhow::Error> {
+    let path = Path::new(source);
+    if path.is_file() {
+        read_catalog_file(path)
+    } else {
+        find_by_name(db, source).await
+    }
+}
+
+pub async fn diff(db: &Db, from: &str, to: &str) -> Result<(), anyhow::Error> {
+    let from = load(db, from).await?;
+    let to = load(db, to).await?;
+    if from.track_name() != to.track_name() {
+        eprintln!(
+            "Warning: comparing different tracks (\"{}\" and \"{}\")",
+            from.track_name(),
+            to.track_name()
+        );
+    }
+    print!("{}", CatalogDiff::between(&from, &to));
+    Ok(())
+}
+
+/// Course ids listed in the index of every semester in the fetcher's cache directory.
+fn cached_course_ids(cache_dir: &Path) -> Result<HashSet<CourseId>, anyhow::Error> {
+    let mut course_ids = HashSet::new();
+    let years = std::fs::read_dir(cache_dir)
+        .map_err(|e| anyhow::anyhow!("Failed to read {}: {e}", cache_dir.display()))?;
+    for year in years.flatten() {
+        if !year.path().is_dir() || year.file_name().to_string_lossy().starts_with('_') {
+            continue;
+        }
+        let Ok(semesters) = std::fs::read_dir(year.path()) else {
+            continue;
+        };
+        for semester in semesters.flatten() {
+            let Ok(data) = std::fs::read_to_string(semester.path().join("_index.json")) else {
+                continue;
+            };
+            let index: Vec<CourseIndexEntry> = serde_json::from_str(&data).map_err(|e| {
+                anyhow::anyhow!("Failed to parse {}: {e}", semester.path().display())
+            })?;
+            course_ids.extend(index.into_iter().map(|entry| entry.id));
+        }
+    }
+    Ok(course_ids)
+}
+
+pub async fn validate(
+    db: &Db,
+    sources: &[String],
+    cache_dir: Option<&Path>,
+) -> Result<(), anyhow::Error> {
+    let known_courses = cache_dir.map(cached_course_ids).transpose()?;
+    let mut invalid = 0;
+    for source in sources {
+        let catalog = load(db, source).await?;
+        if let Some(known_courses) = &known_courses {
+            for course_id in unknown_courses(&catalog, known_courses) {
+                eprintln!(
+                    "  Warning: course {course_id} of \"{}\" is not in the course cache",
+                    catalog.name
+                );
+            }
+        }
+        match validate_catalog(&catalog, known_courses.as_ref()) {
+            Ok(()) => eprintln!("  OK \"{}\"", catalog.name),
+            Err(errors) => {
+                invalid += 1;
+                eprintln!("  {} error(s) in \"{}\":", errors.len(), catalog.name);
+                for error in errors {
+                    eprintln!("    - {error}");
+                }
+            }
+        }
+    }
+    if invalid > 0 {
+        anyhow::bail!("{invalid} of {} catalog(s) are invalid", sources.len());
+    }
+    Ok(())
+}
+
+pub async fn download(db: &Db, name: &str, output: &Path) -> Result<(), anyhow::Error> {
+    let catalog = find_by_name(db, name).await?;
 
     let json = serde_json::to_string_pretty(&catalog)?;
     std::fs::write(output, &json)?;
@@ -153,9 +255,9 @@ mod tests {
     use super::*;
 
     // Every catalog JSON under docs/ must deserialize into `Catalog` through the same
-    // extended-JSON normalization the CLI uses to upsert them.
+    // extended-JSON normalization the CLI uses to upsert them, and be valid.
     #[test]
-    fn all_catalog_docs_deserialize() {
+    fn all_catalog_docs_deserialize_and_validate() {
         let mut checked = 0;
         for entry in std::fs::read_dir("../docs").expect("read ../docs") {
             let path = entry.unwrap().path();
@@ -181,6 +283,13 @@ mod tests {
                 path.display(),
                 result.err()
             );
+            // What `sogrim catalog validate` checks without a course cache
+            let errors = validate_catalog(&result.unwrap(), None).err();
+            assert!(
+                errors.is_none(),
+                "{} is invalid: {errors:?}",
+                path.display()
+            );
             checked += 1;
         }
         assert!(checked > 0, "no catalog files were checked");
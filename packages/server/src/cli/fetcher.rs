@@ -5,12 +5,13 @@ use std::sync::atomic::{AtomicUsize, Ordering};
 use std::sync::Arc;
 use std::time::{Duration, Instant};
 
-use chrono::Local;
+use chrono::{Local, Utc};
 use env_logger::Builder;
 use log::LevelFilter;
 
 use crate::resources::course::CourseId;
-use crate::sap::{CachedSapClient, CourseIndexEntry};
+use crate::sap::changes::{Changelog, CourseChange, SemesterChanges, CHANGES_DIR};
+use crate::sap::{CachedSapClient, CourseDetails, CourseIndexEntry, CourseSource, Semester};
 
 #[derive(Clone, clap::ValueEnum)]
 enum Season {
@@ -72,6 +73,11 @@ pub struct FetcherArgs {
     /// and write them to disk. Fails hard if any course cannot be fetched.
     #[arg(long, conflicts_with_all = &["latest", "year", "semester"])]
     repair: bool,
+
+    /// Incremental mode: only rewrite courses that changed since the last fetch, delete courses
+    /// SAP no longer lists, and record the changes under `_changes/` in the cache directory.
+    #[arg(long, conflicts_with = "repair")]
+    incremental: bool,
 }
 
 // ---------------------------------------------------------------------------
@@ -267,6 +273,46 @@ fn atomic_write(path: &Path, data: &[u8]) -> io::Result<()> {
     Ok(())
 }
 
+/// Like `atomic_write`, but leaves the file alone if it already holds `data`.
+/// Returns whether the file was written.
+fn write_if_changed(path: &Path, data: &[u8]) -> io::Result<bool> {
+    if fs::read(path).is_ok_and(|current| current == data) {
+        return Ok(false);
+    }
+    atomic_write(path, data).map(|_| true)
+}
+
+// An incremental fetch deletes the courses SAP stops listing, unless its index drops more than
+// both of these at once. That's more likely a bad SAP response than courses being withdrawn.
+const MAX_DROPPED_COURSES: usize = 20;
+const MAX_DROPPED_SHARE: f64 = 0.1;
+
+/// Whether a fresh index of `current` courses is suspiciously short of the `previous` one on disk.
+fn index_shrank_sharply(previous: usize, current: usize) -> bool {
+    let dropped = previous.saturating_sub(current);
+    current == 0
+        || (dropped > MAX_DROPPED_COURSES && dropped as f64 > previous as f64 * MAX_DROPPED_SHARE)
+}
+
+/// Course files in a semester directory which the index no longer lists, as (id, path).
+fn unlisted_courses(sem_dir: &Path, index: &[CourseIndexEntry]) -> Vec<(CourseId, PathBuf)> {
+    let listed: std::collections::HashSet<&str> = index.iter().map(|e| &*e.id).collect();
+    let Ok(entries) = fs::read_dir(sem_dir) else {
+        return Vec::new();
+    };
+    let mut unlisted: Vec<(CourseId, PathBuf)> = entries
+        .flatten()
+        .filter_map(|entry| {
+            let name = entry.file_name().to_string_lossy().to_string();
+            let course_id = name.strip_suffix(".json")?;
+            (!name.starts_with('_') && !listed.contains(course_id))
+                .then(|| (CourseId::new(course_id), entry.path()))
+        })
+        .collect();
+    unlisted.sort();
+    unlisted
+}
+
 // ---------------------------------------------------------------------------
 // Repair
 // ---------------------------------------------------------------------------
@@ -278,11 +324,11 @@ struct MissingCourse {
     sem_dir: PathBuf,
 }
 
-fn scan_missing(cache_dir: &Path) -> Vec<MissingCourse> {
-    let mut missing = Vec::new();
-
+// The (year, semester, directory) of every semester in the cache.
+fn semester_dirs(cache_dir: &Path) -> Vec<(String, String, PathBuf)> {
+    let mut dirs = Vec::new();
     let Ok(years) = fs::read_dir(cache_dir) else {
-        return missing;
+        return dirs;
     };
 
     for year_entry in years.flatten() {
@@ -301,25 +347,33 @@ fn scan_missing(cache_dir: &Path) -> Vec<MissingCourse> {
             if !sem_dir.is_dir() || semester.starts_with('_') {
                 continue;
             }
+            dirs.push((year.clone(), semester, sem_dir));
+        }
+    }
+    dirs
+}
 
-            let index_path = sem_dir.join("_index.json");
-            let Ok(data) = fs::read_to_string(&index_path) else {
-                continue;
-            };
-            let Ok(index) = serde_json::from_str::<Vec<CourseIndexEntry>>(&data) else {
-                continue;
-            };
+fn scan_missing(cache_dir: &Path) -> Vec<MissingCourse> {
+    let mut missing = Vec::new();
 
-            for entry in &index {
-                let course_file = sem_dir.join(format!("{}.json", entry.id));
-                if !course_file.exists() {
-                    missing.push(MissingCourse {
-                        year: year.clone(),
-                        semester: semester.clone(),
-                        course_id: entry.id.clone(),
-                        sem_dir: sem_dir.clone(),
-                    });
-                }
+    for (year, semester, sem_dir) in semester_dirs(cache_dir) {
+        let index_path = sem_dir.join("_index.json");
+        let Ok(data) = fs::read_to_string(&index_path) else {
+            continue;
+        };
+        let Ok(index) = serde_json::from_str::<Vec<CourseIndexEntry>>(&data) else {
+            continue;
+        };
+
+        for entry in &index {
+            let course_file = sem_dir.join(format!("{}.json", entry.id));
+            if !course_file.exists() {
+                missing.push(MissingCourse {
+                    year: year.clone(),
+                    semester: semester.clone(),
+                    course_id: entry.id.clone(),
+                    sem_dir: sem_dir.clone(),
+                });
             }
         }
     }
@@ -331,10 +385,52 @@ fn scan_missing(cache_dir: &Path) -> Vec<MissingCourse> {
     missing
 }
 
-async fn run_repair(args: &FetcherArgs) {
+/// Records the semester dates, which the server uses to bound recurring calendar events.
+fn write_semester_dates(sem_dir: &Path, semester: &Semester) {
+    let semester_json =
+        serde_json::to_string_pretty(semester).expect("failed to serialize semester");
+    let _ = write_if_changed(&sem_dir.join("_semester.json"), semester_json.as_bytes());
+}
+
+// Semesters fetched before their dates were recorded, or by an interrupted fetch, have none.
+async fn repair_semester_dates<S: CourseSource>(cache_dir: &Path, client: &S) {
+    let undated = semester_dirs(cache_dir)
+        .into_iter()
+        .filter(|(_, _, sem_dir)| !sem_dir.join("_semester.json").exists())
+        .collect::<Vec<_>>();
+    if undated.is_empty() {
+        return;
+    }
+    let semesters = match client.get_semesters().await {
+        Ok(semesters) => semesters,
+        Err(e) => {
+            log::warn!(target: "sogrim_server", "Repair: failed to fetch the semester dates: {e}");
+            return;
+        }
+    };
+    for (year, semester, sem_dir) in undated {
+        let label = semester_display(&year, &semester);
+        match semesters
+            .iter()
+            .find(|s| s.year == year && s.semester == semester)
+        {
+            Some(dates) => {
+                write_semester_dates(&sem_dir, dates);
+                log::info!(target: "sogrim_server", "Repair: [{label}] wrote the semester dates");
+            }
+            None => {
+                log::warn!(target: "sogrim_server", "Repair: [{label}] SAP has no dates for it")
+            }
+        }
+    }
+}
+
+async fn run_repair<S: CourseSource>(args: &FetcherArgs, client: Arc<S>) {
     let interactive = is_interactive();
     let started = Instant::now();
 
+    repair_semester_dates(&args.cache_dir, &*client).await;
+
     // Phase 1: Scan for missing courses
     print_spinner(interactive, "scanning cache for missing courses");
     let missing = scan_missing(&args.cache_dir);
@@ -379,7 +475,6 @@ async fn run_repair(args: &FetcherArgs) {
 
     // Phase 2: Warm up proxy if needed
     let use_proxy = args.proxy_url.is_some();
-    let client = Arc::new(CachedSapClient::with_proxy_url(args.proxy_url.clone()));
 
     if use_proxy {
         print_spinner(interactive, "warming up proxy");
@@ -570,20 +665,26 @@ pub async fn run(args: FetcherArgs) {
         .ok();
     }
 
+    let client = Arc::new(CachedSapClient::with_proxy_url(args.proxy_url.clone()));
+
     // Repair mode: scan + fetch missing courses, then exit
     if args.repair {
-        run_repair(&args).await;
+        run_repair(&args, client).await;
         if interactive {
             show_cursor();
         }
         return;
     }
 
+    run_fetch(&args, client).await;
+}
+
+async fn run_fetch<S: CourseSource>(args: &FetcherArgs, client: Arc<S>) {
+    let interactive = is_interactive();
     let started = Instant::now();
     log::info!(target: "sogrim_server", "sogrim-fetcher starting, cache_dir={}", args.cache_dir.display());
 
     let use_proxy = args.proxy_url.is_some();
-    let client = Arc::new(CachedSapClient::with_proxy_url(args.proxy_url.clone()));
 
     // Warm up the proxy (if used) with a lightweight request before doing real work.
     if use_proxy {
@@ -699,6 +800,8 @@ pub async fn run(args: FetcherArgs) {
         sem_dir: PathBuf,
         course_ids: Vec<CourseId>,
         index: Vec<CourseIndexEntry>,
+        // The index lost too many courses to trust it with deleting them, see `index_shrank_sharply`
+        index_shrank: bool,
     }
     let mut work: Vec<SemesterWork> = Vec::new();
     let mut total_to_fetch = 0usize;
@@ -722,8 +825,32 @@ pub async fn run(args: FetcherArgs) {
                 continue;
             }
         };
-        let index_json = serde_json::to_string_pretty(&*index).expect("failed to serialize index");
-        let _ = atomic_write(&sem_dir.join("_index.json"), index_json.as_bytes());
+        let previous_len = fs::read_to_string(sem_dir.join("_index.json"))
+            .ok()
+            .and_then(|data| serde_json::from_str::<Vec<CourseIndexEntry>>(&data).ok())
+            .map_or(0, |previous| previous.len());
+        let index_shrank = args.incremental && index_shrank_sharply(previous_len, index.len());
+        if index_shrank {
+            log::error!(
+                target: "sogrim_server",
+                "[{label}] SAP lists {} courses, down from {previous_len}: keeping the index and every \
+                 course file. If SAP really dropped them, run a full fetch",
+                index.len()
+            );
+        }
+        // Incremental runs write the index once the courses are on disk, see phase 3
+        if !args.incremental {
+            let index_json =
+                serde_json::to_string_pretty(&*index).expect("failed to serialize index");
+            let _ = atomic_write(&sem_dir.join("_index.json"), index_json.as_bytes());
+        }
+        if let Some(semester) = client.get_semesters().await.ok().and_then(|all| {
+            all.iter()
+                .find(|s| s.year == target.year && s.semester == target.semester)
+                .cloned()
+        }) {
+            write_semester_dates(&sem_dir, &semester);
+        }
         let course_ids: Vec<CourseId> = index.iter().map(|entry| entry.id.clone()).collect();
         print_spinner_done(
             interactive,
@@ -740,6 +867,7 @@ pub async fn run(args: FetcherArgs) {
             sem_dir,
             course_ids,
             index: index_vec,
+            index_shrank,
         });
     }
 
@@ -753,8 +881,7 @@ pub async fn run(args: FetcherArgs) {
         let year = w.year.clone();
         let sem = w.semester.clone();
         handles.push(tokio::spawn(async move {
-            c.fetch_semester_with_concurrency(&year, &sem, concurrency)
-                .await;
+            c.prefetch_semester(&year, &sem, concurrency).await;
         }));
     }
 
@@ -788,11 +915,17 @@ pub async fn run(args: FetcherArgs) {
     let disk_counter = disk_bar.counter();
     let mut total_courses = 0usize;
     let mut total_errors = 0usize;
+    let mut changelog = Changelog {
+        fetched_at: Utc::now().to_rfc3339(),
+        semesters: Vec::new(),
+    };
 
     for w in &mut work {
         let mut written = 0usize;
+        let mut unchanged = 0usize;
         let mut errors = 0usize;
         let mut failed_ids: Vec<CourseId> = Vec::new();
+        let mut changes: Vec<CourseChange> = Vec::new();
         for course_id in &w.course_ids {
             match client
                 .get_course_details(&w.year, &w.semester, course_id)
@@ -802,8 +935,33 @@ pub async fn run(args: FetcherArgs) {
                     let json = serde_json::to_string_pretty(&*details)
                         .expect("failed to serialize course");
                     let file_path = w.sem_dir.join(format!("{course_id}.json"));
+                    let on_disk = if args.incremental {
+                        fs::read_to_string(&file_path).ok()
+                    } else {
+                        None
+                    };
+                    if on_disk.as_deref() == Some(json.as_str()) {
+                        unchanged += 1;
+                        disk_counter.fetch_add(1, Ordering::Relaxed);
+                        disk_bar.draw();
+                        continue;
+                    }
                     match atomic_write(&file_path, json.as_bytes()) {
-                        Ok(()) => written += 1,
+                        Ok(()) => {
+                            written += 1;
+                            if args.incremental {
+                                let previous = on_disk.and_then(|data| {
+                                    serde_json::from_str::<CourseDetails>(&data).ok()
+                                });
+                                changes.extend(match previous {
+                                    Some(previous) => CourseChange::between(&previous, &details),
+                                    None => Some(CourseChange::Added {
+                                        course_id: course_id.clone(),
+                                        name: details.name.clone(),
+                                    }),
+                                });
+                            }
+                        }
                         Err(e) => {
                             log::error!(target: "sogrim_server", "Failed to write {course_id}.json: {e}");
                             failed_ids.push(course_id.clone());
@@ -823,7 +981,7 @@ pub async fn run(args: FetcherArgs) {
         disk_bar.draw();
 
         // Rebuild index excluding failed courses
-        if !failed_ids.is_empty() {
+        if (!failed_ids.is_empty() || args.incremental) && !w.index_shrank {
             let failed_set: std::collections::HashSet<&CourseId> = failed_ids.iter().collect();
             w.course_ids.retain(|id| !failed_set.contains(id));
             let filtered_index: Vec<&CourseIndexEntry> = w
@@ -833,13 +991,50 @@ pub async fn run(args: FetcherArgs) {
                 .collect();
             let index_json =
                 serde_json::to_string_pretty(&filtered_index).expect("failed to serialize index");
-            let _ = atomic_write(&w.sem_dir.join("_index.json"), index_json.as_bytes());
+            let _ = write_if_changed(&w.sem_dir.join("_index.json"), index_json.as_bytes());
+            if !failed_ids.is_empty() {
+                log::info!(
+                    target: "sogrim_server",
+                    "[{}] rebuilt index: removed {} phantom courses",
+                    w.label,
+                    failed_ids.len()
+                );
+            }
+        }
+
+        // Courses SAP no longer offers this semester. Courses which merely failed to fetch are
+        // still in the index, and keep their previous file.
+        if args.incremental {
+            let unlisted = if w.index_shrank {
+                Vec::new()
+            } else {
+                unlisted_courses(&w.sem_dir, &w.index)
+            };
+            for (course_id, path) in unlisted {
+                let name = fs::read_to_string(&path)
+                    .ok()
+                    .and_then(|data| serde_json::from_str::<CourseDetails>(&data).ok())
+                    .map(|details| details.name)
+                    .unwrap_or_default();
+                match fs::remove_file(&path) {
+                    Ok(()) => changes.push(CourseChange::Removed { course_id, name }),
+                    Err(e) => {
+                        log::error!(target: "sogrim_server", "Failed to remove {course_id}.json: {e}");
+                        errors += 1;
+                    }
+                }
+            }
             log::info!(
                 target: "sogrim_server",
-                "[{}] rebuilt index: removed {} phantom courses",
+                "[{}] {} changes, {unchanged} courses unchanged",
                 w.label,
-                failed_ids.len()
+                changes.len()
             );
+            changelog.semesters.push(SemesterChanges {
+                year: w.year.clone(),
+                semester: w.semester.clone(),
+                changes,
+            });
         }
 
         log::info!(target: "sogrim_server", "[{}] done: {written} written, {errors} errors", w.label);
@@ -848,6 +1043,21 @@ pub async fn run(args: FetcherArgs) {
     }
     disk_bar.finish();
 
+    if !changelog.is_empty() {
+        let changes_dir = args.cache_dir.join(CHANGES_DIR);
+        let path = changes_dir.join(format!("{}.json", Utc::now().format("%Y%m%dT%H%M%S%3fZ")));
+        let changelog_json =
+            serde_json::to_string_pretty(&changelog).expect("failed to serialize changelog");
+        match fs::create_dir_all(&changes_dir)
+            .and_then(|_| atomic_write(&path, changelog_json.as_bytes()))
+        {
+            Ok(()) => log::info!(target: "sogrim_server", "Wrote changelog {}", path.display()),
+            Err(e) => {
+                log::error!(target: "sogrim_server", "Failed to write changelog {}: {e}", path.display())
+            }
+        }
+    }
+
     let elapsed = started.elapsed();
     let (sent, received) = client.transfer_stats();
     log::info!(
@@ -869,3 +1079,7 @@ pub async fn run(args: FetcherArgs) {
         show_cursor();
     }
 }
+
+#[cfg(test)]
+#[path = "fetcher_tests.rs"]
+mod fetcher_tests;
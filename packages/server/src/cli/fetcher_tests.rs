@@ -0,0 +1,185 @@
+use std::collections::BTreeMap;
+
+use serde_json::Value;
+
+use crate::sap::changes::FieldChange;
+use crate::sap::mock::{MockSap, FIXTURE_PATH};
+
+use super::*;
+
+fn args(cache_dir: PathBuf) -> FetcherArgs {
+    FetcherArgs {
+        latest: Some(1),
+        year: None,
+        semester: Vec::new(),
+        cache_dir,
+        proxy_url: None,
+        concurrency: 4,
+        repair: false,
+        incremental: false,
+    }
+}
+
+#[tokio::test]
+async fn fetches_and_repairs_the_cache() {
+    let cache_dir = std::env::temp_dir().join(format!("sogrim_fetcher_{}", std::process::id()));
+    let _ = fs::remove_dir_all(&cache_dir);
+    let sap = MockSap::start().await;
+    let sem_dir = cache_dir.join("2025").join("200");
+
+    run_fetch(&args(cache_dir.clone()), Arc::new(sap.client())).await;
+
+    let semester: crate::sap::Semester =
+        serde_json::from_str(&fs::read_to_string(sem_dir.join("_semester.json")).unwrap()).unwrap();
+    assert_eq!(semester.end_date, "30-01-2026");
+    let course: crate::sap::CourseDetails =
+        serde_json::from_str(&fs::read_to_string(sem_dir.join("02340114.json")).unwrap()).unwrap();
+    assert_eq!(course.schedule.len(), 2);
+    // SAP has no details for the third course of the index, so it's dropped from the index
+    let index: Vec<CourseIndexEntry> =
+        serde_json::from_str(&fs::read_to_string(sem_dir.join("_index.json")).unwrap()).unwrap();
+    let ids = index
+        .iter()
+        .map(|entry| entry.id.to_string())
+        .collect::<Vec<_>>();
+    assert_eq!(ids, vec!["02340114", "01040031"]);
+    assert!(!sem_dir.join("00000000.json").exists());
+
+    fs::remove_file(sem_dir.join("01040031.json")).unwrap();
+    // As in a cache fetched before the semester dates were recorded
+    fs::remove_file(sem_dir.join("_semester.json")).unwrap();
+    let repair = FetcherArgs {
+        latest: None,
+        repair: true,
+        ..args(cache_dir.clone())
+    };
+    run_repair(&repair, Arc::new(sap.client())).await;
+    assert!(sem_dir.join("01040031.json").exists());
+    assert!(sem_dir.join("_semester.json").exists());
+
+    fs::remove_dir_all(&cache_dir).unwrap();
+}
+
+fn changelogs(cache_dir: &Path) -> Vec<Changelog> {
+    let mut paths = fs::read_dir(cache_dir.join(CHANGES_DIR))
+        .map(|entries| entries.flatten().map(|e| e.path()).collect::<Vec<_>>())
+        .unwrap_or_default();
+    paths.sort();
+    paths
+        .iter()
+        .map(|path| serde_json::from_str(&fs::read_to_string(path).unwrap()).unwrap())
+        .collect()
+}
+
+#[tokio::test]
+async fn incremental_fetch_records_changes() {
+    let cache_dir = std::env::temp_dir().join(format!("sogrim_incremental_{}", std::process::id()));
+    let _ = fs::remove_dir_all(&cache_dir);
+    let sem_dir = cache_dir.join("2025").join("200");
+    let args = FetcherArgs {
+        incremental: true,
+        ..args(cache_dir.clone())
+    };
+    let mut fixture: BTreeMap<String, Value> =
+        serde_json::from_str(&fs::read_to_string(FIXTURE_PATH).unwrap()).unwrap();
+
+    // Everything is new on the first run
+    let sap = MockSap::replay(fixture.clone()).await;
+    run_fetch(&args, Arc::new(sap.client())).await;
+    let logs = changelogs(&cache_dir);
+    assert_eq!(logs.len(), 1);
+    let added = logs[0].semesters[0]
+        .changes
+        .iter()
+        .map(|change| change.course_id().to_string())
+        .collect::<Vec<_>>();
+    assert_eq!(added, vec!["02340114", "01040031"]);
+
+    // Nothing changed, so nothing is written
+    let modified = fs::metadata(sem_dir.join("02340114.json"))
+        .unwrap()
+        .modified()
+        .unwrap();
+    let sap = MockSap::replay(fixture.clone()).await;
+    run_fetch(&args, Arc::new(sap.client())).await;
+    assert_eq!(changelogs(&cache_dir).len(), 1);
+    let unchanged = fs::metadata(sem_dir.join("02340114.json"))
+        .unwrap()
+        .modified()
+        .unwrap();
+    assert_eq!(modified, unchanged);
+
+    // SAP drops calculus and changes the credit of intro to CS
+    for (query, answer) in fixture.iter_mut() {
+        if query.starts_with("SmObjectSet?sap-client=700&$skip=0") {
+            let results = answer["d"]["results"].as_array_mut().unwrap();
+            results.retain(|course| course["Otjid"] != "SM01040031");
+        } else if query.contains("Otjid%20eq%20%27SM02340114%27") {
+            answer["d"]["results"][0]["Points"] = "3.5".into();
+        }
+    }
+    let sap = MockSap::replay(fixture).await;
+    run_fetch(&args, Arc::new(sap.client())).await;
+    let logs = changelogs(&cache_dir);
+    assert_eq!(logs.len(), 2);
+    assert_eq!(
+        logs[1].semesters[0].changes,
+        vec![
+            CourseChange::Changed {
+                course_id: CourseId::new("02340114"),
+                name: "מבוא למדעי המחשב מ'".to_string(),
+                changes: vec![FieldChange::Credits { from: 4.0, to: 3.5 }],
+            },
+            CourseChange::Removed {
+                course_id: CourseId::new("01040031"),
+                name: "חשבון אינפיניטסימלי 1מ'".to_string(),
+            },
+        ]
+    );
+    assert!(!sem_dir.join("01040031.json").exists());
+
+    fs::remove_dir_all(&cache_dir).unwrap();
+}
+
+#[tokio::test]
+async fn incremental_fetch_keeps_the_courses_of_an_emptied_index() {
+    let cache_dir = std::env::temp_dir().join(format!("sogrim_emptied_{}", std::process::id()));
+    let _ = fs::remove_dir_all(&cache_dir);
+    let sem_dir = cache_dir.join("2025").join("200");
+    let args = FetcherArgs {
+        incremental: true,
+        ..args(cache_dir.clone())
+    };
+    let mut fixture: BTreeMap<String, Value> =
+        serde_json::from_str(&fs::read_to_string(FIXTURE_PATH).unwrap()).unwrap();
+    let sap = MockSap::replay(fixture.clone()).await;
+    run_fetch(&args, Arc::new(sap.client())).await;
+    let index = fs::read_to_string(sem_dir.join("_index.json")).unwrap();
+
+    // SAP answers with an empty index, e.g. during maintenance
+    for (query, answer) in fixture.iter_mut() {
+        if query.starts_with("SmObjectSet?sap-client=700&$skip=0") {
+            answer["d"]["results"] = Value::Array(Vec::new());
+        }
+    }
+    let sap = MockSap::replay(fixture).await;
+    run_fetch(&args, Arc::new(sap.client())).await;
+
+    assert_eq!(
+        fs::read_to_string(sem_dir.join("_index.json")).unwrap(),
+        index
+    );
+    assert!(sem_dir.join("02340114.json").exists());
+    assert!(sem_dir.join("01040031.json").exists());
+    assert_eq!(changelogs(&cache_dir).len(), 1);
+
+    fs::remove_dir_all(&cache_dir).unwrap();
+}
+
+#[test]
+fn index_shrinks_sharply_when_emptied_or_losing_many_courses() {
+    assert!(index_shrank_sharply(2, 0));
+    assert!(!index_shrank_sharply(2, 1));
+    assert!(!index_shrank_sharply(1500, 1490));
+    assert!(index_shrank_sharply(1500, 1300));
+}
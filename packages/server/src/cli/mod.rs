@@ -1,2 +1,3 @@
 pub mod catalog;
 pub mod fetcher;
+pub mod notify;
@@ -0,0 +1,84 @@
+use std::fs;
+use std::path::{Path, PathBuf};
+
+use crate::core::timetable::notifications;
+use crate::db::{Db, FilterOption};
+use crate::resources::user::User;
+use crate::sap::changes::{Changelog, CHANGES_DIR};
+
+// The name of the last changelog delivered, under the changelog directory.
+const DELIVERED_FILE: &str = "_delivered";
+
+// The changelogs written since the last delivery, oldest first. Their names are timestamps.
+fn pending_changelogs(changes_dir: &Path) -> Result<Vec<PathBuf>, anyhow::Error> {
+    let delivered = fs::read_to_string(changes_dir.join(DELIVERED_FILE)).unwrap_or_default();
+    let delivered = delivered.trim();
+    let Ok(entries) = fs::read_dir(changes_dir) else {
+        return Ok(Vec::new());
+    };
+    let mut pending = Vec::new();
+    for entry in entries {
+        let path = entry?.path();
+        let Some(name) = path.file_name().and_then(|name| name.to_str()) else {
+            continue;
+        };
+        if name.ends_with(".json") && name > delivered {
+            pending.push(path);
+        }
+    }
+    pending.sort();
+    Ok(pending)
+}
+
+/// Delivers the course changes found by `fetcher --incremental` to the students whose published
+/// timetables they affect. Delivering a changelog twice adds nothing.
+pub async fn run(db: &Db, cache_dir: &Path) -> Result<(), anyhow::Error> {
+    let changes_dir = cache_dir.join(CHANGES_DIR);
+    let pending = pending_changelogs(&changes_dir)?;
+    let Some(last) = pending.last() else {
+        println!("No new changelogs in {}.", changes_dir.display());
+        return Ok(());
+    };
+
+    let mut changelogs = Vec::new();
+    for path in &pending {
+        let text = fs::read_to_string(path)
+            .map_err(|e| anyhow::anyhow!("Failed to read {}: {e}", path.display()))?;
+        let changelog: Changelog = serde_json::from_str(&text)
+            .map_err(|e| anyhow::anyhow!("Failed to parse {}: {e}", path.display()))?;
+        changelogs.push(changelog);
+    }
+
+    let users = db
+        .get_filtered::<User>(
+            FilterOption::In,
+            "timetable.drafts.is_published",
+            vec![true],
+        )
+        .await?;
+    let mut notified = 0usize;
+    for mut user in users {
+        let mut delivered = Vec::new();
+        for changelog in &changelogs {
+            let found = notifications::notifications_for(&user.timetable.drafts, changelog);
+            delivered.extend(notifications::deliver(&mut user, found));
+        }
+        if !delivered.is_empty() {
+            // Pushed rather than saved, so that notifications read meanwhile stay read
+            user.push_notifications(db, delivered).await?;
+            notified += 1;
+        }
+    }
+
+    // Only after every student got them, so that a failed run is retried in full
+    let name = last
+        .file_name()
+        .and_then(|name| name.to_str())
+        .unwrap_or_default();
+    fs::write(changes_dir.join(DELIVERED_FILE), name)?;
+    println!(
+        "Delivered {} changelog(s), notified {notified} student(s).",
+        changelogs.len()
+    );
+    Ok(())
+}
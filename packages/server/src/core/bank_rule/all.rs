@@ -26,6 +26,7 @@ impl BankRuleHandler<'_> {
                         credit: 0.0,
                         name: messages::cannot_find_course(),
                         tags: None,
+                        relations: Vec::new(),
                     });
                 self.degree_status.course_statuses.push(CourseStatus {
                     course,
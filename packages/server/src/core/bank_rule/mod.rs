@@ -8,6 +8,7 @@ pub mod specialization_groups;
 pub mod sport;
 #[cfg(test)]
 pub mod tests;
+pub mod wildcard;
 
 use std::collections::HashMap;
 
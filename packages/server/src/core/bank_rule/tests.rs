@@ -8,6 +8,7 @@ use crate::core::types::{
     DoubleGroup, Requirement, SpecializationGroup, SpecializationGroups, SpecializationGroupsType,
 };
 use crate::create_bank_rule_handler;
+use crate::resources::catalog::{Catalog, Faculty};
 use crate::resources::course::{Course, CourseId, CourseState, CourseStatus, Grade};
 
 static COURSES: LazyLock<HashMap<CourseId, Course>> = LazyLock::new(|| {
@@ -19,6 +20,7 @@ static COURSES: LazyLock<HashMap<CourseId, Course>> = LazyLock::new(|| {
                 credit: 5.5,
                 name: "infi1m".to_string(),
                 tags: None,
+                relations: Vec::new(),
             },
         ),
         (
@@ -28,6 +30,7 @@ static COURSES: LazyLock<HashMap<CourseId, Course>> = LazyLock::new(|| {
                 credit: 5.5,
                 name: "Algebra alef".to_string(),
                 tags: None,
+                relations: Vec::new(),
             },
         ),
         (
@@ -37,6 +40,7 @@ static COURSES: LazyLock<HashMap<CourseId, Course>> = LazyLock::new(|| {
                 credit: 3.5,
                 name: "פיסיקה 2".to_string(),
                 tags: None,
+                relations: Vec::new(),
             },
         ),
         (
@@ -46,6 +50,7 @@ static COURSES: LazyLock<HashMap<CourseId, Course>> = LazyLock::new(|| {
                 credit: 3.5,
                 name: "פיסיקה 3".to_string(),
                 tags: None,
+                relations: Vec::new(),
             },
         ),
         (
@@ -55,6 +60,7 @@ static COURSES: LazyLock<HashMap<CourseId, Course>> = LazyLock::new(|| {
                 credit: 3.0,
                 name: "project1".to_string(),
                 tags: None,
+                relations: Vec::new(),
             },
         ),
         (
@@ -64,6 +70,7 @@ static COURSES: LazyLock<HashMap<CourseId, Course>> = LazyLock::new(|| {
                 credit: 3.0,
                 name: "project2".to_string(),
                 tags: None,
+                relations: Vec::new(),
             },
         ),
         (
@@ -73,6 +80,7 @@ static COURSES: LazyLock<HashMap<CourseId, Course>> = LazyLock::new(|| {
                 credit: 1.0,
                 name: "".to_string(),
                 tags: None,
+                relations: Vec::new(),
             },
         ),
         (
@@ -82,6 +90,7 @@ static COURSES: LazyLock<HashMap<CourseId, Course>> = LazyLock::new(|| {
                 credit: 2.0,
                 name: "".to_string(),
                 tags: None,
+                relations: Vec::new(),
             },
         ),
         (
@@ -91,6 +100,7 @@ static COURSES: LazyLock<HashMap<CourseId, Course>> = LazyLock::new(|| {
                 credit: 3.0,
                 name: "".to_string(),
                 tags: None,
+                relations: Vec::new(),
             },
         ),
     ])
@@ -349,6 +359,7 @@ async fn test_specialization_group() {
                     credit: 5.5,
                     name: "".to_string(),
                     tags: None,
+                    relations: Vec::new(),
                 },
                 state: Some(CourseState::Complete),
                 grade: Some(Grade::Numeric(85)),
@@ -360,6 +371,7 @@ async fn test_specialization_group() {
                     credit: 5.5,
                     name: "".to_string(),
                     tags: None,
+                    relations: Vec::new(),
                 },
                 state: Some(CourseState::Complete),
                 grade: Some(Grade::Binary(true)),
@@ -371,6 +383,7 @@ async fn test_specialization_group() {
                     credit: 3.5,
                     name: "".to_string(),
                     tags: None,
+                    relations: Vec::new(),
                 },
                 state: Some(CourseState::Complete),
                 grade: Some(Grade::Numeric(85)),
@@ -382,6 +395,7 @@ async fn test_specialization_group() {
                     credit: 3.0,
                     name: "".to_string(),
                     tags: None,
+                    relations: Vec::new(),
                 },
                 state: Some(CourseState::Complete),
                 grade: Some(Grade::Numeric(85)),
@@ -393,6 +407,7 @@ async fn test_specialization_group() {
                     credit: 3.0,
                     name: "".to_string(),
                     tags: None,
+                    relations: Vec::new(),
                 },
                 state: Some(CourseState::Complete),
                 grade: Some(Grade::Numeric(85)),
@@ -404,6 +419,7 @@ async fn test_specialization_group() {
                     credit: 2.0,
                     name: "".to_string(),
                     tags: None,
+                    relations: Vec::new(),
                 },
                 state: Some(CourseState::Complete),
                 grade: Some(Grade::Numeric(99)),
@@ -415,6 +431,7 @@ async fn test_specialization_group() {
                     credit: 1.0,
                     name: "".to_string(),
                     tags: None,
+                    relations: Vec::new(),
                 },
                 state: Some(CourseState::Complete),
                 grade: Some(Grade::Numeric(100)),
@@ -426,6 +443,7 @@ async fn test_specialization_group() {
                     credit: 1.0,
                     name: "".to_string(),
                     tags: None,
+                    relations: Vec::new(),
                 },
                 state: Some(CourseState::Complete),
                 grade: Some(Grade::Numeric(100)),
@@ -437,6 +455,7 @@ async fn test_specialization_group() {
                     credit: 3.5,
                     name: "".to_string(),
                     tags: None,
+                    relations: Vec::new(),
                 },
                 state: Some(CourseState::Complete),
                 grade: Some(Grade::Numeric(85)),
@@ -448,6 +467,7 @@ async fn test_specialization_group() {
                     credit: 3.5,
                     name: "".to_string(),
                     tags: None,
+                    relations: Vec::new(),
                 },
                 state: Some(CourseState::Complete),
                 grade: Some(Grade::Numeric(85)),
@@ -459,6 +479,7 @@ async fn test_specialization_group() {
                     credit: 3.5,
                     name: "".to_string(),
                     tags: None,
+                    relations: Vec::new(),
                 },
                 state: Some(CourseState::Complete),
                 grade: Some(Grade::Numeric(85)),
@@ -470,6 +491,7 @@ async fn test_specialization_group() {
                     credit: 3.5,
                     name: "".to_string(),
                     tags: None,
+                    relations: Vec::new(),
                 },
                 state: Some(CourseState::Complete),
                 grade: Some(Grade::Numeric(85)),
@@ -479,6 +501,8 @@ async fn test_specialization_group() {
         course_bank_requirements: Vec::<Requirement>::new(),
         overflow_msgs: Vec::<String>::new(),
         total_credit: 0.0,
+        averages: Default::default(),
+        trace: None,
     };
     let course_list = vec![
         CourseId::new("236334"),
@@ -678,6 +702,7 @@ fn sg_completed_course(id: &str) -> CourseStatus {
             credit: 3.0,
             name: String::new(),
             tags: None,
+            relations: Vec::new(),
         },
         state: Some(CourseState::Complete),
         grade: Some(Grade::Numeric(85)),
@@ -706,6 +731,8 @@ fn run_specialization_group(
         course_bank_requirements: Vec::new(),
         overflow_msgs: Vec::new(),
         total_credit: 0.0,
+        averages: Default::default(),
+        trace: None,
     };
     let course_list = sg_ids(completed);
     let handler =
@@ -883,3 +910,62 @@ fn test_double_ignored_unless_groups_type_is_double() {
     assert_eq!(weight, 1);
     assert!(groups.contains(&"a".to_string()));
 }
+
+fn run_wildcard(bank_credit: Option<f32>, outside_faculty: bool) -> (DegreeStatus, f32) {
+    let mut in_progress = sg_completed_course("02360343");
+    in_progress.state = Some(CourseState::InProgress);
+    in_progress.grade = None;
+    let mut moved_by_user = sg_completed_course("02360766");
+    moved_by_user.set_type("wild");
+    moved_by_user.modified = true;
+    let mut degree_status = DegreeStatus {
+        course_statuses: vec![
+            sg_completed_course("02340114"),
+            sg_completed_course("01040031"),
+            sg_completed_course("02340118"),
+            in_progress,
+            sg_completed_course("02360501"),
+            sg_completed_course("02360756"),
+            moved_by_user,
+        ],
+        ..Default::default()
+    };
+    let catalog = Catalog {
+        faculty: Faculty::ComputerScience,
+        course_to_bank: HashMap::from([(CourseId::new("02340118"), "hova".to_string())]),
+        ..Default::default()
+    };
+    let handler = create_bank_rule_handler!(&mut degree_status, "wild".to_string(), vec![], 0.0, 0);
+    let sum_credit = handler.wildcard(&catalog, bank_credit, outside_faculty);
+    (degree_status, sum_credit)
+}
+
+fn wildcard_courses(degree_status: &DegreeStatus) -> Vec<String> {
+    degree_status
+        .course_statuses
+        .iter()
+        .filter(|course_status| course_status.r#type == Some("wild".to_string()))
+        .map(|course_status| course_status.course.id.to_string())
+        .collect()
+}
+
+#[test]
+fn test_rule_wildcard() {
+    // Faculty courses only, until the bank credit is reached. Courses the user moved to the bank
+    // count regardless, and courses the catalog puts in other banks are never taken.
+    let (degree_status, sum_credit) = run_wildcard(Some(4.0), false);
+    assert_eq!(
+        wildcard_courses(&degree_status),
+        vec!["02340114", "02360501", "02360766"]
+    );
+    assert_eq!(sum_credit, 9.0);
+
+    // Without a credit requirement every qualifying course is taken, here from any faculty
+    let (degree_status, sum_credit) = run_wildcard(None, true);
+    assert_eq!(
+        wildcard_courses(&degree_status),
+        vec!["02340114", "01040031", "02360501", "02360756", "02360766"]
+    );
+    assert_eq!(sum_credit, 15.0);
+    assert_eq!(degree_status.course_statuses[3].r#type, None);
+}
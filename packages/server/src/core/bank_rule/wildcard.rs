@@ -0,0 +1,51 @@
+use crate::resources::catalog::Catalog;
+
+use super::BankRuleHandler;
+
+impl BankRuleHandler<'_> {
+    // Takes completed courses no other bank claimed, in order, until `bank_credit` is reached. The
+    // rest are left for the banks after this one. Unless `outside_faculty` is set, only courses of
+    // the catalog's faculty qualify (every course does when the faculty prefixes aren't known).
+    pub fn wildcard(
+        self,
+        catalog: &Catalog,
+        bank_credit: Option<f32>,
+        outside_faculty: bool,
+    ) -> f32 {
+        let prefixes = catalog.course_prefixes();
+        let mut sum_credit = self.credit_overflow;
+        for course_status in self
+            .degree_status
+            .course_statuses
+            .iter_mut()
+            .filter(|course_status| course_status.valid_for_bank(&self.bank_name))
+        {
+            // If the course is valid for the bank and its type is set, the user moved it here
+            if course_status.r#type.is_none() {
+                let Some(credit) = course_status.credit() else {
+                    continue;
+                };
+                let full = bank_credit.is_some_and(|bank_credit| sum_credit >= bank_credit);
+                let in_faculty = outside_faculty
+                    || prefixes.is_empty()
+                    || prefixes
+                        .iter()
+                        .any(|prefix| course_status.course.id.starts_with(prefix));
+                // Faculty courses outside the catalog are only in the default bank as a fallback
+                let other_bank = catalog
+                    .course_to_bank
+                    .get(&course_status.course.id)
+                    .is_some_and(|bank_name| *bank_name != self.bank_name)
+                    && !catalog.prefix_courses.contains(&course_status.course.id);
+                if full || credit == 0.0 || !in_faculty || other_bank {
+                    continue;
+                }
+            }
+            sum_credit += course_status
+                .set_type(&self.bank_name)
+                .credit()
+                .unwrap_or(0.0);
+        }
+        sum_credit
+    }
+}
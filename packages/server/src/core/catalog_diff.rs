@@ -0,0 +1,457 @@
+use std::collections::{BTreeMap, BTreeSet};
+use std::fmt;
+
+use serde::Serialize;
+
+use crate::{
+    core::types::{BankConstraints, Chain, Rule, SpecializationGroup, SpecializationGroups},
+    resources::{
+        catalog::{Catalog, OptionalReplacements},
+        course::{CourseBank, CourseId},
+    },
+};
+
+#[derive(Clone, Debug, PartialEq, Serialize)]
+pub struct Change<T> {
+    pub from: T,
+    pub to: T,
+}
+
+impl<T: PartialEq> Change<T> {
+    fn of(from: T, to: T) -> Option<Self> {
+        (from != to).then_some(Change { from, to })
+    }
+}
+
+#[derive(Clone, Debug, PartialEq, Serialize)]
+#[serde(tag = "kind", rename_all = "snake_case")]
+pub enum RuleChange {
+    // The rule became a different kind of rule
+    Replaced { from: String, to: String },
+    CourseCount(Change<usize>),
+    Wildcard(Change<bool>),
+    AddedChain { chain: Chain },
+    RemovedChain { chain: Chain },
+    GroupsNumber(Change<usize>),
+    GroupsType(Change<String>),
+    AddedGroup { name: String },
+    RemovedGroup { name: String },
+    Group(GroupChange),
+}
+
+#[derive(Clone, Debug, Default, PartialEq, Serialize)]
+pub struct GroupChange {
+    pub name: String,
+    pub courses_sum: Option<Change<usize>>,
+    pub added_courses: Vec<CourseId>,
+    pub removed_courses: Vec<CourseId>,
+    pub mandatory_changed: bool,
+    pub double_changed: bool,
+}
+
+#[derive(Clone, Debug, PartialEq, Serialize)]
+pub struct BankChange {
+    pub name: String,
+    pub credit: Option<Change<Option<f32>>>,
+    pub rule: Vec<RuleChange>,
+    pub constraints: Option<Change<Option<BankConstraints>>>,
+}
+
+#[derive(Clone, Debug, PartialEq, Serialize)]
+pub struct CourseMove {
+    pub course_id: CourseId,
+    // None when the course was added to the catalog
+    pub from: Option<String>,
+    // None when the course was removed from the catalog
+    pub to: Option<String>,
+}
+
+#[derive(Clone, Debug, PartialEq, Serialize)]
+pub struct ReplacementChange {
+    pub course_id: CourseId,
+    pub from: OptionalReplacements,
+    pub to: OptionalReplacements,
+}
+
+/// What changed from one catalog to another, typically the previous year of the same track.
+#[derive(Clone, Debug, PartialEq, Serialize)]
+pub struct CatalogDiff {
+    pub from: String,
+    pub to: String,
+    pub total_credit: Option<Change<f64>>,
+    pub added_banks: Vec<CourseBank>,
+    pub removed_banks: Vec<CourseBank>,
+    pub changed_banks: Vec<BankChange>,
+    pub added_credit_overflows: Vec<(String, String)>,
+    pub removed_credit_overflows: Vec<(String, String)>,
+    pub course_moves: Vec<CourseMove>,
+    pub catalog_replacements: Vec<ReplacementChange>,
+    pub common_replacements: Vec<ReplacementChange>,
+}
+
+fn added_and_removed<T: Ord + Clone>(from: &[T], to: &[T]) -> (Vec<T>, Vec<T>) {
+    let from = from.iter().collect::<BTreeSet<_>>();
+    let to = to.iter().collect::<BTreeSet<_>>();
+    (
+        to.difference(&from).map(|&item| item.clone()).collect(),
+        from.difference(&to).map(|&item| item.clone()).collect(),
+    )
+}
+
+fn group_change(from: &SpecializationGroup, to: &SpecializationGroup) -> Option<GroupChange> {
+    let (added_courses, removed_courses) = added_and_removed(&from.course_list, &to.course_list);
+    let change = GroupChange {
+        name: to.name.clone(),
+        courses_sum: Change::of(from.courses_sum, to.courses_sum),
+        added_courses,
+        removed_courses,
+        mandatory_changed: from.mandatory != to.mandatory,
+        double_changed: from.double != to.double,
+    };
+    (change
+        != GroupChange {
+            name: to.name.clone(),
+            ..Default::default()
+        })
+    .then_some(change)
+}
+
+fn specialization_groups_changes(
+    from: &SpecializationGroups,
+    to: &SpecializationGroups,
+) -> Vec<RuleChange> {
+    let mut changes = Vec::new();
+    if let Some(change) = Change::of(from.groups_number, to.groups_number) {
+        changes.push(RuleChange::GroupsNumber(change));
+    }
+    if from.groups_type != to.groups_type {
+        changes.push(RuleChange::GroupsType(Change {
+            from: format!("{:?}", from.groups_type),
+            to: format!("{:?}", to.groups_type),
+        }));
+    }
+
+    let from_groups = from
+        .groups_list
+        .iter()
+        .map(|group| (group.name.as_str(), group))
+        .collect::<BTreeMap<_, _>>();
+    let to_groups = to
+        .groups_list
+        .iter()
+        .map(|group| (group.name.as_str(), group))
+        .collect::<BTreeMap<_, _>>();
+    for (name, to_group) in &to_groups {
+        match from_groups.get(name) {
+            Some(from_group) => {
+                changes.extend(group_change(from_group, to_group).map(RuleChange::Group))
+            }
+            None => changes.push(RuleChange::AddedGroup {
+                name: name.to_string(),
+            }),
+        }
+    }
+    for name in from_groups
+        .keys()
+        .filter(|name| !to_groups.contains_key(*name))
+    {
+        changes.push(RuleChange::RemovedGroup {
+            name: name.to_string(),
+        });
+    }
+    changes
+}
+
+fn rule_changes(from: &Rule, to: &Rule) -> Vec<RuleChange> {
+    match (from, to) {
+        (Rule::AccumulateCourses(from), Rule::AccumulateCourses(to)) => Change::of(*from, *to)
+            .map(RuleChange::CourseCount)
+            .into_iter()
+            .collect(),
+        (Rule::Wildcard(from), Rule::Wildcard(to)) => Change::of(*from, *to)
+            .map(RuleChange::Wildcard)
+            .into_iter()
+            .collect(),
+        (Rule::Chains(from), Rule::Chains(to)) => {
+            let (added, removed) = added_and_removed(from, to);
+            added
+                .into_iter()
+                .map(|chain| RuleChange::AddedChain { chain })
+                .chain(
+                    removed
+                        .into_iter()
+                        .map(|chain| RuleChange::RemovedChain { chain }),
+                )
+                .collect()
+        }
+        (Rule::SpecializationGroups(from), Rule::SpecializationGroups(to)) => {
+            specialization_groups_changes(from, to)
+        }
+        (from, to) if std::mem::discriminant(from) != std::mem::discriminant(to) => {
+            vec![RuleChange::Replaced {
+                from: from.to_string(),
+                to: to.to_string(),
+            }]
+        }
+        _ => Vec::new(),
+    }
+}
+
+fn replacement_changes(
+    from: &std::collections::HashMap<CourseId, OptionalReplacements>,
+    to: &std::collections::HashMap<CourseId, OptionalReplacements>,
+) -> Vec<ReplacementChange> {
+    let course_ids = from.keys().chain(to.keys()).collect::<BTreeSet<_>>();
+    course_ids
+        .into_iter()
+        .filter_map(|course_id| {
+            let from = from.get(course_id).cloned().unwrap_or_default();
+            let to = to.get(course_id).cloned().unwrap_or_default();
+            let (added, removed) = added_and_removed(&from, &to);
+            (!added.is_empty() || !removed.is_empty()).then(|| ReplacementChange {
+                course_id: course_id.clone(),
+                from,
+                to,
+            })
+        })
+        .collect()
+}
+
+impl CatalogDiff {
+    pub fn between(from: &Catalog, to: &Catalog) -> Self {
+        let from_banks = from
+            .course_banks
+            .iter()
+            .map(|bank| (bank.name.as_str(), bank))
+            .collect::<BTreeMap<_, _>>();
+        let to_banks = to
+            .course_banks
+            .iter()
+            .map(|bank| (bank.name.as_str(), bank))
+            .collect::<BTreeMap<_, _>>();
+
+        let mut added_banks = Vec::new();
+        let mut changed_banks = Vec::new();
+        for (name, to_bank) in &to_banks {
+            let Some(from_bank) = from_banks.get(name) else {
+                added_banks.push((*to_bank).clone());
+                continue;
+            };
+            let change = BankChange {
+                name: name.to_string(),
+                credit: Change::of(from_bank.credit, to_bank.credit),
+                rule: rule_changes(&from_bank.rule, &to_bank.rule),
+                constraints: Change::of(from_bank.constraints.clone(), to_bank.constraints.clone()),
+            };
+            if change.credit.is_some() || !change.rule.is_empty() || change.constraints.is_some() {
+                changed_banks.push(change);
+            }
+        }
+        let removed_banks = from_banks
+            .iter()
+            .filter(|(name, _)| !to_banks.contains_key(*name))
+            .map(|(_, bank)| (*bank).clone())
+            .collect();
+
+        let overflows = |catalog: &Catalog| {
+            catalog
+                .credit_overflows
+                .iter()
+                .map(|overflow| (overflow.from.clone(), overflow.to.clone()))
+                .collect::<Vec<_>>()
+        };
+        let (added_credit_overflows, removed_credit_overflows) =
+            added_and_removed(&overflows(from), &overflows(to));
+
+        let course_moves = from
+            .course_to_bank
+            .keys()
+            .chain(to.course_to_bank.keys())
+            .collect::<BTreeSet<_>>()
+            .into_iter()
+            .filter_map(|course_id| {
+                let from = from.course_to_bank.get(course_id).cloned();
+                let to = to.course_to_bank.get(course_id).cloned();
+                (from != to).then(|| CourseMove {
+                    course_id: course_id.clone(),
+                    from,
+                    to,
+                })
+            })
+            .collect();
+
+        CatalogDiff {
+            from: from.name.clone(),
+            to: to.name.clone(),
+            total_credit: Change::of(from.total_credit, to.total_credit),
+            added_banks,
+            removed_banks,
+            changed_banks,
+            added_credit_overflows,
+            removed_credit_overflows,
+            course_moves,
+            catalog_replacements: replacement_changes(
+                &from.catalog_replacements,
+                &to.catalog_replacements,
+            ),
+            common_replacements: replacement_changes(
+                &from.common_replacements,
+                &to.common_replacements,
+            ),
+        }
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.total_credit.is_none()
+            && self.added_banks.is_empty()
+            && self.removed_banks.is_empty()
+            && self.changed_banks.is_empty()
+            && self.added_credit_overflows.is_empty()
+            && self.removed_credit_overflows.is_empty()
+            && self.course_moves.is_empty()
+            && self.catalog_replacements.is_empty()
+            && self.common_replacements.is_empty()
+    }
+}
+
+fn fmt_credit(credit: Option<f32>) -> String {
+    credit.map_or("-".to_string(), |credit| credit.to_string())
+}
+
+fn fmt_courses(course_ids: &[CourseId]) -> String {
+    course_ids
+        .iter()
+        .map(CourseId::to_string)
+        .collect::<Vec<_>>()
+        .join(", ")
+}
+
+impl fmt::Display for RuleChange {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            RuleChange::Replaced { from, to } => write!(f, "rule: {from} → {to}"),
+            RuleChange::CourseCount(Change { from, to }) => write!(f, "courses: {from} → {to}"),
+            RuleChange::Wildcard(Change { from, to }) => write!(f, "wildcard: {from} → {to}"),
+            RuleChange::AddedChain { chain } => write!(f, "+ chain [{}]", fmt_courses(chain)),
+            RuleChange::RemovedChain { chain } => write!(f, "- chain [{}]", fmt_courses(chain)),
+            RuleChange::GroupsNumber(Change { from, to }) => {
+                write!(f, "groups required: {from} → {to}")
+            }
+            RuleChange::GroupsType(Change { from, to }) => write!(f, "groups type: {from} → {to}"),
+            RuleChange::AddedGroup { name } => write!(f, "+ group \"{name}\""),
+            RuleChange::RemovedGroup { name } => write!(f, "- group \"{name}\""),
+            RuleChange::Group(group) => {
+                let mut parts = Vec::new();
+                if let Some(Change { from, to }) = &group.courses_sum {
+                    parts.push(format!("courses: {from} → {to}"));
+                }
+                if !group.added_courses.is_empty() {
+                    parts.push(format!("+ [{}]", fmt_courses(&group.added_courses)));
+                }
+                if !group.removed_courses.is_empty() {
+                    parts.push(format!("- [{}]", fmt_courses(&group.removed_courses)));
+                }
+                if group.mandatory_changed {
+                    parts.push("mandatory courses changed".to_string());
+                }
+                if group.double_changed {
+                    parts.push("double group changed".to_string());
+                }
+                write!(f, "~ group \"{}\": {}", group.name, parts.join("; "))
+            }
+        }
+    }
+}
+
+impl fmt::Display for CatalogDiff {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        writeln!(f, "\"{}\" → \"{}\"", self.from, self.to)?;
+        if self.is_empty() {
+            return writeln!(f, "No changes");
+        }
+        if let Some(Change { from, to }) = &self.total_credit {
+            writeln!(f, "Total credit: {from} → {to}")?;
+        }
+
+        if !self.added_banks.is_empty()
+            || !self.removed_banks.is_empty()
+            || !self.changed_banks.is_empty()
+        {
+            writeln!(f, "\nBanks:")?;
+        }
+        for bank in &self.added_banks {
+            writeln!(
+                f,
+                "  + \"{}\" ({}, credit {})",
+                bank.name,
+                bank.rule,
+                fmt_credit(bank.credit)
+            )?;
+        }
+        for bank in &self.removed_banks {
+            writeln!(f, "  - \"{}\"", bank.name)?;
+        }
+        for bank in &self.changed_banks {
+            writeln!(f, "  ~ \"{}\"", bank.name)?;
+            if let Some(Change { from, to }) = bank.credit {
+                writeln!(f, "      credit: {} → {}", fmt_credit(from), fmt_credit(to))?;
+            }
+            for change in &bank.rule {
+                writeln!(f, "      {change}")?;
+            }
+            if bank.constraints.is_some() {
+                writeln!(f, "      constraints changed")?;
+            }
+        }
+
+        if !self.added_credit_overflows.is_empty() || !self.removed_credit_overflows.is_empty() {
+            writeln!(f, "\nCredit overflows:")?;
+        }
+        for (from, to) in &self.added_credit_overflows {
+            writeln!(f, "  + \"{from}\" → \"{to}\"")?;
+        }
+        for (from, to) in &self.removed_credit_overflows {
+            writeln!(f, "  - \"{from}\" → \"{to}\"")?;
+        }
+
+        if !self.course_moves.is_empty() {
+            writeln!(f, "\nCourses:")?;
+        }
+        for course_move in &self.course_moves {
+            match (&course_move.from, &course_move.to) {
+                (None, Some(to)) => writeln!(f, "  + {} → \"{to}\"", course_move.course_id)?,
+                (Some(from), None) => writeln!(f, "  - {} (\"{from}\")", course_move.course_id)?,
+                (from, to) => writeln!(
+                    f,
+                    "  ~ {}: \"{}\" → \"{}\"",
+                    course_move.course_id,
+                    from.as_deref().unwrap_or_default(),
+                    to.as_deref().unwrap_or_default()
+                )?,
+            }
+        }
+
+        for (title, changes) in [
+            ("Catalog replacements", &self.catalog_replacements),
+            ("Common replacements", &self.common_replacements),
+        ] {
+            if !changes.is_empty() {
+                writeln!(f, "\n{title}:")?;
+            }
+            for change in changes {
+                writeln!(
+                    f,
+                    "  ~ {}: [{}] → [{}]",
+                    change.course_id,
+                    fmt_courses(&change.from),
+                    fmt_courses(&change.to)
+                )?;
+            }
+        }
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+#[path = "catalog_diff_tests.rs"]
+mod catalog_diff_tests;
@@ -0,0 +1,257 @@
+use std::collections::{HashMap, HashSet};
+
+use crate::{
+    core::types::CreditOverflow,
+    resources::catalog::{Catalog, Faculty},
+};
+
+use super::*;
+
+fn bank(name: &str, rule: Rule, credit: Option<f32>) -> CourseBank {
+    CourseBank {
+        name: name.to_string(),
+        rule,
+        credit,
+        constraints: None,
+    }
+}
+
+fn ids(ids: &[&str]) -> Vec<CourseId> {
+    ids.iter().map(|id| CourseId::new(*id)).collect()
+}
+
+fn group(name: &str, courses_sum: usize, course_list: &[&str]) -> SpecializationGroup {
+    SpecializationGroup {
+        name: name.to_string(),
+        courses_sum,
+        course_list: ids(course_list),
+        ..Default::default()
+    }
+}
+
+fn catalog(name: &str) -> Catalog {
+    Catalog {
+        id: bson::oid::ObjectId::new(),
+        name: name.to_string(),
+        faculty: Faculty::ComputerScience,
+        total_credit: 118.5,
+        description: String::new(),
+        course_banks: vec![
+            bank("hova", Rule::All, Some(80.0)),
+            bank("reshima", Rule::AccumulateCourses(3), Some(9.0)),
+            bank(
+                "chains",
+                Rule::Chains(vec![ids(&["01140051", "01140052"])]),
+                Some(8.0),
+            ),
+            bank(
+                "groups",
+                Rule::SpecializationGroups(SpecializationGroups {
+                    groups_list: vec![
+                        group("ai", 3, &["02360501", "02360756"]),
+                        group("systems", 3, &["02360370"]),
+                    ],
+                    groups_number: 2,
+                    groups_type: Default::default(),
+                }),
+                None,
+            ),
+            bank("sport", Rule::Sport, Some(2.0)),
+        ],
+        credit_overflows: vec![CreditOverflow {
+            from: "hova".to_string(),
+            to: "reshima".to_string(),
+        }],
+        course_to_bank: HashMap::from([
+            (CourseId::new("02340114"), "hova".to_string()),
+            (CourseId::new("02340118"), "hova".to_string()),
+            (CourseId::new("02360501"), "reshima".to_string()),
+        ]),
+        catalog_replacements: HashMap::from([(CourseId::new("02340114"), ids(&["02340117"]))]),
+        common_replacements: HashMap::new(),
+        prefix_courses: HashSet::new(),
+    }
+}
+
+#[test]
+fn identical_catalogs_have_no_diff() {
+    let diff = CatalogDiff::between(&catalog("cs 2024-2025"), &catalog("cs 2025-2026"));
+    assert!(diff.is_empty());
+    assert!(diff.to_string().contains("No changes"));
+}
+
+#[test]
+fn diff_banks_and_rules() {
+    let from = catalog("cs 2024-2025");
+    let mut to = catalog("cs 2025-2026");
+    to.total_credit = 120.0;
+    to.course_banks.retain(|bank| bank.name != "sport");
+    to.course_banks.push(bank("malag", Rule::Malag, Some(6.0)));
+    for bank in to.course_banks.iter_mut() {
+        match bank.name.as_str() {
+            "hova" => bank.credit = Some(82.0),
+            "reshima" => bank.rule = Rule::AccumulateCourses(4),
+            "chains" => bank.rule = Rule::Chains(vec![ids(&["01140051", "01140054"])]),
+            "groups" => {
+                bank.rule = Rule::SpecializationGroups(SpecializationGroups {
+                    groups_list: vec![
+                        group("ai", 4, &["02360501", "02360766"]),
+                        group("theory", 3, &["02360343"]),
+                    ],
+                    groups_number: 2,
+                    groups_type: Default::default(),
+                })
+            }
+            _ => {}
+        }
+    }
+
+    let diff = CatalogDiff::between(&from, &to);
+
+    assert_eq!(
+        diff.total_credit,
+        Some(Change {
+            from: 118.5,
+            to: 120.0
+        })
+    );
+    assert_eq!(
+        diff.added_banks,
+        vec![bank("malag", Rule::Malag, Some(6.0))]
+    );
+    assert_eq!(
+        diff.removed_banks,
+        vec![bank("sport", Rule::Sport, Some(2.0))]
+    );
+    assert_eq!(
+        diff.changed_banks,
+        vec![
+            BankChange {
+                name: "chains".to_string(),
+                credit: None,
+                rule: vec![
+                    RuleChange::AddedChain {
+                        chain: ids(&["01140051", "01140054"])
+                    },
+                    RuleChange::RemovedChain {
+                        chain: ids(&["01140051", "01140052"])
+                    },
+                ],
+                constraints: None,
+            },
+            BankChange {
+                name: "groups".to_string(),
+                credit: None,
+                rule: vec![
+                    RuleChange::Group(GroupChange {
+                        name: "ai".to_string(),
+                        courses_sum: Some(Change { from: 3, to: 4 }),
+                        added_courses: ids(&["02360766"]),
+                        removed_courses: ids(&["02360756"]),
+                        ..Default::default()
+                    }),
+                    RuleChange::AddedGroup {
+                        name: "theory".to_string()
+                    },
+                    RuleChange::RemovedGroup {
+                        name: "systems".to_string()
+                    },
+                ],
+                constraints: None,
+            },
+            BankChange {
+                name: "hova".to_string(),
+                credit: Some(Change {
+                    from: Some(80.0),
+                    to: Some(82.0)
+                }),
+                rule: vec![],
+                constraints: None,
+            },
+            BankChange {
+                name: "reshima".to_string(),
+                credit: None,
+                rule: vec![RuleChange::CourseCount(Change { from: 3, to: 4 })],
+                constraints: None,
+            },
+        ]
+    );
+}
+
+#[test]
+fn diff_rule_kind_change() {
+    let from = catalog("cs 2024-2025");
+    let mut to = catalog("cs 2025-2026");
+    to.course_banks[1].rule = Rule::AccumulateCredit;
+
+    let diff = CatalogDiff::between(&from, &to);
+
+    assert_eq!(
+        diff.changed_banks[0].rule,
+        vec![RuleChange::Replaced {
+            from: "accumulate courses".to_string(),
+            to: "accumulate credit".to_string(),
+        }]
+    );
+}
+
+#[test]
+fn diff_courses_overflows_and_replacements() {
+    let from = catalog("cs 2024-2025");
+    let mut to = catalog("cs 2025-2026");
+    to.course_to_bank.remove(&CourseId::new("02340118"));
+    to.course_to_bank
+        .insert(CourseId::new("02360501"), "hova".to_string());
+    to.course_to_bank
+        .insert(CourseId::new("02340124"), "hova".to_string());
+    to.credit_overflows = vec![CreditOverflow {
+        from: "reshima".to_string(),
+        to: "hova".to_string(),
+    }];
+    to.catalog_replacements
+        .insert(CourseId::new("02340114"), ids(&["02340117", "02340111"]));
+
+    let diff = CatalogDiff::between(&from, &to);
+
+    assert_eq!(
+        diff.course_moves,
+        vec![
+            CourseMove {
+                course_id: CourseId::new("02340118"),
+                from: Some("hova".to_string()),
+                to: None,
+            },
+            CourseMove {
+                course_id: CourseId::new("02340124"),
+                from: None,
+                to: Some("hova".to_string()),
+            },
+            CourseMove {
+                course_id: CourseId::new("02360501"),
+                from: Some("reshima".to_string()),
+                to: Some("hova".to_string()),
+            },
+        ]
+    );
+    assert_eq!(
+        diff.added_credit_overflows,
+        vec![("reshima".to_string(), "hova".to_string())]
+    );
+    assert_eq!(
+        diff.removed_credit_overflows,
+        vec![("hova".to_string(), "reshima".to_string())]
+    );
+    assert_eq!(
+        diff.catalog_replacements,
+        vec![ReplacementChange {
+            course_id: CourseId::new("02340114"),
+            from: ids(&["02340117"]),
+            to: ids(&["02340117", "02340111"]),
+        }]
+    );
+    assert!(diff.common_replacements.is_empty());
+
+    let text = diff.to_string();
+    assert!(text.contains("~ 02360501: \"reshima\" → \"hova\""));
+    assert!(text.contains("+ \"reshima\" → \"hova\""));
+}
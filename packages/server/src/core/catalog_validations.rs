@@ -1,8 +1,316 @@
-use crate::{error::AppError, resources::catalog::Catalog};
+use std::collections::{BTreeSet, HashSet};
+use std::fmt;
 
-use super::credit_transfer_graph::validate_acyclic_credit_transfer_graph;
+use serde::Serialize;
 
-pub fn validate_catalog(catalog: &Catalog) -> Result<(), AppError> {
-    validate_acyclic_credit_transfer_graph(catalog)?;
-    Ok(())
+use crate::{
+    error::AppError,
+    resources::{catalog::Catalog, course::CourseId},
+};
+
+use super::{
+    credit_transfer_graph::validate_acyclic_credit_transfer_graph,
+    types::{Rule, SpecializationGroupsType},
+};
+
+// Bank credits are halves and quarters, anything below this is a rounding artifact.
+const CREDIT_EPSILON: f64 = 0.001;
+
+#[derive(Clone, Debug, PartialEq, Serialize)]
+#[serde(tag = "kind", rename_all = "snake_case")]
+pub enum CatalogError {
+    // A credit overflow from or to a bank the catalog doesn't have
+    UnknownOverflowBank {
+        from: String,
+        to: String,
+    },
+    // The credit transfer graph couldn't be built or has a cycle
+    CreditTransfer {
+        message: String,
+    },
+    UnknownBank {
+        course_id: CourseId,
+        bank: String,
+    },
+    // A chain or specialization group course which isn't in `course_to_bank`
+    CourseNotInCatalog {
+        bank: String,
+        course_id: CourseId,
+    },
+    UnknownCourse {
+        course_id: CourseId,
+    },
+    GroupsNumberTooLarge {
+        bank: String,
+        groups_number: usize,
+        groups: usize,
+    },
+    DoubleGroupNotAllowed {
+        bank: String,
+        group: String,
+    },
+    CreditMismatch {
+        banks_credit: f64,
+        total_credit: f64,
+    },
+    // A catalog replacement for a course the catalog doesn't have
+    UnknownReplacedCourse {
+        course_id: CourseId,
+    },
+}
+
+impl fmt::Display for CatalogError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            CatalogError::UnknownOverflowBank { from, to } => {
+                write!(f, "credit overflow \"{from}\" → \"{to}\" references an unknown bank")
+            }
+            CatalogError::CreditTransfer { message } => write!(f, "{message}"),
+            CatalogError::UnknownBank { course_id, bank } => {
+                write!(f, "course {course_id} is mapped to an unknown bank \"{bank}\"")
+            }
+            CatalogError::CourseNotInCatalog { bank, course_id } => write!(
+                f,
+                "course {course_id} appears in bank \"{bank}\" but is missing from course_to_bank"
+            ),
+            CatalogError::UnknownCourse { course_id } => {
+                write!(f, "course {course_id} is not in the course cache")
+            }
+            CatalogError::GroupsNumberTooLarge {
+                bank,
+                groups_number,
+                groups,
+            } => write!(
+                f,
+                "bank \"{bank}\" requires {groups_number} specialization groups but only has {groups}"
+            ),
+            CatalogError::DoubleGroupNotAllowed { bank, group } => write!(
+                f,
+                "group \"{group}\" in bank \"{bank}\" has a double spec but the groups type isn't Double"
+            ),
+            CatalogError::CreditMismatch {
+                banks_credit,
+                total_credit,
+            } => write!(
+                f,
+                "bank credits add up to {banks_credit} but the total credit is {total_credit}"
+            ),
+            CatalogError::UnknownReplacedCourse { course_id } => write!(
+                f,
+                "catalog replacements are listed for course {course_id} which isn't in the catalog"
+            ),
+        }
+    }
+}
+
+impl From<Vec<CatalogError>> for AppError {
+    fn from(errors: Vec<CatalogError>) -> Self {
+        AppError::BadRequest(
+            errors
+                .iter()
+                .map(ToString::to_string)
+                .collect::<Vec<_>>()
+                .join("\n"),
+        )
+    }
 }
+
+fn validate_credit_overflows(catalog: &Catalog, errors: &mut Vec<CatalogError>) {
+    let unknown_banks = catalog
+        .credit_overflows
+        .iter()
+        .filter(|overflow| {
+            catalog.get_course_bank_by_name(&overflow.from).is_none()
+                || catalog.get_course_bank_by_name(&overflow.to).is_none()
+        })
+        .map(|overflow| CatalogError::UnknownOverflowBank {
+            from: overflow.from.clone(),
+            to: overflow.to.clone(),
+        })
+        .collect::<Vec<_>>();
+    if !unknown_banks.is_empty() {
+        errors.extend(unknown_banks);
+        return;
+    }
+    if let Err(e) = validate_acyclic_credit_transfer_graph(catalog) {
+        errors.push(CatalogError::CreditTransfer {
+            message: e.to_string(),
+        });
+    }
+}
+
+fn validate_course_to_bank(catalog: &Catalog, errors: &mut Vec<CatalogError>) {
+    let mut unknown = catalog
+        .course_to_bank
+        .iter()
+        .filter(|(_, bank)| catalog.get_course_bank_by_name(bank).is_none())
+        .collect::<Vec<_>>();
+    unknown.sort();
+    errors.extend(
+        unknown
+            .into_iter()
+            .map(|(course_id, bank)| CatalogError::UnknownBank {
+                course_id: course_id.clone(),
+                bank: bank.clone(),
+            }),
+    );
+}
+
+// Every course a bank rule names on its own (rather than through `course_to_bank`).
+fn rule_courses(rule: &Rule) -> BTreeSet<&CourseId> {
+    match rule {
+        Rule::Chains(chains) => chains.iter().flatten().collect(),
+        Rule::SpecializationGroups(groups) => {
+            let mut courses = BTreeSet::new();
+            for group in &groups.groups_list {
+                courses.extend(&group.course_list);
+                courses.extend(group.mandatory.iter().flatten().flatten());
+                if let Some(double) = &group.double {
+                    courses.extend(double.mandatory.iter().flatten().flatten());
+                }
+            }
+            if let SpecializationGroupsType::MandatoryNotShared(pool) = &groups.groups_type {
+                courses.extend(pool);
+            }
+            courses
+        }
+        _ => BTreeSet::new(),
+    }
+}
+
+fn validate_rules(catalog: &Catalog, errors: &mut Vec<CatalogError>) {
+    for bank in &catalog.course_banks {
+        for course_id in rule_courses(&bank.rule) {
+            if !catalog.course_to_bank.contains_key(course_id) {
+                errors.push(CatalogError::CourseNotInCatalog {
+                    bank: bank.name.clone(),
+                    course_id: course_id.clone(),
+                });
+            }
+        }
+
+        let Rule::SpecializationGroups(groups) = &bank.rule else {
+            continue;
+        };
+        if groups.groups_number > groups.groups_list.len() {
+            errors.push(CatalogError::GroupsNumberTooLarge {
+                bank: bank.name.clone(),
+                groups_number: groups.groups_number,
+                groups: groups.groups_list.len(),
+            });
+        }
+        if groups.groups_type != SpecializationGroupsType::Double {
+            errors.extend(
+                groups
+                    .groups_list
+                    .iter()
+                    .filter(|group| group.double.is_some())
+                    .map(|group| CatalogError::DoubleGroupNotAllowed {
+                        bank: bank.name.clone(),
+                        group: group.name.clone(),
+                    }),
+            );
+        }
+    }
+}
+
+fn validate_total_credit(catalog: &Catalog, errors: &mut Vec<CatalogError>) {
+    let banks_credit = catalog
+        .course_banks
+        .iter()
+        .filter_map(|bank| bank.credit)
+        .map(f64::from)
+        .sum::<f64>();
+    if (banks_credit - catalog.total_credit).abs() > CREDIT_EPSILON {
+        errors.push(CatalogError::CreditMismatch {
+            banks_credit,
+            total_credit: catalog.total_credit,
+        });
+    }
+}
+
+fn validate_replacements(catalog: &Catalog, errors: &mut Vec<CatalogError>) {
+    let mut replaced = catalog
+        .catalog_replacements
+        .keys()
+        .filter(|course_id| !catalog.course_to_bank.contains_key(*course_id))
+        .collect::<Vec<_>>();
+    replaced.sort();
+    errors.extend(
+        replaced
+            .into_iter()
+            .map(|course_id| CatalogError::UnknownReplacedCourse {
+                course_id: course_id.clone(),
+            }),
+    );
+}
+
+fn validate_known_courses(
+    catalog: &Catalog,
+    known_courses: &HashSet<CourseId>,
+    errors: &mut Vec<CatalogError>,
+) {
+    // Chains and specialization groups name their courses, so a mistyped id never counts
+    let course_ids = catalog
+        .course_banks
+        .iter()
+        .flat_map(|bank| rule_courses(&bank.rule))
+        .collect::<BTreeSet<_>>();
+    errors.extend(
+        course_ids
+            .into_iter()
+            .filter(|course_id| !known_courses.contains(*course_id))
+            .map(|course_id| CatalogError::UnknownCourse {
+                course_id: course_id.clone(),
+            }),
+    );
+}
+
+/// Courses of `course_to_bank` and replaced courses which aren't in `known_courses`. They're only
+/// worth a warning, catalogs keep courses which are no longer given for the students who took them.
+pub fn unknown_courses(catalog: &Catalog, known_courses: &HashSet<CourseId>) -> Vec<CourseId> {
+    let rule_courses = catalog
+        .course_banks
+        .iter()
+        .flat_map(|bank| rule_courses(&bank.rule))
+        .collect::<BTreeSet<_>>();
+    catalog
+        .course_to_bank
+        .keys()
+        .chain(catalog.catalog_replacements.keys())
+        .chain(catalog.common_replacements.keys())
+        .filter(|course_id| {
+            !known_courses.contains(*course_id) && !rule_courses.contains(course_id)
+        })
+        .collect::<BTreeSet<_>>()
+        .into_iter()
+        .cloned()
+        .collect()
+}
+
+/// Checks that the catalog is consistent before it's stored. The courses of chains and
+/// specialization groups are also checked against `known_courses` when given, typically the course
+/// cache; see `unknown_courses` for the rest.
+pub fn validate_catalog(
+    catalog: &Catalog,
+    known_courses: Option<&HashSet<CourseId>>,
+) -> Result<(), Vec<CatalogError>> {
+    let mut errors = Vec::new();
+    validate_credit_overflows(catalog, &mut errors);
+    validate_course_to_bank(catalog, &mut errors);
+    validate_rules(catalog, &mut errors);
+    validate_total_credit(catalog, &mut errors);
+    validate_replacements(catalog, &mut errors);
+    if let Some(known_courses) = known_courses {
+        validate_known_courses(catalog, known_courses, &mut errors);
+    }
+    if errors.is_empty() {
+        Ok(())
+    } else {
+        Err(errors)
+    }
+}
+
+#[cfg(test)]
+#[path = "catalog_validations_tests.rs"]
+mod catalog_validations_tests;
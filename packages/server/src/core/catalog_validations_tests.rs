@@ -0,0 +1,183 @@
+use std::collections::{HashMap, HashSet};
+
+use crate::core::messages;
+use crate::core::types::{CreditOverflow, DoubleGroup, SpecializationGroup, SpecializationGroups};
+use crate::resources::catalog::Faculty;
+use crate::resources::course::CourseBank;
+
+use super::*;
+
+fn bank(name: &str, rule: Rule, credit: Option<f32>) -> CourseBank {
+    CourseBank {
+        name: name.to_string(),
+        rule,
+        credit,
+        constraints: None,
+    }
+}
+
+fn ids(ids: &[&str]) -> Vec<CourseId> {
+    ids.iter().map(|id| CourseId::new(*id)).collect()
+}
+
+fn groups(groups_number: usize, groups_type: SpecializationGroupsType) -> Rule {
+    Rule::SpecializationGroups(SpecializationGroups {
+        groups_list: vec![SpecializationGroup {
+            name: "ai".to_string(),
+            courses_sum: 2,
+            course_list: ids(&["02360501", "02360756"]),
+            mandatory: Some(vec![ids(&["02360501"])]),
+            double: None,
+        }],
+        groups_number,
+        groups_type,
+    })
+}
+
+fn catalog() -> Catalog {
+    Catalog {
+        id: bson::oid::ObjectId::new(),
+        name: "catalog".to_string(),
+        faculty: Faculty::Unknown,
+        total_credit: 20.5,
+        description: String::new(),
+        course_banks: vec![
+            bank("hova", Rule::All, Some(10.5)),
+            bank(
+                "chains",
+                Rule::Chains(vec![ids(&["01140051", "01140052"])]),
+                Some(8.0),
+            ),
+            bank("groups", groups(1, Default::default()), None),
+            bank("free", Rule::Elective, Some(2.0)),
+        ],
+        credit_overflows: vec![CreditOverflow {
+            from: "chains".to_string(),
+            to: "free".to_string(),
+        }],
+        course_to_bank: HashMap::from([
+            (CourseId::new("02340114"), "hova".to_string()),
+            (CourseId::new("01140051"), "chains".to_string()),
+            (CourseId::new("01140052"), "chains".to_string()),
+            (CourseId::new("02360501"), "groups".to_string()),
+            (CourseId::new("02360756"), "groups".to_string()),
+        ]),
+        catalog_replacements: HashMap::from([(CourseId::new("02340114"), ids(&["02340117"]))]),
+        common_replacements: HashMap::from([(CourseId::new("01040031"), ids(&["01040195"]))]),
+        prefix_courses: HashSet::new(),
+    }
+}
+
+#[test]
+fn valid_catalog() {
+    assert_eq!(validate_catalog(&catalog(), None), Ok(()));
+}
+
+#[test]
+fn reports_every_error() {
+    let mut catalog = catalog();
+    catalog.total_credit = 30.0;
+    catalog
+        .course_to_bank
+        .insert(CourseId::new("02340118"), "hova ".to_string());
+    catalog.course_to_bank.remove(&CourseId::new("01140052"));
+    catalog
+        .catalog_replacements
+        .insert(CourseId::new("00440252"), ids(&["02340252"]));
+    let mut rule = groups(2, Default::default());
+    if let Rule::SpecializationGroups(groups) = &mut rule {
+        groups.groups_list[0].double = Some(DoubleGroup {
+            courses_sum: 3,
+            mandatory: None,
+        });
+    }
+    catalog.course_banks[2].rule = rule;
+
+    assert_eq!(
+        validate_catalog(&catalog, None),
+        Err(vec![
+            CatalogError::UnknownBank {
+                course_id: CourseId::new("02340118"),
+                bank: "hova ".to_string(),
+            },
+            CatalogError::CourseNotInCatalog {
+                bank: "chains".to_string(),
+                course_id: CourseId::new("01140052"),
+            },
+            CatalogError::GroupsNumberTooLarge {
+                bank: "groups".to_string(),
+                groups_number: 2,
+                groups: 1,
+            },
+            CatalogError::DoubleGroupNotAllowed {
+                bank: "groups".to_string(),
+                group: "ai".to_string(),
+            },
+            CatalogError::CreditMismatch {
+                banks_credit: 20.5,
+                total_credit: 30.0,
+            },
+            CatalogError::UnknownReplacedCourse {
+                course_id: CourseId::new("00440252"),
+            },
+        ])
+    );
+
+    catalog.course_banks[2].rule = groups(1, SpecializationGroupsType::Double);
+    assert_eq!(validate_catalog(&catalog, None).unwrap_err().len(), 4);
+}
+
+#[test]
+fn credit_overflow_errors() {
+    let mut catalog = catalog();
+    catalog.credit_overflows.push(CreditOverflow {
+        from: "free".to_string(),
+        to: "chains".to_string(),
+    });
+    assert_eq!(
+        validate_catalog(&catalog, None),
+        Err(vec![CatalogError::CreditTransfer {
+            message: messages::cyclic_credit_transfer_graph("free"),
+        }])
+    );
+
+    // Unknown banks are reported by name rather than as a graph failure
+    catalog.credit_overflows = vec![CreditOverflow {
+        from: "hova".to_string(),
+        to: "malag".to_string(),
+    }];
+    assert_eq!(
+        validate_catalog(&catalog, None),
+        Err(vec![CatalogError::UnknownOverflowBank {
+            from: "hova".to_string(),
+            to: "malag".to_string(),
+        }])
+    );
+}
+
+#[test]
+fn checks_courses_against_known_courses() {
+    let catalog = catalog();
+    let mut known_courses = ["02340114", "01140051", "01140052", "02360501"]
+        .into_iter()
+        .map(CourseId::new)
+        .collect::<HashSet<_>>();
+
+    // Only the courses chains and specialization groups name are errors
+    assert_eq!(
+        validate_catalog(&catalog, Some(&known_courses)),
+        Err(vec![CatalogError::UnknownCourse {
+            course_id: CourseId::new("02360756"),
+        }])
+    );
+    assert_eq!(
+        unknown_courses(&catalog, &known_courses),
+        ids(&["01040031"])
+    );
+
+    // Replacement options may be discontinued courses
+    known_courses.insert(CourseId::new("02360756"));
+    known_courses.insert(CourseId::new("01040031"));
+    assert_eq!(validate_catalog(&catalog, Some(&known_courses)), Ok(()));
+    assert!(unknown_courses(&catalog, &known_courses).is_empty());
+}
@@ -0,0 +1,306 @@
+//! In-memory full-text search over the course list.
+//!
+//! Course names, ids, faculties and syllabi are split into normalized terms, so that a query matches
+//! regardless of niqqud, final letters or geresh. Every query term must match a term of the course,
+//! either fully or as a prefix, and courses are ranked by where their terms matched. Hebrew prefix
+//! letters are stripped when indexing, see `index_terms`.
+
+use std::collections::{BTreeMap, HashMap, HashSet};
+
+use crate::{
+    resources::course::{Course, CourseId, Tag},
+    sap::CourseDetails,
+};
+
+pub const DEFAULT_PAGE_SIZE: usize = 50;
+pub const MAX_PAGE_SIZE: usize = 200;
+
+// An exact term match is worth this many prefix matches.
+const EXACT_MATCH_FACTOR: f32 = 2.0;
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+enum Field {
+    Id,
+    Name,
+    Faculty,
+    Syllabus,
+}
+
+impl Field {
+    fn weight(self) -> f32 {
+        match self {
+            Field::Id => 8.0,
+            Field::Name => 4.0,
+            Field::Faculty => 2.0,
+            Field::Syllabus => 1.0,
+        }
+    }
+}
+
+fn final_letter(c: char) -> char {
+    match c {
+        'ך' => 'כ',
+        'ם' => 'מ',
+        'ן' => 'נ',
+        'ף' => 'פ',
+        'ץ' => 'צ',
+        _ => c,
+    }
+}
+
+/// Lowercases the text, drops niqqud, cantillation marks, geresh and gershayim, replaces final
+/// letters with their regular form and turns any other punctuation into spaces.
+pub fn normalize(text: &str) -> String {
+    let mut normalized = String::with_capacity(text.len());
+    for c in text.chars().flat_map(char::to_lowercase) {
+        match c {
+            // Maqaf, paseq, sof pasuq and nun hafukha separate words
+            '\u{05BE}' | '\u{05C0}' | '\u{05C3}' | '\u{05C6}' => normalized.push(' '),
+            // Niqqud and cantillation marks
+            '\u{0591}'..='\u{05C7}' => {}
+            '\u{05F3}' | '\u{05F4}' | '\'' | '"' | '`' | '’' => {}
+            c if c.is_alphanumeric() => normalized.push(final_letter(c)),
+            _ => normalized.push(' '),
+        }
+    }
+    normalized
+}
+
+fn terms(text: &str) -> Vec<String> {
+    normalize(text)
+        .split_whitespace()
+        .map(str::to_string)
+        .collect()
+}
+
+// Hebrew attaches prepositions, the definite article and conjunctions to the word, as in "למדעי
+// המחשב". The words are indexed with up to two such letters stripped as well, leaving at least
+// three letters, so that "מחשב" finds "המחשב". Some words merely start with these letters, which
+// only adds a few loose matches.
+const PREFIX_LETTERS: [char; 7] = ['ו', 'ה', 'ב', 'כ', 'ל', 'מ', 'ש'];
+const MIN_STRIPPED_LEN: usize = 3;
+
+fn index_terms(text: &str) -> Vec<String> {
+    let mut index_terms = Vec::new();
+    for term in terms(text) {
+        let mut stripped = term.as_str();
+        for _ in 0..2 {
+            let Some(rest) = stripped.strip_prefix(PREFIX_LETTERS) else {
+                break;
+            };
+            if rest.chars().count() < MIN_STRIPPED_LEN {
+                break;
+            }
+            index_terms.push(rest.to_string());
+            stripped = rest;
+        }
+        index_terms.push(term);
+    }
+    index_terms
+}
+
+/// A course as the search index sees it.
+#[derive(Clone, Debug, Default)]
+pub struct SearchEntry {
+    pub course: Course,
+    pub faculty: Option<String>,
+    pub syllabus: Option<String>,
+    // The semesters the course is offered in, as "{year}/{semester}" like the course cache
+    pub semesters: Vec<String>,
+}
+
+impl From<&CourseDetails> for SearchEntry {
+    fn from(details: &CourseDetails) -> Self {
+        SearchEntry {
+            course: Course::from(details),
+            faculty: details.faculty.clone(),
+            syllabus: details.syllabus.clone(),
+            semesters: Vec::new(),
+        }
+    }
+}
+
+#[derive(Clone, Debug, Default)]
+pub struct SearchQuery {
+    pub text: String,
+    pub faculty: Option<String>,
+    pub min_credit: Option<f32>,
+    pub max_credit: Option<f32>,
+    pub tags: Vec<Tag>,
+    // "{year}/{semester}", like `SearchEntry::semesters`
+    pub semester: Option<String>,
+    pub offset: usize,
+    pub limit: usize,
+}
+
+impl SearchQuery {
+    pub fn is_empty(&self) -> bool {
+        self.text.trim().is_empty()
+            && self.faculty.is_none()
+            && self.min_credit.is_none()
+            && self.max_credit.is_none()
+            && self.tags.is_empty()
+            && self.semester.is_none()
+    }
+}
+
+#[derive(Clone, Debug, Default)]
+pub struct SearchResults {
+    // How many courses matched, before pagination
+    pub total: usize,
+    pub courses: Vec<Course>,
+}
+
+#[derive(Default)]
+pub struct CourseSearchIndex {
+    entries: Vec<SearchEntry>,
+    // Term → the courses and fields it appears in. Ordered, so that prefixes are a range scan.
+    terms: BTreeMap<String, Vec<(usize, Field)>>,
+}
+
+impl CourseSearchIndex {
+    pub fn new(entries: impl IntoIterator<Item = SearchEntry>) -> Self {
+        let mut index = CourseSearchIndex::default();
+        for entry in entries {
+            let i = index.entries.len();
+            let mut postings = HashSet::new();
+            postings.insert((normalize(&entry.course.id), Field::Id));
+            for term in index_terms(&entry.course.name) {
+                postings.insert((term, Field::Name));
+            }
+            for term in entry
+                .faculty
+                .iter()
+                .flat_map(|faculty| index_terms(faculty))
+            {
+                postings.insert((term, Field::Faculty));
+            }
+            for term in entry
+                .syllabus
+                .iter()
+                .flat_map(|syllabus| index_terms(syllabus))
+            {
+                postings.insert((term, Field::Syllabus));
+            }
+            for (term, field) in postings {
+                index.terms.entry(term).or_default().push((i, field));
+            }
+            index.entries.push(entry);
+        }
+        index
+    }
+
+    pub fn len(&self) -> usize {
+        self.entries.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.entries.is_empty()
+    }
+
+    // The best score of every course matching a single query term
+    fn match_term(&self, term: &str) -> HashMap<usize, f32> {
+        let mut scores: HashMap<usize, f32> = HashMap::new();
+        let mut add = |i: usize, score: f32| {
+            let best = scores.entry(i).or_default();
+            *best = best.max(score);
+        };
+
+        // Course numbers are also matched in the middle and in their legacy 6 digit form
+        if term.chars().all(|c| c.is_ascii_digit()) {
+            let course_id = CourseId::new(term);
+            for (i, entry) in self.entries.iter().enumerate() {
+                let id: &str = &entry.course.id;
+                if id == &*course_id {
+                    add(i, Field::Id.weight() * EXACT_MATCH_FACTOR);
+                } else if id.contains(term) {
+                    add(i, Field::Id.weight());
+                }
+            }
+        }
+
+        for (indexed_term, postings) in self
+            .terms
+            .range(term.to_string()..)
+            .take_while(|(indexed_term, _)| indexed_term.starts_with(term))
+        {
+            let factor = if indexed_term == term {
+                EXACT_MATCH_FACTOR
+            } else {
+                1.0
+            };
+            for (i, field) in postings {
+                add(*i, field.weight() * factor);
+            }
+        }
+        scores
+    }
+
+    fn passes_filters(entry: &SearchEntry, query: &SearchQuery) -> bool {
+        if let Some(faculty) = &query.faculty {
+            let faculty = normalize(faculty);
+            if !entry
+                .faculty
+                .as_ref()
+                .is_some_and(|entry_faculty| normalize(entry_faculty).contains(faculty.trim()))
+            {
+                return false;
+            }
+        }
+        let credit = entry.course.credit;
+        if query.min_credit.is_some_and(|min| credit < min)
+            || query.max_credit.is_some_and(|max| credit > max)
+        {
+            return false;
+        }
+        let tags = entry.course.tags.as_deref().unwrap_or_default();
+        if !query.tags.iter().all(|tag| tags.contains(tag)) {
+            return false;
+        }
+        query
+            .semester
+            .as_ref()
+            .is_none_or(|semester| entry.semesters.contains(semester))
+    }
+
+    /// Finds the courses matching every term of the query text and all of its filters, best match
+    /// first. Without text, every course passing the filters matches, ordered by course number.
+    pub fn search(&self, query: &SearchQuery) -> SearchResults {
+        let mut scores: Option<HashMap<usize, f32>> = None;
+        for term in terms(&query.text) {
+            let term_scores = self.match_term(&term);
+            scores = Some(match scores {
+                None => term_scores,
+                Some(scores) => scores
+                    .into_iter()
+                    .filter_map(|(i, score)| Some((i, score + term_scores.get(&i)?)))
+                    .collect(),
+            });
+        }
+        let scores = scores.unwrap_or_else(|| (0..self.entries.len()).map(|i| (i, 0.0)).collect());
+
+        let mut matches = scores
+            .into_iter()
+            .filter(|(i, _)| Self::passes_filters(&self.entries[*i], query))
+            .collect::<Vec<_>>();
+        matches.sort_by(|(a, a_score), (b, b_score)| {
+            b_score
+                .total_cmp(a_score)
+                .then_with(|| self.entries[*a].course.id.cmp(&self.entries[*b].course.id))
+        });
+
+        SearchResults {
+            total: matches.len(),
+            courses: matches
+                .into_iter()
+                .skip(query.offset)
+                .take(query.limit)
+                .map(|(i, _)| self.entries[i].course.clone())
+                .collect(),
+        }
+    }
+}
+
+#[cfg(test)]
+#[path = "course_search_tests.rs"]
+mod course_search_tests;
@@ -0,0 +1,138 @@
+use super::*;
+
+fn entry(id: &str, name: &str, credit: f32, faculty: &str, semesters: &[&str]) -> SearchEntry {
+    SearchEntry {
+        course: Course {
+            id: CourseId::new(id),
+            credit,
+            name: name.to_string(),
+            tags: None,
+            relations: Vec::new(),
+        },
+        faculty: Some(faculty.to_string()),
+        syllabus: None,
+        semesters: semesters
+            .iter()
+            .map(|semester| semester.to_string())
+            .collect(),
+    }
+}
+
+fn index() -> CourseSearchIndex {
+    let mut english = entry(
+        "02340124",
+        "מבוא לתכנות מערכות",
+        4.0,
+        "מדעי המחשב",
+        &["2024/200"],
+    );
+    english.course.tags = Some(vec![Tag::English]);
+    english.syllabus = Some("C, C++, ניהול זיכרון".to_string());
+    CourseSearchIndex::new([
+        entry(
+            "02340114",
+            "מבוא למדעי המחשב מ'",
+            4.0,
+            "מדעי המחשב",
+            &["2024/200", "2024/201"],
+        ),
+        english,
+        entry(
+            "01040031",
+            "חשבון אינפיניטסימלי 1מ'",
+            5.5,
+            "מתמטיקה",
+            &["2024/201"],
+        ),
+        entry("01040166", "אלגברה אמ'", 5.5, "מתמטיקה", &["2024/200"]),
+    ])
+}
+
+fn query(text: &str) -> SearchQuery {
+    SearchQuery {
+        text: text.to_string(),
+        limit: DEFAULT_PAGE_SIZE,
+        ..Default::default()
+    }
+}
+
+fn ids(results: &SearchResults) -> Vec<String> {
+    results
+        .courses
+        .iter()
+        .map(|course| course.id.to_string())
+        .collect()
+}
+
+#[test]
+fn normalizes_hebrew() {
+    assert_eq!(normalize("מַדְּעֵי הַמַּחְשֵׁב"), "מדעי המחשב");
+    assert_eq!(normalize("חשבון אינפי' 1מ׳"), "חשבונ אינפי 1מ");
+    assert_eq!(normalize("דו״ח תלת־שנתי"), "דוח תלת שנתי");
+    assert_eq!(normalize("Intro-To C++"), "intro to c  ");
+}
+
+#[test]
+fn matches_prefixes_of_every_term() {
+    let index = index();
+
+    // The first course matches "מדעי" in its name, the second only in its faculty
+    assert_eq!(
+        ids(&index.search(&query("מבוא מדע"))),
+        vec!["02340114", "02340124"]
+    );
+    // Final letters and niqqud in the query don't matter
+    assert_eq!(
+        ids(&index.search(&query("מָבוֹא לתכנות מער"))),
+        vec!["02340124"]
+    );
+    assert_eq!(ids(&index.search(&query("חשבונ"))), vec!["01040031"]);
+    assert_eq!(ids(&index.search(&query("פיזיקה"))).len(), 0);
+}
+
+#[test]
+fn ranks_better_matches_first() {
+    let index = index();
+
+    // The name match beats the faculty match
+    assert_eq!(
+        ids(&index.search(&query("מחשב"))),
+        vec!["02340114", "02340124"]
+    );
+    // Course numbers match exactly, in their legacy form and in the middle
+    assert_eq!(ids(&index.search(&query("234114"))), vec!["02340114"]);
+    assert_eq!(ids(&index.search(&query("4011"))), vec!["02340114"]);
+    assert_eq!(
+        ids(&index.search(&query("0104"))),
+        vec!["01040031", "01040166"]
+    );
+    // Syllabus terms count too
+    assert_eq!(ids(&index.search(&query("זיכרון"))), vec!["02340124"]);
+}
+
+#[test]
+fn filters_and_pages() {
+    let index = index();
+
+    let results = index.search(&SearchQuery {
+        faculty: Some("מתמטיקה".to_string()),
+        semester: Some("2024/200".to_string()),
+        ..query("")
+    });
+    assert_eq!(ids(&results), vec!["01040166"]);
+
+    let results = index.search(&SearchQuery {
+        tags: vec![Tag::English],
+        ..query("מבוא")
+    });
+    assert_eq!(ids(&results), vec!["02340124"]);
+
+    let results = index.search(&SearchQuery {
+        min_credit: Some(5.0),
+        offset: 1,
+        limit: 1,
+        ..query("")
+    });
+    assert_eq!(results.total, 2);
+    assert_eq!(ids(&results), vec!["01040166"]);
+}
@@ -1,4 +1,4 @@
-use std::collections::HashMap;
+use std::collections::{HashMap, HashSet};
 
 use crate::core::types::Rule;
 use crate::resources::catalog::Catalog;
@@ -12,6 +12,7 @@ fn bank(name: &str, credit: Option<f32>) -> CourseBank {
         name: name.to_string(),
         rule: Rule::All,
         credit,
+        constraints: None,
     }
 }
 
@@ -27,6 +28,7 @@ fn catalog(course_banks: Vec<CourseBank>, credit_overflows: Vec<CreditOverflow>)
         course_to_bank: HashMap::new(),
         catalog_replacements: HashMap::new(),
         common_replacements: HashMap::new(),
+        prefix_courses: HashSet::new(),
     }
 }
 
@@ -0,0 +1,124 @@
+use std::collections::{BTreeMap, HashMap};
+
+use serde::{Deserialize, Serialize};
+
+use crate::resources::course::{AcademicSemester, CourseStatus, Grade};
+
+use super::DegreeStatus;
+
+#[derive(Default, Clone, Debug, Deserialize, Serialize, PartialEq)]
+pub struct SemesterAverage {
+    pub semester: AcademicSemester,
+    pub average: f32,
+    pub credit: f32,
+}
+
+#[derive(Default, Clone, Debug, Deserialize, Serialize, PartialEq)]
+pub struct CourseBankAverage {
+    pub course_bank_name: String,
+    pub average: f32,
+    pub credit: f32,
+}
+
+#[derive(Default, Clone, Debug, Deserialize, Serialize, PartialEq)]
+pub struct Averages {
+    // None until the student has a numeric grade
+    pub cumulative: Option<f32>,
+    // The credit the cumulative average is weighted by
+    pub credit: f32,
+    pub semesters: Vec<SemesterAverage>,
+    pub course_banks: Vec<CourseBankAverage>,
+}
+
+#[derive(Default)]
+struct WeightedSum {
+    sum: f32,
+    credit: f32,
+}
+
+impl WeightedSum {
+    fn add(&mut self, grade: u32, credit: f32) {
+        self.sum += grade as f32 * credit;
+        self.credit += credit;
+    }
+
+    fn average(&self) -> Option<f32> {
+        (self.credit > 0.0).then(|| self.sum / self.credit)
+    }
+}
+
+// Only numeric grades count towards an average: binary grades and exemptions carry no grade, and
+// courses without credit carry no weight.
+fn numeric_grade(course_status: &CourseStatus) -> Option<u32> {
+    match course_status.grade {
+        Some(Grade::Numeric(grade)) if course_status.course.credit > 0.0 => Some(grade),
+        _ => None,
+    }
+}
+
+impl DegreeStatus {
+    /// Computes the credit-weighted averages the way the Technion does: the cumulative and the course
+    /// bank averages only count the latest attempt of every course, while a semester average counts
+    /// every grade received in that semester, including attempts that were retaken later.
+    pub fn compute_averages(&self) -> Averages {
+        let mut cumulative = WeightedSum::default();
+        let mut semesters: BTreeMap<i32, (AcademicSemester, WeightedSum)> = BTreeMap::new();
+        let mut course_banks: HashMap<&str, WeightedSum> = HashMap::new();
+
+        for course_status in &self.course_statuses {
+            let Some(grade) = numeric_grade(course_status) else {
+                continue;
+            };
+            let credit = course_status.course.credit;
+            if let Some(semester) = &course_status.semester {
+                semesters
+                    .entry(semester.order_key())
+                    .or_insert_with(|| (semester.clone(), WeightedSum::default()))
+                    .1
+                    .add(grade, credit);
+            }
+            if course_status.is_repetition {
+                continue;
+            }
+            cumulative.add(grade, credit);
+            if let Some(bank_name) = &course_status.r#type {
+                course_banks
+                    .entry(bank_name)
+                    .or_default()
+                    .add(grade, credit);
+            }
+        }
+
+        let mut course_banks = course_banks
+            .into_iter()
+            .filter_map(|(course_bank_name, sum)| {
+                Some(CourseBankAverage {
+                    course_bank_name: course_bank_name.to_string(),
+                    average: sum.average()?,
+                    credit: sum.credit,
+                })
+            })
+            .collect::<Vec<_>>();
+        course_banks.sort_by(|a, b| a.course_bank_name.cmp(&b.course_bank_name));
+
+        Averages {
+            cumulative: cumulative.average(),
+            credit: cumulative.credit,
+            semesters: semesters
+                .into_values()
+                .filter_map(|(semester, sum)| {
+                    Some(SemesterAverage {
+                        semester,
+                        average: sum.average()?,
+                        credit: sum.credit,
+                    })
+                })
+                .collect(),
+            course_banks,
+        }
+    }
+}
+
+#[cfg(test)]
+#[path = "averages_tests.rs"]
+mod averages_tests;
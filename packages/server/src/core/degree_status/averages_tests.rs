@@ -0,0 +1,166 @@
+use std::collections::{HashMap, HashSet};
+
+use crate::{
+    core::types::Rule,
+    resources::{
+        catalog::{Catalog, Faculty},
+        course::*,
+    },
+};
+
+use super::*;
+
+fn course_status(id: &str, credit: f32, grade: Grade, semester: AcademicSemester) -> CourseStatus {
+    CourseStatus {
+        course: Course {
+            id: CourseId::new(id),
+            credit,
+            name: id.to_string(),
+            tags: None,
+            relations: Vec::new(),
+        },
+        state: Some(CourseState::Complete),
+        semester: Some(semester),
+        grade: Some(grade),
+        ..Default::default()
+    }
+}
+
+fn winter(year: i32) -> AcademicSemester {
+    AcademicSemester::new(SemesterSeason::Winter, year)
+}
+
+fn spring(year: i32) -> AcademicSemester {
+    AcademicSemester::new(SemesterSeason::Spring, year)
+}
+
+fn catalog() -> Catalog {
+    Catalog {
+        id: bson::oid::ObjectId::new(),
+        name: "catalog".to_string(), // no year in the name → English requirement is skipped
+        faculty: Faculty::Unknown,
+        total_credit: 0.0,
+        description: String::new(),
+        course_banks: vec![
+            CourseBank {
+                name: "hova".to_string(),
+                rule: Rule::All,
+                credit: Some(8.0),
+                constraints: None,
+            },
+            CourseBank {
+                name: "reshima".to_string(),
+                rule: Rule::AccumulateCredit,
+                credit: Some(10.0),
+                constraints: None,
+            },
+        ],
+        credit_overflows: vec![],
+        course_to_bank: HashMap::from([
+            (CourseId::new("h1"), "hova".to_string()),
+            (CourseId::new("h2"), "hova".to_string()),
+            (CourseId::new("r1"), "reshima".to_string()),
+            (CourseId::new("r2"), "reshima".to_string()),
+        ]),
+        catalog_replacements: HashMap::new(),
+        common_replacements: HashMap::new(),
+        prefix_courses: HashSet::new(),
+    }
+}
+
+fn compute(course_statuses: Vec<CourseStatus>) -> DegreeStatus {
+    let mut degree_status = DegreeStatus {
+        course_statuses,
+        ..Default::default()
+    };
+    degree_status.compute(catalog(), HashMap::new());
+    degree_status
+}
+
+#[test]
+fn averages_are_weighted_by_credit() {
+    let degree_status = compute(vec![
+        course_status("h1", 4.0, Grade::Numeric(90), winter(2022)),
+        course_status("h2", 4.0, Grade::Numeric(70), spring(2022)),
+        course_status("r1", 2.0, Grade::Numeric(100), spring(2022)),
+    ]);
+
+    let averages = &degree_status.averages;
+    assert_eq!(averages.cumulative, Some(84.0));
+    assert_eq!(averages.credit, 10.0);
+    assert_eq!(
+        averages.semesters,
+        vec![
+            SemesterAverage {
+                semester: winter(2022),
+                average: 90.0,
+                credit: 4.0,
+            },
+            SemesterAverage {
+                semester: spring(2022),
+                average: 80.0,
+                credit: 6.0,
+            },
+        ]
+    );
+    assert_eq!(
+        averages.course_banks,
+        vec![
+            CourseBankAverage {
+                course_bank_name: "hova".to_string(),
+                average: 80.0,
+                credit: 8.0,
+            },
+            CourseBankAverage {
+                course_bank_name: "reshima".to_string(),
+                average: 100.0,
+                credit: 2.0,
+            },
+        ]
+    );
+}
+
+#[test]
+fn only_the_latest_attempt_counts_towards_the_cumulative_average() {
+    let degree_status = compute(vec![
+        course_status("h1", 4.0, Grade::Numeric(40), winter(2022)),
+        course_status("h1", 4.0, Grade::Numeric(80), winter(2023)),
+    ]);
+
+    let averages = &degree_status.averages;
+    assert_eq!(averages.cumulative, Some(80.0));
+    assert_eq!(averages.credit, 4.0);
+    // The failed attempt still counts towards the average of the semester it was taken in
+    assert_eq!(averages.semesters.len(), 2);
+    assert_eq!(averages.semesters[0].average, 40.0);
+    assert_eq!(averages.course_banks[0].average, 80.0);
+}
+
+#[test]
+fn binary_grades_and_exemptions_are_excluded() {
+    let degree_status = compute(vec![
+        course_status("h1", 4.0, Grade::Numeric(75), winter(2022)),
+        course_status("h2", 4.0, Grade::ExemptionWithCredit, winter(2022)),
+        course_status("r1", 2.0, Grade::Binary(true), winter(2022)),
+        course_status("r2", 2.0, Grade::ExemptionWithoutCredit, winter(2022)),
+    ]);
+
+    let averages = &degree_status.averages;
+    assert_eq!(averages.cumulative, Some(75.0));
+    assert_eq!(averages.credit, 4.0);
+    assert_eq!(averages.semesters[0].credit, 4.0);
+    // A bank without numeric grades has no average
+    assert_eq!(averages.course_banks.len(), 1);
+}
+
+#[test]
+fn no_numeric_grades_has_no_average() {
+    let degree_status = compute(vec![course_status(
+        "h1",
+        4.0,
+        Grade::Binary(true),
+        winter(2022),
+    )]);
+
+    assert_eq!(degree_status.averages, Averages::default());
+}
@@ -1,4 +1,4 @@
-use crate::core::types::Requirement;
+use crate::core::types::{Requirement, Transfer};
 use crate::{
     core::{bank_rule::BankRuleHandler, messages, types::Rule},
     resources::course::{CourseBank, CourseId},
@@ -15,6 +15,11 @@ impl DegreeStatusHandler<'_> {
         missing_credit_from_prev_banks: f32,
         courses_overflow: usize,
     ) {
+        let hidden = match bank.constraints.as_ref().and_then(|c| c.min_grade) {
+            Some(min_grade) => self.hide_below_min_grade(&bank.name, min_grade),
+            None => Vec::new(),
+        };
+        let catalog = self.catalog;
         let bank_rule_handler = BankRuleHandler {
             degree_status: self.degree_status,
             bank_name: bank.name.clone(),
@@ -47,6 +52,12 @@ impl DegreeStatusHandler<'_> {
                 if missing_credit > 0.0 {
                     self.missing_credit_map
                         .insert(bank.name.clone(), missing_credit);
+                    self.degree_status.record_transfer(
+                        Transfer::MissingCredit,
+                        &bank.name,
+                        None,
+                        missing_credit,
+                    );
                 }
             }
             Rule::AccumulateCredit => sum_credit = bank_rule_handler.accumulate_credit(),
@@ -87,8 +98,23 @@ impl DegreeStatusHandler<'_> {
                     specialization_groups.groups_number,
                 ));
             }
-            Rule::Wildcard(_) => {
-                sum_credit = 0.0; // TODO: change this
+            Rule::Wildcard(outside_faculty) => {
+                // Credit missing in previous banks raises the requirement, so the bank takes more
+                let bank_credit = bank
+                    .credit
+                    .map(|credit| credit + missing_credit_from_prev_banks);
+                sum_credit = bank_rule_handler.wildcard(catalog, bank_credit, outside_faculty);
+            }
+        }
+
+        if let Some(constraints) = &bank.constraints {
+            let (excess_credit, satisfied, msgs) =
+                self.check_bank_constraints(&bank.name, constraints, hidden);
+            sum_credit -= excess_credit;
+            completed &= satisfied;
+            if !msgs.is_empty() {
+                let msgs = requirement.message.take().into_iter().chain(msgs);
+                requirement.message(msgs.collect::<Vec<_>>().join("\n"));
             }
         }
 
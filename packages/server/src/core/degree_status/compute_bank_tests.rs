@@ -1,8 +1,11 @@
-use std::collections::HashMap;
+use std::collections::{HashMap, HashSet};
 
 use crate::{
-    core::degree_status::DegreeStatus,
-    resources::catalog::{Catalog, Faculty},
+    core::{degree_status::DegreeStatus, types::CreditOverflow},
+    resources::{
+        catalog::{Catalog, Faculty},
+        course::{Course, CourseState, CourseStatus, Grade},
+    },
 };
 
 use super::*;
@@ -19,6 +22,7 @@ fn catalog() -> Catalog {
         course_to_bank: HashMap::new(),
         catalog_replacements: HashMap::new(),
         common_replacements: HashMap::new(),
+        prefix_courses: HashSet::new(),
     }
 }
 
@@ -40,6 +44,7 @@ fn compute_bank_wildcard_creates_uncompleted_requirement() {
         name: "wild".to_string(),
         rule: Rule::Wildcard(false),
         credit: Some(2.0),
+        constraints: None,
     };
 
     handler.compute_bank(bank, vec![], 0.0, 0.0, 0);
@@ -52,3 +57,130 @@ fn compute_bank_wildcard_creates_uncompleted_requirement() {
     assert_eq!(requirement.credit_completed, 0.0);
     assert!(!requirement.completed);
 }
+
+#[test]
+fn compute_bank_wildcard_overflows_past_credit_requirement() {
+    let completed_course = |id: &str, credit: f32| CourseStatus {
+        course: Course {
+            id: CourseId::new(id),
+            credit,
+            ..Default::default()
+        },
+        state: Some(CourseState::Complete),
+        grade: Some(Grade::Numeric(90)),
+        ..Default::default()
+    };
+    let mut degree_status = DegreeStatus {
+        course_statuses: vec![
+            completed_course("02340114", 3.0),
+            completed_course("02360501", 3.0),
+            completed_course("02360756", 3.0),
+        ],
+        ..Default::default()
+    };
+    let catalog = catalog();
+    let mut handler = DegreeStatusHandler {
+        degree_status: &mut degree_status,
+        course_banks: vec![],
+        catalog: &catalog,
+        courses: HashMap::new(),
+        credit_overflow_map: HashMap::new(),
+        missing_credit_map: HashMap::new(),
+        courses_overflow_map: HashMap::new(),
+    };
+
+    let bank = CourseBank {
+        name: "wild".to_string(),
+        rule: Rule::Wildcard(true),
+        credit: Some(2.0),
+        constraints: None,
+    };
+
+    // 2 missing credit from previous banks raise the requirement to 4, so the second course
+    // completes it and its extra credit overflows. The third course is left for other banks.
+    handler.compute_bank(bank, vec![], 0.0, 2.0, 0);
+
+    let requirement = &handler.degree_status.course_bank_requirements[0];
+    assert_eq!(requirement.credit_requirement, Some(4.0));
+    assert_eq!(requirement.credit_completed, 4.0);
+    assert!(requirement.completed);
+    assert_eq!(handler.credit_overflow_map.get("wild"), Some(&2.0));
+    assert_eq!(handler.degree_status.course_statuses[2].r#type, None);
+}
+
+#[test]
+fn wildcard_takes_faculty_courses_outside_the_catalog() {
+    let completed_course = |id: &str| CourseStatus {
+        course: Course {
+            id: CourseId::new(id),
+            credit: 2.0,
+            ..Default::default()
+        },
+        state: Some(CourseState::Complete),
+        grade: Some(Grade::Numeric(90)),
+        ..Default::default()
+    };
+    let bank = |name: &str, rule| CourseBank {
+        name: name.to_string(),
+        rule,
+        credit: Some(4.0),
+        constraints: None,
+    };
+    let catalog = Catalog {
+        faculty: Faculty::ComputerScience,
+        course_banks: vec![
+            bank("wild", Rule::Wildcard(false)),
+            bank("reshima a", Rule::AccumulateCredit),
+        ],
+        credit_overflows: vec![CreditOverflow {
+            from: "wild".to_string(),
+            to: "reshima a".to_string(),
+        }],
+        course_to_bank: HashMap::from([(CourseId::new("02360343"), "reshima a".to_string())]),
+        ..catalog()
+    };
+    let mut degree_status = DegreeStatus {
+        course_statuses: vec![
+            completed_course("02360501"),
+            completed_course("01040031"),
+            completed_course("02360756"),
+            completed_course("02360766"),
+        ],
+        ..Default::default()
+    };
+
+    // Preprocessing puts the faculty courses in the default accumulate bank, "reshima a", but
+    // that's no claim of another bank: the wildcard bank comes first and takes them up to its
+    // credit, leaving the third to "reshima a".
+    degree_status.compute(catalog, HashMap::new());
+
+    let types = degree_status
+        .course_statuses
+        .iter()
+        .map(|course_status| course_status.r#type.as_deref())
+        .collect::<Vec<_>>();
+    assert_eq!(
+        types
+            .iter()
+            .filter(|r#type| **r#type == Some("wild"))
+            .count(),
+        2
+    );
+    assert_eq!(
+        types
+            .iter()
+            .filter(|r#type| **r#type == Some("reshima a"))
+            .count(),
+        1
+    );
+    let outside_faculty = degree_status
+        .course_statuses
+        .iter()
+        .find(|course_status| *course_status.course.id == *"01040031")
+        .unwrap();
+    assert_eq!(outside_faculty.r#type, None);
+    let requirement = &degree_status.course_bank_requirements[0];
+    assert_eq!(requirement.course_bank_name, "wild");
+    assert_eq!(requirement.credit_completed, 4.0);
+    assert!(requirement.completed);
+}
@@ -1,4 +1,4 @@
-use std::collections::HashMap;
+use std::collections::{HashMap, HashSet};
 
 use crate::{
     core::degree_status::DegreeStatus,
@@ -16,6 +16,7 @@ fn bank(name: &str, credit: Option<f32>) -> CourseBank {
         name: name.to_string(),
         rule: Rule::Wildcard(false),
         credit,
+        constraints: None,
     }
 }
 
@@ -34,6 +35,7 @@ fn catalog() -> Catalog {
         course_to_bank: HashMap::new(),
         catalog_replacements: HashMap::new(),
         common_replacements: HashMap::new(),
+        prefix_courses: HashSet::new(),
     }
 }
 
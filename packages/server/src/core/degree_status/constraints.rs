@@ -0,0 +1,125 @@
+use crate::{
+    core::{
+        messages,
+        types::{BankConstraints, Transfer},
+    },
+    resources::course::{CourseState, Grade},
+};
+
+use super::DegreeStatusHandler;
+
+impl DegreeStatusHandler<'_> {
+    // Courses graded below the bank's minimum grade are treated as not completed while the bank's
+    // rule runs, so they don't count toward it. Returns their indices for `check_bank_constraints`,
+    // which completes them again.
+    pub fn hide_below_min_grade(&mut self, bank_name: &str, min_grade: u32) -> Vec<usize> {
+        self.degree_status
+            .course_statuses
+            .iter_mut()
+            .enumerate()
+            .filter(|(_, course_status)| {
+                course_status.completed()
+                    && course_status.valid_for_bank(bank_name)
+                    && matches!(course_status.grade, Some(Grade::Numeric(grade)) if grade < min_grade)
+            })
+            .map(|(index, course_status)| {
+                course_status.state = Some(CourseState::NotComplete);
+                index
+            })
+            .collect()
+    }
+
+    // Checks the constraints against the courses the bank took. Credit above a credit cap doesn't
+    // count toward the bank and moves on as a credit overflow. Returns that credit, whether the
+    // course count constraints hold, and a message per constraint worth reporting.
+    pub fn check_bank_constraints(
+        &mut self,
+        bank_name: &str,
+        constraints: &BankConstraints,
+        hidden: Vec<usize>,
+    ) -> (f32, bool, Vec<String>) {
+        // The hidden courses are still not completed here, so they count toward no constraint
+        let taken = self
+            .degree_status
+            .course_statuses
+            .iter()
+            .filter(|course_status| {
+                course_status.r#type.as_deref() == Some(bank_name) && course_status.completed()
+            })
+            .collect::<Vec<_>>();
+
+        let mut msgs = Vec::new();
+        let mut satisfied = true;
+        for constraint in &constraints.min_courses {
+            let completed = taken
+                .iter()
+                .filter(|course_status| constraint.course_list.contains(&course_status.course.id))
+                .count();
+            satisfied &= completed >= constraint.count;
+            msgs.push(messages::min_courses_constraint_msg(
+                &constraint.name,
+                constraint.count,
+                completed,
+            ));
+        }
+
+        let mut excess_credit = 0.0;
+        for constraint in &constraints.max_credit {
+            let credit = taken
+                .iter()
+                .filter(|course_status| {
+                    constraint
+                        .prefixes
+                        .iter()
+                        .any(|prefix| course_status.course.id.starts_with(prefix.as_str()))
+                })
+                .filter_map(|course_status| course_status.credit())
+                .sum::<f32>();
+            if credit > constraint.credit {
+                let excess = credit - constraint.credit;
+                excess_credit += excess;
+                msgs.push(messages::max_credit_constraint_msg(
+                    &constraint.name,
+                    constraint.credit,
+                    excess,
+                ));
+            }
+        }
+        let mut below_min_grade = Vec::new();
+        for index in hidden {
+            let course_status = &mut self.degree_status.course_statuses[index];
+            course_status.state = Some(CourseState::Complete);
+            if let Some(min_grade) = constraints.min_grade {
+                if course_status.r#type.as_deref() == Some(bank_name) {
+                    course_status.set_msg(messages::course_below_min_grade_msg(min_grade));
+                    below_min_grade.push(course_status.course.name.clone());
+                }
+            }
+        }
+        if let (Some(min_grade), false) = (constraints.min_grade, below_min_grade.is_empty()) {
+            msgs.insert(
+                0,
+                messages::min_grade_constraint_msg(min_grade, &below_min_grade),
+            );
+        }
+
+        if excess_credit > 0.0 {
+            *self
+                .credit_overflow_map
+                .entry(bank_name.to_string())
+                .or_default() += excess_credit;
+            self.degree_status.record_transfer(
+                Transfer::CreditOverflow,
+                bank_name,
+                None,
+                excess_credit,
+            );
+        }
+
+        (excess_credit, satisfied, msgs)
+    }
+}
+
+#[cfg(test)]
+#[path = "constraints_tests.rs"]
+mod constraints_tests;
@@ -0,0 +1,222 @@
+use std::collections::HashMap;
+
+use crate::{
+    core::{
+        degree_status::DegreeStatus,
+        types::{CourseCountConstraint, CreditCapConstraint, Rule},
+    },
+    resources::{
+        catalog::Catalog,
+        course::{Course, CourseBank, CourseId, CourseStatus},
+    },
+};
+
+use super::*;
+
+fn course_status(id: &str, grade: u32) -> CourseStatus {
+    CourseStatus {
+        course: Course {
+            id: CourseId::new(id),
+            credit: 3.0,
+            name: id.to_string(),
+            tags: None,
+            relations: Vec::new(),
+        },
+        state: Some(CourseState::Complete),
+        grade: Some(Grade::Numeric(grade)),
+        ..Default::default()
+    }
+}
+
+fn ids(ids: &[&str]) -> Vec<CourseId> {
+    ids.iter().map(|id| CourseId::new(*id)).collect()
+}
+
+// Computes a single bank whose catalog courses are `course_list`.
+fn compute(
+    course_statuses: Vec<CourseStatus>,
+    bank: CourseBank,
+    course_list: Vec<CourseId>,
+) -> (DegreeStatus, HashMap<String, f32>) {
+    let mut degree_status = DegreeStatus {
+        course_statuses,
+        ..Default::default()
+    };
+    let catalog = Catalog::default();
+    let mut handler = DegreeStatusHandler {
+        degree_status: &mut degree_status,
+        course_banks: vec![],
+        catalog: &catalog,
+        courses: HashMap::new(),
+        credit_overflow_map: HashMap::new(),
+        missing_credit_map: HashMap::new(),
+        courses_overflow_map: HashMap::new(),
+    };
+    handler.compute_bank(bank, course_list, 0.0, 0.0, 0);
+    let credit_overflow_map = handler.credit_overflow_map;
+    (degree_status, credit_overflow_map)
+}
+
+#[test]
+fn courses_below_min_grade_do_not_count() {
+    let bank = CourseBank {
+        name: "hova".to_string(),
+        rule: Rule::All,
+        credit: Some(6.0),
+        constraints: Some(BankConstraints {
+            min_grade: Some(70),
+            ..Default::default()
+        }),
+    };
+    let (degree_status, _) = compute(
+        vec![course_status("02340114", 65), course_status("02340118", 90)],
+        bank,
+        ids(&["02340114", "02340118"]),
+    );
+
+    let requirement = &degree_status.course_bank_requirements[0];
+    assert_eq!(requirement.credit_completed, 3.0);
+    assert!(!requirement.completed);
+    assert_eq!(
+        requirement.message,
+        Some(messages::min_grade_constraint_msg(
+            70,
+            &["02340114".to_string()]
+        ))
+    );
+
+    // The course is still passed, it just doesn't count toward this bank
+    let below_min_grade = &degree_status.course_statuses[0];
+    assert_eq!(below_min_grade.state, Some(CourseState::Complete));
+    assert_eq!(below_min_grade.r#type, Some("hova".to_string()));
+    assert_eq!(
+        below_min_grade.additional_msg,
+        Some(messages::course_below_min_grade_msg(70))
+    );
+    assert_eq!(degree_status.course_statuses[1].additional_msg, None);
+}
+
+#[test]
+fn min_courses_from_sub_list() {
+    let bank = |count| CourseBank {
+        name: "list b".to_string(),
+        rule: Rule::AccumulateCredit,
+        credit: Some(6.0),
+        constraints: Some(BankConstraints {
+            min_courses: vec![CourseCountConstraint {
+                name: "labs".to_string(),
+                course_list: ids(&["02360340", "02360341", "02360342"]),
+                count,
+            }],
+            ..Default::default()
+        }),
+    };
+    let course_statuses = vec![
+        course_status("02360340", 90),
+        course_status("02360501", 90),
+        course_status("02360756", 90),
+    ];
+    let course_list = ids(&["02360340", "02360341", "02360342", "02360501", "02360756"]);
+
+    // Enough credit, but only one lab out of the two required
+    let (degree_status, _) = compute(course_statuses.clone(), bank(2), course_list.clone());
+    let requirement = &degree_status.course_bank_requirements[0];
+    assert_eq!(requirement.credit_completed, 6.0);
+    assert!(!requirement.completed);
+    assert_eq!(
+        requirement.message,
+        Some(messages::min_courses_constraint_msg("labs", 2, 1))
+    );
+
+    let (degree_status, _) = compute(course_statuses, bank(1), course_list);
+    assert!(degree_status.course_bank_requirements[0].completed);
+}
+
+#[test]
+fn credit_above_cap_overflows() {
+    let bank = CourseBank {
+        name: "free".to_string(),
+        rule: Rule::Elective,
+        credit: Some(10.0),
+        constraints: Some(BankConstraints {
+            max_credit: vec![CreditCapConstraint {
+                name: "קורסים מחוץ לפקולטה".to_string(),
+                prefixes: vec!["01".to_string()],
+                credit: 4.0,
+            }],
+            ..Default::default()
+        }),
+    };
+    let (degree_status, credit_overflow_map) = compute(
+        vec![
+            course_status("02340114", 90),
+            course_status("01040031", 90),
+            course_status("01140051", 90),
+        ],
+        bank,
+        vec![],
+    );
+
+    let requirement = &degree_status.course_bank_requirements[0];
+    assert_eq!(requirement.credit_completed, 7.0);
+    assert!(!requirement.completed);
+    assert_eq!(
+        requirement.message,
+        Some(messages::max_credit_constraint_msg(
+            "קורסים מחוץ לפקולטה",
+            4.0,
+            2.0
+        ))
+    );
+    assert_eq!(credit_overflow_map.get("free"), Some(&2.0));
+}
+
+#[test]
+fn courses_below_min_grade_count_toward_no_constraint() {
+    let bank = CourseBank {
+        name: "list b".to_string(),
+        rule: Rule::AccumulateCredit,
+        credit: Some(6.0),
+        constraints: Some(BankConstraints {
+            min_grade: Some(70),
+            min_courses: vec![CourseCountConstraint {
+                name: "labs".to_string(),
+                course_list: ids(&["02360340", "02360341"]),
+                count: 2,
+            }],
+            max_credit: vec![CreditCapConstraint {
+                name: "קורסים מחוץ לפקולטה".to_string(),
+                prefixes: vec!["01".to_string()],
+                credit: 3.0,
+            }],
+        }),
+    };
+    let (degree_status, credit_overflow_map) = compute(
+        vec![
+            course_status("02360340", 95),
+            course_status("02360341", 60),
+            course_status("01040031", 90),
+            course_status("01140051", 55),
+        ],
+        bank,
+        ids(&["02360340", "02360341", "01040031", "01140051"]),
+    );
+
+    let requirement = &degree_status.course_bank_requirements[0];
+    assert_eq!(requirement.credit_completed, 6.0);
+    assert!(!requirement.completed);
+    assert_eq!(
+        requirement.message,
+        Some(
+            [
+                messages::min_grade_constraint_msg(
+                    70,
+                    &["02360341".to_string(), "01140051".to_string()]
+                ),
+                messages::min_courses_constraint_msg("labs", 2, 1),
+            ]
+            .join("\n")
+        )
+    );
+    assert_eq!(credit_overflow_map.get("list b"), None);
+}
@@ -1,8 +1,16 @@
+pub mod averages;
 pub mod compute_bank;
 pub mod compute_status;
+pub mod constraints;
 pub mod overflow;
+pub mod planner;
 pub mod postprocessing;
 pub mod preprocessing;
+pub mod relations;
+pub mod secondary;
+pub mod simulation;
+pub mod timeline;
+pub mod trace;
 
 use std::collections::{HashMap, HashSet};
 
@@ -13,12 +21,20 @@ use crate::resources::{
 };
 use serde::{Deserialize, Serialize};
 
+use averages::Averages;
+use trace::AssignmentTrace;
+
 #[derive(Default, Clone, Debug, Deserialize, Serialize)]
 pub struct DegreeStatus {
     pub course_statuses: Vec<CourseStatus>,
     pub course_bank_requirements: Vec<Requirement>,
     pub overflow_msgs: Vec<String>,
     pub total_credit: f32,
+    #[serde(default)]
+    pub averages: Averages,
+    // Recorded only when set before computing, see `AssignmentTrace`
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub trace: Option<AssignmentTrace>,
 }
 
 impl DegreeStatus {
@@ -192,10 +208,11 @@ impl DegreeStatus {
     pub fn compute(&mut self, mut catalog: Catalog, mut courses: HashMap<CourseId, Course>) {
         self.preprocess(&mut catalog, &mut courses);
 
-        // Extract social courses and superseded retake attempts, then remove them so they don't
-        // affect the compute status logic; both are restored afterward for display.
+        // Extract social courses, superseded retake attempts and courses which overlap others, then
+        // remove them so they don't affect the compute status logic; all are restored afterward for display.
         let social_courses = self.extract_social_courses();
         let repetitions = self.extract_repetitions();
+        let overlapping_courses = self.extract_overlapping_courses(&catalog, &courses);
 
         let course_banks = catalog.get_bank_traversal_order();
 
@@ -211,6 +228,11 @@ impl DegreeStatus {
         .compute_status();
 
         self.course_statuses.extend(social_courses);
+        self.course_statuses
+            .extend(overlapping_courses.into_iter().map(|mut overlapping| {
+                overlapping.no_additional_credit = true;
+                overlapping
+            }));
         self.course_statuses
             .extend(repetitions.into_iter().map(|mut repetition| {
                 repetition.is_repetition = true;
@@ -218,6 +240,8 @@ impl DegreeStatus {
             }));
 
         self.postprocess(&catalog);
+        self.averages = self.compute_averages();
+        self.explain_courses(&catalog);
     }
 }
 
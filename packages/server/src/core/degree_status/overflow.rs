@@ -23,6 +23,12 @@ impl DegreeStatusHandler<'_> {
                 .entry(bank.name.clone())
                 .and_modify(|bank_overflow_item| *bank_overflow_item += sum_credit - bank_credit)
                 .or_insert(sum_credit - bank_credit);
+            self.degree_status.record_transfer(
+                Transfer::CreditOverflow,
+                &bank.name,
+                None,
+                sum_credit - bank_credit,
+            );
 
             self.degree_status.total_credit += bank_credit;
             bank_credit
@@ -40,6 +46,12 @@ impl DegreeStatusHandler<'_> {
         } else {
             self.courses_overflow_map
                 .insert(bank.name.clone(), (count_courses - num_courses) as f32);
+            self.degree_status.record_transfer(
+                Transfer::CoursesOverflow,
+                &bank.name,
+                None,
+                (count_courses - num_courses) as f32,
+            );
             num_courses
         }
     }
@@ -80,6 +92,8 @@ impl DegreeStatusHandler<'_> {
             if let Some(msg) = msg {
                 self.degree_status.overflow_msgs.push(msg);
             }
+            self.degree_status
+                .record_transfer(transfer, &rule.from, Some(&rule.to), *overflow);
             sum += *overflow;
             *overflow = 0.0;
         }
@@ -1,4 +1,4 @@
-use std::collections::HashMap;
+use std::collections::{HashMap, HashSet};
 
 use crate::{
     core::degree_status::DegreeStatus,
@@ -23,11 +23,13 @@ fn make_catalog() -> Catalog {
                 name: "from".to_string(),
                 rule: Rule::All,
                 credit: Some(4.0),
+                constraints: None,
             },
             CourseBank {
                 name: "to".to_string(),
                 rule: Rule::All,
                 credit: Some(4.0),
+                constraints: None,
             },
         ],
         credit_overflows: vec![CreditOverflow {
@@ -37,6 +39,7 @@ fn make_catalog() -> Catalog {
         course_to_bank: HashMap::new(),
         catalog_replacements: HashMap::new(),
         common_replacements: HashMap::new(),
+        prefix_courses: HashSet::new(),
     }
 }
 
@@ -0,0 +1,361 @@
+use std::collections::{HashMap, HashSet};
+
+use serde::{Deserialize, Serialize};
+
+use crate::{
+    core::types::{Requirement, Rule, SpecializationGroup, SpecializationGroups},
+    resources::{
+        catalog::Catalog,
+        course::{Course, CourseBank, CourseId},
+    },
+};
+
+use super::DegreeStatus;
+
+#[derive(Clone, Debug, Default, Deserialize, Serialize)]
+pub struct SuggestedCourse {
+    pub course: Course,
+    // The bank the course was counted in once the plan was applied
+    pub course_bank_name: Option<String>,
+}
+
+// A requirement the planner could not close with concrete courses (e.g. free electives, malag, or a
+// bank whose course list ran out).
+#[derive(Clone, Debug, Default, Deserialize, Serialize)]
+pub struct OpenRequirement {
+    pub course_bank_name: String,
+    pub credit_missing: f32,
+    pub courses_missing: usize,
+}
+
+#[derive(Clone, Debug, Default, Deserialize, Serialize)]
+pub struct DegreePlan {
+    pub suggested_courses: Vec<SuggestedCourse>,
+    pub open_requirements: Vec<OpenRequirement>,
+    // The requirements as they would look after taking every suggested course
+    pub course_bank_requirements: Vec<Requirement>,
+    pub total_credit: f32,
+    pub total_credit_missing: f32,
+}
+
+// Planning state shared between the rounds of `DegreeStatus::plan`.
+struct Planner<'a> {
+    catalog: &'a Catalog,
+    courses: &'a HashMap<CourseId, Course>,
+    // Courses the student completed, plus the ones suggested so far
+    unavailable: HashSet<CourseId>,
+}
+
+impl Planner<'_> {
+    fn credit(&self, course_id: &CourseId) -> f32 {
+        self.courses
+            .get(course_id)
+            .map(|course| course.credit)
+            .unwrap_or_default()
+    }
+
+    fn available(&self, course_id: &CourseId) -> bool {
+        !self.unavailable.contains(course_id) && self.courses.contains_key(course_id)
+    }
+
+    // The bank's own courses followed by the courses of every bank overflowing into it, each part
+    // ordered by credit (highest first) so that fewer courses are needed.
+    fn pool(&self, bank_name: &str) -> Vec<CourseId> {
+        let mut pool = Vec::new();
+        let mut visited = HashSet::new();
+        let mut banks = vec![bank_name.to_string()];
+        while !banks.is_empty() {
+            let mut next = Vec::new();
+            for bank in banks {
+                if !visited.insert(bank.clone()) {
+                    continue;
+                }
+                let mut course_list = self
+                    .catalog
+                    .get_course_list(&bank)
+                    .into_iter()
+                    .filter(|course_id| self.available(course_id) && !pool.contains(course_id))
+                    .collect::<Vec<_>>();
+                self.sort_by_credit(&mut course_list);
+                pool.extend(course_list);
+                next.extend(
+                    self.catalog
+                        .credit_overflows
+                        .iter()
+                        .filter(|overflow| overflow.to == bank)
+                        .map(|overflow| overflow.from.clone()),
+                );
+            }
+            banks = next;
+        }
+        pool
+    }
+
+    fn sort_by_credit(&self, course_list: &mut [CourseId]) {
+        course_list.sort_by(|c1, c2| {
+            self.credit(c2)
+                .total_cmp(&self.credit(c1))
+                .then_with(|| c1.cmp(c2))
+        });
+    }
+
+    fn fill_credit(&self, bank_name: &str, credit_missing: f32) -> Vec<CourseId> {
+        let mut picked = Vec::new();
+        let mut credit = 0.0;
+        for course_id in self.pool(bank_name) {
+            if credit >= credit_missing {
+                break;
+            }
+            let course_credit = self.credit(&course_id);
+            if course_credit <= 0.0 {
+                continue;
+            }
+            credit += course_credit;
+            picked.push(course_id);
+        }
+        picked
+    }
+
+    fn fill_courses(&self, bank_name: &str, courses_missing: usize) -> Vec<CourseId> {
+        self.pool(bank_name)
+            .into_iter()
+            .take(courses_missing)
+            .collect()
+    }
+
+    // The chain that is closest to completion.
+    fn pick_chain(&self, chains: &[Vec<CourseId>]) -> Vec<CourseId> {
+        chains
+            .iter()
+            .filter_map(|chain| {
+                let missing = chain
+                    .iter()
+                    .filter(|course_id| !self.unavailable.contains(*course_id))
+                    .cloned()
+                    .collect::<Vec<_>>();
+                // A chain with a course we know nothing about can't be suggested
+                missing
+                    .iter()
+                    .all(|course_id| self.courses.contains_key(course_id))
+                    .then_some(missing)
+            })
+            .filter(|missing| !missing.is_empty())
+            .min_by(|m1, m2| {
+                let credit = |missing: &Vec<CourseId>| -> f32 {
+                    missing.iter().map(|course_id| self.credit(course_id)).sum()
+                };
+                m1.len()
+                    .cmp(&m2.len())
+                    .then_with(|| credit(m1).total_cmp(&credit(m2)))
+            })
+            .unwrap_or_default()
+    }
+
+    // The courses that complete `group`, given the courses completed in the bank.
+    fn complete_group(
+        &self,
+        group: &SpecializationGroup,
+        bank_completed: &HashSet<CourseId>,
+    ) -> Option<Vec<CourseId>> {
+        let mut picked: Vec<CourseId> = Vec::new();
+        let mut used = HashSet::new();
+        for sublist in group.mandatory.iter().flatten() {
+            if let Some(course_id) = sublist
+                .iter()
+                .find(|course_id| bank_completed.contains(*course_id) && !used.contains(*course_id))
+            {
+                used.insert(course_id.clone());
+                continue;
+            }
+            let mut candidates = sublist
+                .iter()
+                .filter(|course_id| self.available(course_id) && !picked.contains(course_id))
+                .cloned()
+                .collect::<Vec<_>>();
+            candidates.sort_by(|c1, c2| self.credit(c1).total_cmp(&self.credit(c2)));
+            let course_id = candidates.into_iter().next()?;
+            used.insert(course_id.clone());
+            picked.push(course_id);
+        }
+
+        let completed_in_group = group
+            .course_list
+            .iter()
+            .filter(|course_id| bank_completed.contains(*course_id))
+            .count();
+        let mut courses_missing = group
+            .courses_sum
+            .saturating_sub(completed_in_group + picked.len());
+        let mut course_list = group
+            .course_list
+            .iter()
+            .filter(|course_id| self.available(course_id) && !picked.contains(course_id))
+            .cloned()
+            .collect::<Vec<_>>();
+        self.sort_by_credit(&mut course_list);
+        for course_id in course_list {
+            if courses_missing == 0 {
+                break;
+            }
+            picked.push(course_id);
+            courses_missing -= 1;
+        }
+        (courses_missing == 0).then_some(picked)
+    }
+
+    // The cheapest group which still needs courses. One group is picked per round; the engine then
+    // decides whether it was enough.
+    fn pick_specialization_group(
+        &self,
+        sgs: &SpecializationGroups,
+        bank_completed: &HashSet<CourseId>,
+    ) -> Vec<CourseId> {
+        sgs.groups_list
+            .iter()
+            .filter_map(|group| self.complete_group(group, bank_completed))
+            .filter(|picked| !picked.is_empty())
+            .min_by_key(|picked| picked.len())
+            .unwrap_or_default()
+    }
+
+    fn pick(
+        &self,
+        bank: &CourseBank,
+        requirement: &Requirement,
+        degree_status: &DegreeStatus,
+    ) -> Vec<CourseId> {
+        let credit_missing = (requirement.credit_requirement.unwrap_or_default()
+            - requirement.credit_completed)
+            .max(0.0);
+        let courses_missing = requirement
+            .course_requirement
+            .unwrap_or_default()
+            .saturating_sub(requirement.course_completed);
+
+        let picked = match &bank.rule {
+            // The engine already lists the missing courses of the bank (after replacements)
+            Rule::All => degree_status
+                .course_statuses
+                .iter()
+                .filter(|cs| {
+                    cs.r#type.as_deref() == Some(bank.name.as_str())
+                        && !cs.completed()
+                        && self.available(&cs.course.id)
+                })
+                .map(|cs| cs.course.id.clone())
+                .collect(),
+            Rule::AccumulateCourses(_) => self.fill_courses(&bank.name, courses_missing),
+            Rule::Chains(chains) => self.pick_chain(chains),
+            Rule::SpecializationGroups(sgs) => {
+                let bank_completed = degree_status
+                    .get_all_completed_courses_for_bank(&bank.name)
+                    .into_iter()
+                    .collect();
+                self.pick_specialization_group(sgs, &bank_completed)
+            }
+            _ => Vec::new(),
+        };
+
+        if picked.is_empty() {
+            // Either the rule itself is satisfied and only credit is missing, or the rule is about
+            // credit in the first place.
+            self.fill_credit(&bank.name, credit_missing)
+        } else {
+            picked
+        }
+    }
+}
+
+impl DegreeStatus {
+    /// Suggests a small set of courses which, once completed, closes every unfinished requirement.
+    /// Banks are planned in the catalog's traversal order, and the full computation is re-run after
+    /// each step so that overflows between banks are taken into account.
+    /// `self` is left untouched.
+    pub fn plan(&self, catalog: Catalog, courses: HashMap<CourseId, Course>) -> DegreePlan {
+        let mut planner = Planner {
+            catalog: &catalog,
+            courses: &courses,
+            unavailable: self
+                .course_statuses
+                .iter()
+                .filter(|cs| cs.completed())
+                .map(|cs| cs.course.id.clone())
+                .collect(),
+        };
+        let course_banks = catalog.get_bank_traversal_order();
+
+        let mut suggested: Vec<CourseId> = Vec::new();
+        let mut stuck: HashSet<String> = HashSet::new();
+        let mut current = self.clone();
+        current.compute(catalog.clone(), courses.clone());
+
+        loop {
+            let next = course_banks.iter().find_map(|bank| {
+                current
+                    .course_bank_requirements
+                    .iter()
+                    .find(|r| r.course_bank_name == bank.name)
+                    .filter(|r| !r.completed && !stuck.contains(&bank.name))
+                    .map(|r| (bank, r))
+            });
+            let Some((bank, requirement)) = next else {
+                break;
+            };
+
+            let picked = planner.pick(bank, requirement, &current);
+            if picked.is_empty() {
+                stuck.insert(bank.name.clone());
+                continue;
+            }
+            planner.unavailable.extend(picked.iter().cloned());
+            suggested.extend(picked);
+
+            current = self.clone();
+            for course_id in &suggested {
+                current.add_planned_course(course_id, None, &courses);
+            }
+            current.compute(catalog.clone(), courses.clone());
+        }
+
+        let open_requirements = current
+            .course_bank_requirements
+            .iter()
+            .filter(|r| !r.completed)
+            .map(|r| OpenRequirement {
+                course_bank_name: r.course_bank_name.clone(),
+                credit_missing: (r.credit_requirement.unwrap_or_default() - r.credit_completed)
+                    .max(0.0),
+                courses_missing: r
+                    .course_requirement
+                    .unwrap_or_default()
+                    .saturating_sub(r.course_completed),
+            })
+            .collect();
+
+        let suggested_courses = suggested
+            .iter()
+            .filter_map(|course_id| {
+                current
+                    .course_statuses
+                    .iter()
+                    .find(|cs| cs.course.id == *course_id && !cs.is_repetition)
+            })
+            .map(|cs| SuggestedCourse {
+                course: cs.course.clone(),
+                course_bank_name: cs.r#type.clone(),
+            })
+            .collect();
+
+        DegreePlan {
+            suggested_courses,
+            open_requirements,
+            total_credit_missing: (catalog.total_credit as f32 - current.total_credit).max(0.0),
+            total_credit: current.total_credit,
+            course_bank_requirements: current.course_bank_requirements,
+        }
+    }
+}
+
+#[cfg(test)]
+#[path = "planner_tests.rs"]
+mod planner_tests;
@@ -0,0 +1,266 @@
+use std::collections::{HashMap, HashSet};
+
+use crate::{
+    core::types::{CreditOverflow, Rule, SpecializationGroup, SpecializationGroups},
+    resources::{
+        catalog::{Catalog, Faculty},
+        course::*,
+    },
+};
+
+use super::*;
+
+fn course(id: &str, credit: f32) -> Course {
+    Course {
+        id: CourseId::new(id),
+        credit,
+        name: id.to_string(),
+        tags: None,
+        relations: Vec::new(),
+    }
+}
+
+fn completed(id: &str, credit: f32) -> CourseStatus {
+    CourseStatus {
+        course: course(id, credit),
+        state: Some(CourseState::Complete),
+        semester: Some(AcademicSemester::new(SemesterSeason::Winter, 2023)),
+        grade: Some(Grade::Numeric(90)),
+        ..Default::default()
+    }
+}
+
+fn bank(name: &str, rule: Rule, credit: Option<f32>) -> CourseBank {
+    CourseBank {
+        name: name.to_string(),
+        rule,
+        credit,
+        constraints: None,
+    }
+}
+
+// Builds a catalog and the course details from (course id, credit, bank name) triples.
+fn catalog(
+    course_banks: Vec<CourseBank>,
+    credit_overflows: Vec<CreditOverflow>,
+    catalog_courses: &[(&str, f32, &str)],
+) -> (Catalog, HashMap<CourseId, Course>) {
+    let catalog = Catalog {
+        id: bson::oid::ObjectId::new(),
+        name: "catalog".to_string(),
+        faculty: Faculty::Unknown,
+        total_credit: course_banks
+            .iter()
+            .filter_map(|bank| bank.credit)
+            .sum::<f32>() as f64,
+        description: String::new(),
+        course_banks,
+        credit_overflows,
+        course_to_bank: catalog_courses
+            .iter()
+            .map(|(id, _, bank_name)| (CourseId::new(*id), bank_name.to_string()))
+            .collect(),
+        catalog_replacements: HashMap::new(),
+        common_replacements: HashMap::new(),
+        prefix_courses: HashSet::new(),
+    };
+    let courses = catalog_courses
+        .iter()
+        .map(|(id, credit, _)| (CourseId::new(*id), course(id, *credit)))
+        .collect();
+    (catalog, courses)
+}
+
+fn suggested_ids(plan: &DegreePlan) -> Vec<String> {
+    let mut ids = plan
+        .suggested_courses
+        .iter()
+        .map(|suggested| suggested.course.name.clone())
+        .collect::<Vec<_>>();
+    ids.sort();
+    ids
+}
+
+#[test]
+fn plan_completes_mandatory_courses_and_missing_credit() {
+    let (catalog, courses) = catalog(
+        vec![
+            bank("hova", Rule::All, Some(6.0)),
+            bank("list a", Rule::AccumulateCredit, Some(4.0)),
+        ],
+        vec![],
+        &[
+            ("h1", 3.0, "hova"),
+            ("h2", 3.0, "hova"),
+            ("a1", 2.0, "list a"),
+            ("a2", 1.0, "list a"),
+            ("a3", 3.0, "list a"),
+        ],
+    );
+    let degree_status = DegreeStatus {
+        course_statuses: vec![completed("h1", 3.0)],
+        ..Default::default()
+    };
+
+    let plan = degree_status.plan(catalog, courses);
+
+    // The highest credit courses are picked first
+    assert_eq!(suggested_ids(&plan), vec!["a1", "a3", "h2"]);
+    assert!(plan.open_requirements.is_empty());
+    assert!(plan.course_bank_requirements.iter().all(|r| r.completed));
+    assert_eq!(plan.total_credit_missing, 0.0);
+    // The stored status is untouched
+    assert_eq!(degree_status.course_statuses.len(), 1);
+}
+
+#[test]
+fn plan_picks_the_chain_closest_to_completion() {
+    let chains = vec![
+        vec![CourseId::new("c1"), CourseId::new("c2")],
+        vec![
+            CourseId::new("c3"),
+            CourseId::new("c4"),
+            CourseId::new("c5"),
+        ],
+    ];
+    let (catalog, courses) = catalog(
+        vec![bank("science", Rule::Chains(chains), None)],
+        vec![],
+        &[
+            ("c1", 3.0, "science"),
+            ("c2", 3.0, "science"),
+            ("c3", 3.0, "science"),
+            ("c4", 3.0, "science"),
+            ("c5", 3.0, "science"),
+        ],
+    );
+    let degree_status = DegreeStatus {
+        course_statuses: vec![completed("c3", 3.0), completed("c4", 3.0)],
+        ..Default::default()
+    };
+
+    let plan = degree_status.plan(catalog, courses);
+
+    assert_eq!(suggested_ids(&plan), vec!["c5"]);
+    assert!(plan.open_requirements.is_empty());
+}
+
+#[test]
+fn plan_respects_specialization_group_mandatory_courses() {
+    let sgs = SpecializationGroups {
+        groups_list: vec![
+            SpecializationGroup {
+                name: "g1".to_string(),
+                courses_sum: 2,
+                course_list: vec![
+                    CourseId::new("s1"),
+                    CourseId::new("s2"),
+                    CourseId::new("s3"),
+                ],
+                mandatory: Some(vec![vec![CourseId::new("s1")]]),
+                ..Default::default()
+            },
+            SpecializationGroup {
+                name: "g2".to_string(),
+                courses_sum: 2,
+                course_list: vec![CourseId::new("s4"), CourseId::new("s5")],
+                ..Default::default()
+            },
+        ],
+        groups_number: 1,
+        ..Default::default()
+    };
+    let (catalog, courses) = catalog(
+        vec![bank("groups", Rule::SpecializationGroups(sgs), None)],
+        vec![],
+        &[
+            ("s1", 3.0, "groups"),
+            ("s2", 3.0, "groups"),
+            ("s3", 4.0, "groups"),
+            ("s4", 3.0, "groups"),
+            ("s5", 3.0, "groups"),
+        ],
+    );
+    let degree_status = DegreeStatus {
+        course_statuses: vec![completed("s2", 3.0)],
+        ..Default::default()
+    };
+
+    let plan = degree_status.plan(catalog, courses);
+
+    // s3 has more credit but doesn't satisfy the mandatory requirement of g1
+    assert_eq!(suggested_ids(&plan), vec!["s1"]);
+    assert!(plan.open_requirements.is_empty());
+}
+
+#[test]
+fn plan_suggests_the_missing_number_of_courses() {
+    let (catalog, courses) = catalog(
+        vec![bank("projects", Rule::AccumulateCourses(3), None)],
+        vec![],
+        &[
+            ("p1", 3.0, "projects"),
+            ("p2", 3.0, "projects"),
+            ("p3", 3.0, "projects"),
+            ("p4", 3.0, "projects"),
+        ],
+    );
+    let degree_status = DegreeStatus {
+        course_statuses: vec![completed("p1", 3.0)],
+        ..Default::default()
+    };
+
+    let plan = degree_status.plan(catalog, courses);
+
+    assert_eq!(plan.suggested_courses.len(), 2);
+    assert!(plan.open_requirements.is_empty());
+}
+
+#[test]
+fn plan_fills_bank_through_credit_overflow() {
+    let (catalog, courses) = catalog(
+        vec![
+            bank("list a", Rule::AccumulateCredit, Some(2.0)),
+            bank("list b", Rule::AccumulateCredit, Some(4.0)),
+        ],
+        vec![CreditOverflow {
+            from: "list a".to_string(),
+            to: "list b".to_string(),
+        }],
+        &[
+            ("a1", 2.0, "list a"),
+            ("a2", 2.0, "list a"),
+            ("a3", 2.0, "list a"),
+        ],
+    );
+
+    let plan = DegreeStatus::default().plan(catalog, courses);
+
+    // "list b" has no courses of its own, so its credit comes from the overflow of "list a"
+    assert_eq!(suggested_ids(&plan), vec!["a1", "a2", "a3"]);
+    assert!(plan.open_requirements.is_empty());
+    assert!(plan.course_bank_requirements.iter().all(|r| r.completed));
+}
+
+#[test]
+fn plan_reports_requirements_without_courses_to_suggest() {
+    let (catalog, courses) = catalog(
+        vec![
+            bank("hova", Rule::All, Some(3.0)),
+            bank("elective", Rule::Elective, Some(4.0)),
+        ],
+        vec![CreditOverflow {
+            from: "hova".to_string(),
+            to: "elective".to_string(),
+        }],
+        &[("h1", 3.0, "hova")],
+    );
+
+    let plan = DegreeStatus::default().plan(catalog, courses);
+
+    assert_eq!(suggested_ids(&plan), vec!["h1"]);
+    assert_eq!(plan.open_requirements.len(), 1);
+    assert_eq!(plan.open_requirements[0].course_bank_name, "elective");
+    assert_eq!(plan.open_requirements[0].credit_missing, 4.0);
+    assert_eq!(plan.total_credit_missing, 4.0);
+}
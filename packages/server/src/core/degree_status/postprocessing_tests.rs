@@ -1,4 +1,4 @@
-use std::collections::HashMap;
+use std::collections::{HashMap, HashSet};
 
 use crate::{
     consts::{self, medicine},
@@ -18,6 +18,7 @@ fn english_course(id: &str, grade: Grade) -> CourseStatus {
             credit: 2.0,
             name: id.to_string(),
             tags: Some(vec![Tag::English]),
+            relations: Vec::new(),
         },
         state: Some(CourseState::Complete),
         grade: Some(grade),
@@ -32,6 +33,7 @@ fn regular_course(id: &str, bank: &str, grade: Grade, repeats: usize) -> CourseS
             credit: 3.0,
             name: id.to_string(),
             tags: None,
+            relations: Vec::new(),
         },
         state: Some(CourseState::Complete),
         grade: Some(grade),
@@ -54,6 +56,7 @@ fn catalog(name: &str, faculty: Faculty, course_banks: Vec<CourseBank>) -> Catal
         course_to_bank: HashMap::new(),
         catalog_replacements: HashMap::new(),
         common_replacements: HashMap::new(),
+        prefix_courses: HashSet::new(),
     }
 }
 
@@ -101,6 +104,7 @@ fn medicine_postprocessing_reports_zero_average_when_no_numeric_grades() {
                 credit: 3.0,
                 name: "med".to_string(),
                 tags: None,
+                relations: Vec::new(),
             },
             state: Some(CourseState::Complete),
             grade: Some(Grade::Binary(true)),
@@ -117,16 +121,19 @@ fn medicine_postprocessing_reports_zero_average_when_no_numeric_grades() {
                 name: medicine::ALL_BANK_NAME.to_string(),
                 rule: Rule::All,
                 credit: Some(1.0),
+                constraints: None,
             },
             CourseBank {
                 name: medicine::SPORT_BANK_NAME.to_string(),
                 rule: Rule::All,
                 credit: Some(1.0),
+                constraints: None,
             },
             CourseBank {
                 name: medicine::FACULTY_ELECTIVE_BANK_NAME.to_string(),
                 rule: Rule::All,
                 credit: Some(1.0),
+                constraints: None,
             },
         ],
     );
@@ -157,16 +164,19 @@ fn medicine_postprocessing_flags_course_repetition_violations() {
                 name: medicine::ALL_BANK_NAME.to_string(),
                 rule: Rule::All,
                 credit: Some(1.0),
+                constraints: None,
             },
             CourseBank {
                 name: medicine::SPORT_BANK_NAME.to_string(),
                 rule: Rule::All,
                 credit: Some(1.0),
+                constraints: None,
             },
             CourseBank {
                 name: medicine::FACULTY_ELECTIVE_BANK_NAME.to_string(),
                 rule: Rule::All,
                 credit: Some(1.0),
+                constraints: None,
             },
         ],
     );
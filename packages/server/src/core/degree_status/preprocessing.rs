@@ -8,7 +8,7 @@ use crate::{
     },
 };
 
-use super::DegreeStatus;
+use super::{trace::AssignmentReason, DegreeStatus};
 
 impl DegreeStatus {
     // Courses that are not modified, not completed, and don't have a semester should be removed,
@@ -71,10 +71,15 @@ impl DegreeStatus {
         self.course_bank_requirements.clear();
         self.overflow_msgs.clear();
         self.total_credit = 0.0;
+        if let Some(trace) = &mut self.trace {
+            trace.courses.clear();
+            trace.transfers.clear();
+        }
 
-        self.course_statuses
-            .iter_mut()
-            .for_each(|course_status| course_status.is_repetition = false);
+        self.course_statuses.iter_mut().for_each(|course_status| {
+            course_status.is_repetition = false;
+            course_status.no_additional_credit = false;
+        });
 
         self.remove_courses_added_by_algorithm();
         self.remove_irrelevant_courses_added_by_user();
@@ -135,6 +140,21 @@ impl DegreeStatus {
         student_replacements
             .iter()
             .for_each(|(course_id, replacement)| {
+                if let Some(trace) = &mut self.trace {
+                    let in_catalog_replacements = catalog
+                        .catalog_replacements
+                        .get(course_id)
+                        .is_some_and(|replacements| replacements.contains(replacement));
+                    let replaces = course_id.clone();
+                    trace.record_origin(
+                        replacement.clone(),
+                        if in_catalog_replacements {
+                            AssignmentReason::CatalogReplacement { replaces }
+                        } else {
+                            AssignmentReason::CommonReplacement { replaces }
+                        },
+                    );
+                }
                 catalog.replace_courses(course_id, replacement);
             });
     }
@@ -166,8 +186,14 @@ impl DegreeStatus {
             .sort_by_key(|c1| c1.semester_order_key());
 
         self.replace_student_course_with_courses_in_catalog(catalog, courses);
+        self.replace_contained_courses(catalog, courses);
 
-        catalog.enrich_with_prefix_courses(&self.course_statuses);
+        let prefix_courses = catalog.enrich_with_prefix_courses(&self.course_statuses);
+        if let Some(trace) = &mut self.trace {
+            for course_id in prefix_courses {
+                trace.record_origin(course_id, AssignmentReason::FacultyPrefix);
+            }
+        }
     }
 }
 
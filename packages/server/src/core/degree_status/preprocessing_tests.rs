@@ -1,4 +1,4 @@
-use std::collections::HashMap;
+use std::collections::{HashMap, HashSet};
 
 use crate::{
     core::types::Rule,
@@ -23,6 +23,7 @@ fn cs(
             credit: 1.0,
             name: id.to_string(),
             tags: None,
+            relations: Vec::new(),
         },
         state,
         semester,
@@ -44,6 +45,7 @@ fn make_catalog() -> Catalog {
             name: "hova".to_string(),
             rule: Rule::All,
             credit: Some(1.0),
+            constraints: None,
         }],
         credit_overflows: vec![],
         course_to_bank: HashMap::from([
@@ -52,6 +54,7 @@ fn make_catalog() -> Catalog {
         ]),
         catalog_replacements: HashMap::new(),
         common_replacements: HashMap::new(),
+        prefix_courses: HashSet::new(),
     }
 }
 
@@ -0,0 +1,133 @@
+use std::collections::{HashMap, HashSet};
+
+use crate::{
+    core::messages,
+    resources::{
+        catalog::Catalog,
+        course::{Course, CourseId, CourseStatus},
+    },
+};
+
+use super::{trace::AssignmentReason, DegreeStatus};
+
+impl DegreeStatus {
+    // The course as the course list has it, since the student's transcript carries no relations
+    fn course_with_relations<'a>(
+        course_status: &'a CourseStatus,
+        courses: &'a HashMap<CourseId, Course>,
+    ) -> &'a Course {
+        courses
+            .get(&course_status.course.id)
+            .unwrap_or(&course_status.course)
+    }
+
+    // A completed course which contains a catalog course takes its place in the catalog, the same
+    // way a catalog replacement does. Each course takes the place of one catalog course at most.
+    pub(super) fn replace_contained_courses(
+        &mut self,
+        catalog: &mut Catalog,
+        courses: &HashMap<CourseId, Course>,
+    ) {
+        let mut catalog_courses = catalog
+            .course_to_bank
+            .keys()
+            .filter_map(|course_id| courses.get(course_id))
+            .collect::<Vec<_>>();
+        catalog_courses.sort_by(|a, b| a.id.cmp(&b.id));
+
+        let mut replaced = HashSet::new();
+        let mut replacements = Vec::new();
+        for course_status in self.course_statuses.iter_mut() {
+            if !course_status.completed()
+                || catalog
+                    .course_to_bank
+                    .contains_key(&course_status.course.id)
+            {
+                continue;
+            }
+            let course = Self::course_with_relations(course_status, courses);
+            let Some(contained) = catalog_courses
+                .iter()
+                .find(|contained| !replaced.contains(&contained.id) && course.contains(contained))
+            else {
+                continue;
+            };
+            replaced.insert(contained.id.clone());
+            replacements.push((contained.id.clone(), course_status.course.id.clone()));
+            course_status.set_msg(messages::contained_course_replacement_msg(contained));
+        }
+
+        for (course_id, replacement) in replacements {
+            if let Some(trace) = &mut self.trace {
+                trace.record_origin(
+                    replacement.clone(),
+                    AssignmentReason::ContainedCourse {
+                        replaces: course_id.clone(),
+                    },
+                );
+            }
+            catalog.replace_courses(&course_id, &replacement);
+        }
+    }
+
+    // Of two completed courses which overlap, only one grants credit. A course the catalog lists is
+    // preferred, then a course which contains the other, then the earlier one. The other course is
+    // taken out until the banks are computed, like a repetition, and the student is told why.
+    pub(super) fn extract_overlapping_courses(
+        &mut self,
+        catalog: &Catalog,
+        courses: &HashMap<CourseId, Course>,
+    ) -> Vec<CourseStatus> {
+        let in_catalog = |course: &Course| catalog.course_to_bank.contains_key(&course.id);
+        let mut kept: Vec<(usize, &Course)> = Vec::new();
+        let mut overlapping = HashMap::new();
+        let mut completed = self
+            .course_statuses
+            .iter()
+            .enumerate()
+            .filter(|(_, course_status)| course_status.completed())
+            .collect::<Vec<_>>();
+        completed.sort_by_key(|(_, course_status)| course_status.semester_order_key());
+        for (i, course_status) in completed {
+            let course = Self::course_with_relations(course_status, courses);
+            let Some(k) = kept
+                .iter()
+                .position(|(_, other)| other.id != course.id && other.overlaps(course))
+            else {
+                kept.push((i, course));
+                continue;
+            };
+            let (j, other) = kept[k];
+            let keep_new = match (in_catalog(course), in_catalog(other)) {
+                (true, false) => true,
+                (false, true) => false,
+                _ => course.contains(other),
+            };
+            if keep_new {
+                overlapping.insert(j, messages::no_additional_credit_msg(course));
+                kept[k] = (i, course);
+            } else {
+                overlapping.insert(i, messages::no_additional_credit_msg(other));
+            }
+        }
+
+        let mut extracted = Vec::new();
+        for (i, mut course_status) in std::mem::take(&mut self.course_statuses)
+            .into_iter()
+            .enumerate()
+        {
+            match overlapping.remove(&i) {
+                Some(msg) => {
+                    course_status.set_msg(msg);
+                    extracted.push(course_status);
+                }
+                None => self.course_statuses.push(course_status),
+            }
+        }
+        extracted
+    }
+}
+
+#[cfg(test)]
+#[path = "relations_tests.rs"]
+mod relations_tests;
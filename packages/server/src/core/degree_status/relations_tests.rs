@@ -0,0 +1,189 @@
+use crate::{
+    core::types::{CreditOverflow, Rule},
+    resources::{
+        catalog::Faculty,
+        course::{AcademicSemester, CourseBank, CourseState, Grade, SemesterSeason},
+    },
+    sap::{Relation, RelationType},
+};
+
+use super::*;
+
+fn course(id: &str, relations: &[(&str, RelationType)]) -> Course {
+    Course {
+        id: CourseId::new(id),
+        credit: 3.0,
+        name: id.to_string(),
+        tags: None,
+        relations: relations
+            .iter()
+            .map(|(course_id, relation_type)| Relation {
+                course_id: CourseId::new(*course_id),
+                relation_type: relation_type.clone(),
+            })
+            .collect(),
+    }
+}
+
+fn course_status(id: &str, start_year: i32) -> CourseStatus {
+    CourseStatus {
+        course: course(id, &[]),
+        state: Some(CourseState::Complete),
+        grade: Some(Grade::Numeric(90)),
+        semester: Some(AcademicSemester::new(SemesterSeason::Winter, start_year)),
+        ..Default::default()
+    }
+}
+
+fn bank(name: &str, rule: Rule) -> CourseBank {
+    CourseBank {
+        name: name.to_string(),
+        rule,
+        credit: Some(3.0),
+        constraints: None,
+    }
+}
+
+fn catalog() -> Catalog {
+    Catalog {
+        id: bson::oid::ObjectId::new(),
+        name: "catalog".to_string(),
+        faculty: Faculty::Unknown,
+        total_credit: 6.0,
+        description: String::new(),
+        course_banks: vec![bank("hova", Rule::All), bank("free", Rule::Elective)],
+        credit_overflows: vec![CreditOverflow {
+            from: "hova".to_string(),
+            to: "free".to_string(),
+        }],
+        course_to_bank: HashMap::from([(CourseId::new("02340114"), "hova".to_string())]),
+        catalog_replacements: HashMap::new(),
+        common_replacements: HashMap::new(),
+        prefix_courses: HashSet::new(),
+    }
+}
+
+fn compute(course_statuses: Vec<CourseStatus>, courses: Vec<Course>) -> DegreeStatus {
+    let mut degree_status = DegreeStatus {
+        course_statuses,
+        ..Default::default()
+    };
+    degree_status.compute(
+        catalog(),
+        courses
+            .into_iter()
+            .map(|course| (course.id.clone(), course))
+            .collect(),
+    );
+    degree_status
+}
+
+fn courses() -> Vec<Course> {
+    vec![
+        course("02340114", &[]),
+        course("02340124", &[("02340114", RelationType::Contains)]),
+        course("01040031", &[]),
+        course(
+            "01040036",
+            &[("01040031", RelationType::NoAdditionalCredit)],
+        ),
+    ]
+}
+
+#[test]
+fn containing_course_satisfies_catalog_course() {
+    let degree_status = compute(vec![course_status("02340124", 2020)], courses());
+
+    let containing = degree_status
+        .get_course_status(&CourseId::new("02340124"))
+        .unwrap();
+    assert_eq!(containing.r#type, Some("hova".to_string()));
+    assert_eq!(
+        containing.additional_msg,
+        Some(messages::contained_course_replacement_msg(&course(
+            "02340114",
+            &[]
+        )))
+    );
+    assert!(degree_status.course_bank_requirements[0].completed);
+    assert!(degree_status
+        .get_course_status(&CourseId::new("02340114"))
+        .is_none());
+}
+
+#[test]
+fn no_additional_credit_for_overlapping_courses() {
+    let degree_status = compute(
+        vec![
+            course_status("01040036", 2021),
+            course_status("01040031", 2020),
+        ],
+        courses(),
+    );
+
+    assert_eq!(degree_status.total_credit, 3.0);
+    assert_eq!(
+        degree_status
+            .get_course_status(&CourseId::new("01040031"))
+            .unwrap()
+            .r#type,
+        Some("free".to_string())
+    );
+    // The later course is kept for display but grants nothing
+    let overlapping = degree_status
+        .get_course_status(&CourseId::new("01040036"))
+        .unwrap();
+    assert_eq!(overlapping.r#type, None);
+    assert_eq!(
+        overlapping.additional_msg,
+        Some(messages::no_additional_credit_msg(&course("01040031", &[])))
+    );
+}
+
+#[test]
+fn containing_course_is_kept_over_contained_one() {
+    let degree_status = compute(
+        vec![
+            course_status("02340114", 2020),
+            course_status("02340124", 2021),
+        ],
+        courses(),
+    );
+
+    assert_eq!(
+        degree_status
+            .get_course_status(&CourseId::new("02340124"))
+            .unwrap()
+            .r#type,
+        Some("hova".to_string())
+    );
+    let contained = degree_status
+        .get_course_status(&CourseId::new("02340114"))
+        .unwrap();
+    assert_eq!(contained.r#type, None);
+    assert_eq!(contained.state, Some(CourseState::Complete));
+    assert_eq!(degree_status.total_credit, 3.0);
+}
+
+#[test]
+fn timeline_counts_overlapping_courses_once() {
+    let degree_status = compute(
+        vec![
+            course_status("01040031", 2020),
+            course_status("01040036", 2021),
+        ],
+        courses(),
+    );
+
+    assert!(
+        degree_status
+            .get_course_status(&CourseId::new("01040036"))
+            .unwrap()
+            .no_additional_credit
+    );
+    let timeline = degree_status.timeline(Some(6.0), &HashMap::new());
+    assert_eq!(timeline.len(), 2);
+    assert_eq!(timeline[1].credit_earned, 0.0);
+    assert_eq!(timeline[1].cumulative_credit, degree_status.total_credit);
+    assert_eq!(timeline[1].progress, Some(0.5));
+}
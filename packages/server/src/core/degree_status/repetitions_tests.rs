@@ -1,4 +1,4 @@
-use std::collections::HashMap;
+use std::collections::{HashMap, HashSet};
 
 use crate::{
     core::types::Rule,
@@ -31,6 +31,7 @@ fn course_status(
             credit,
             name: id.to_string(),
             tags: None,
+            relations: Vec::new(),
         },
         state,
         semester,
@@ -293,6 +294,7 @@ fn hova_catalog() -> Catalog {
             name: "hova".to_string(),
             rule: Rule::All,
             credit: Some(100.0),
+            constraints: None,
         }],
         credit_overflows: vec![],
         course_to_bank: HashMap::from([
@@ -301,6 +303,7 @@ fn hova_catalog() -> Catalog {
         ]),
         catalog_replacements: HashMap::new(),
         common_replacements: HashMap::new(),
+        prefix_courses: HashSet::new(),
     }
 }
 
@@ -471,11 +474,13 @@ fn sport_catalog() -> Catalog {
             name: "sport".to_string(),
             rule: Rule::Sport,
             credit: Some(2.0),
+            constraints: None,
         }],
         credit_overflows: vec![],
         course_to_bank: HashMap::new(),
         catalog_replacements: HashMap::new(),
         common_replacements: HashMap::new(),
+        prefix_courses: HashSet::new(),
     }
 }
 
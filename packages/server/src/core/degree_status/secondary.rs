@@ -0,0 +1,118 @@
+use std::collections::{HashMap, HashSet};
+
+use serde::{Deserialize, Serialize};
+
+use crate::resources::{
+    catalog::Catalog,
+    course::{Course, CourseId},
+};
+
+use super::DegreeStatus;
+
+/// A course which counts toward both the primary catalog and a secondary one.
+#[derive(Clone, Debug, Default, PartialEq, Deserialize, Serialize)]
+pub struct SharedCourse {
+    pub course_id: CourseId,
+    pub credit: f32,
+    pub primary_bank: String,
+    pub bank: String,
+}
+
+impl DegreeStatus {
+    // The bank each completed course counts toward
+    fn counted_banks(&self) -> HashMap<CourseId, String> {
+        self.course_statuses
+            .iter()
+            .filter(|course_status| course_status.completed() && !course_status.is_repetition)
+            .filter_map(|course_status| {
+                course_status
+                    .r#type
+                    .clone()
+                    .map(|bank| (course_status.course.id.clone(), bank))
+            })
+            .collect()
+    }
+
+    // In semester order, so the shared credit cap goes to the earlier courses
+    fn shared_courses(&self, primary_banks: &HashMap<CourseId, String>) -> Vec<SharedCourse> {
+        let mut course_statuses = self
+            .course_statuses
+            .iter()
+            .filter(|course_status| course_status.completed() && !course_status.is_repetition)
+            .collect::<Vec<_>>();
+        course_statuses.sort_by_key(|course_status| course_status.semester_order_key());
+        course_statuses
+            .into_iter()
+            .filter_map(|course_status| {
+                let bank = course_status.r#type.clone()?;
+                let primary_bank = primary_banks.get(&course_status.course.id)?.clone();
+                Some(SharedCourse {
+                    course_id: course_status.course.id.clone(),
+                    credit: course_status.course.credit,
+                    primary_bank,
+                    bank,
+                })
+            })
+            .collect()
+    }
+
+    /// Evaluates the transcript of an already computed primary degree status against a secondary
+    /// catalog (a minor or a second degree). Courses counted by the primary catalog may count here
+    /// too, but only up to `max_shared_credit`, and not at all without it; shared courses which
+    /// don't fit under the cap, in semester order, are left out of the secondary status. Returns
+    /// the secondary status and the courses both catalogs count.
+    pub fn compute_secondary(
+        primary: &DegreeStatus,
+        catalog: Catalog,
+        courses: HashMap<CourseId, Course>,
+        max_shared_credit: Option<f32>,
+    ) -> (DegreeStatus, Vec<SharedCourse>) {
+        let primary_banks = primary.counted_banks();
+        let max_shared_credit = max_shared_credit.unwrap_or_default();
+        let mut excluded = HashSet::new();
+        loop {
+            // The student's bank choices were made for the primary catalog
+            let mut degree_status = DegreeStatus {
+                course_statuses: primary
+                    .course_statuses
+                    .iter()
+                    .filter(|course_status| !excluded.contains(&course_status.course.id))
+                    .cloned()
+                    .map(|mut course_status| {
+                        course_status.r#type = None;
+                        course_status.modified = false;
+                        course_status.specialization_group_name = None;
+                        course_status.additional_msg = None;
+                        course_status
+                    })
+                    .collect(),
+                ..Default::default()
+            };
+            degree_status.compute(catalog.clone(), courses.clone());
+
+            let shared_courses = degree_status.shared_courses(&primary_banks);
+            let mut shared_credit = 0.0;
+            let over_cap = shared_courses
+                .iter()
+                .filter(|shared| {
+                    if shared_credit + shared.credit <= max_shared_credit {
+                        shared_credit += shared.credit;
+                        false
+                    } else {
+                        true
+                    }
+                })
+                .map(|shared| shared.course_id.clone())
+                .collect::<Vec<_>>();
+            if over_cap.is_empty() {
+                return (degree_status, shared_courses);
+            }
+            // Leaving courses out may let others take their place, so compute again
+            excluded.extend(over_cap);
+        }
+    }
+}
+
+#[cfg(test)]
+#[path = "secondary_tests.rs"]
+mod secondary_tests;
@@ -0,0 +1,183 @@
+use std::collections::{HashMap, HashSet};
+
+use crate::{
+    core::types::{CreditOverflow, Rule},
+    resources::{
+        catalog::{Catalog, Faculty},
+        course::{AcademicSemester, CourseBank, CourseState, CourseStatus, Grade, SemesterSeason},
+    },
+};
+
+use super::*;
+
+fn course_status(id: &str, start_year: i32) -> CourseStatus {
+    CourseStatus {
+        course: Course {
+            id: CourseId::new(id),
+            credit: 3.0,
+            name: id.to_string(),
+            tags: None,
+            relations: Vec::new(),
+        },
+        state: Some(CourseState::Complete),
+        grade: Some(Grade::Numeric(90)),
+        semester: Some(AcademicSemester::new(SemesterSeason::Winter, start_year)),
+        ..Default::default()
+    }
+}
+
+fn bank(name: &str, rule: Rule, credit: f32) -> CourseBank {
+    CourseBank {
+        name: name.to_string(),
+        rule,
+        credit: Some(credit),
+        constraints: None,
+    }
+}
+
+fn catalog(
+    course_banks: Vec<CourseBank>,
+    credit_overflows: Vec<CreditOverflow>,
+    course_to_bank: &[(&str, &str)],
+) -> Catalog {
+    Catalog {
+        id: bson::oid::ObjectId::new(),
+        name: "catalog".to_string(),
+        faculty: Faculty::Unknown,
+        total_credit: course_banks
+            .iter()
+            .filter_map(|bank| bank.credit)
+            .sum::<f32>() as f64,
+        description: String::new(),
+        course_banks,
+        credit_overflows,
+        course_to_bank: course_to_bank
+            .iter()
+            .map(|(course_id, bank)| (CourseId::new(*course_id), bank.to_string()))
+            .collect(),
+        catalog_replacements: HashMap::new(),
+        common_replacements: HashMap::new(),
+        prefix_courses: HashSet::new(),
+    }
+}
+
+// 02340114 and 02340118 are mandatory, the rest are electives.
+fn primary() -> DegreeStatus {
+    let mut primary = DegreeStatus {
+        course_statuses: vec![
+            course_status("02340114", 2020),
+            course_status("02340118", 2020),
+            course_status("01040031", 2021),
+            course_status("01140051", 2022),
+        ],
+        ..Default::default()
+    };
+    let catalog = catalog(
+        vec![
+            bank("hova", Rule::All, 6.0),
+            bank("free", Rule::Elective, 6.0),
+        ],
+        vec![CreditOverflow {
+            from: "hova".to_string(),
+            to: "free".to_string(),
+        }],
+        &[("02340114", "hova"), ("02340118", "hova")],
+    );
+    primary.compute(catalog, HashMap::new());
+    primary
+}
+
+fn minor() -> Catalog {
+    catalog(
+        vec![bank("minor", Rule::AccumulateCredit, 9.0)],
+        vec![],
+        &[
+            ("02340114", "minor"),
+            ("01140051", "minor"),
+            ("02360501", "minor"),
+        ],
+    )
+}
+
+fn shared(course_id: &str, primary_bank: &str) -> SharedCourse {
+    SharedCourse {
+        course_id: CourseId::new(course_id),
+        credit: 3.0,
+        primary_bank: primary_bank.to_string(),
+        bank: "minor".to_string(),
+    }
+}
+
+#[test]
+fn courses_count_toward_both_catalogs() {
+    let primary = primary();
+    let (minor_status, shared_courses) =
+        DegreeStatus::compute_secondary(&primary, minor(), HashMap::new(), Some(6.0));
+
+    assert_eq!(
+        shared_courses,
+        vec![shared("02340114", "hova"), shared("01140051", "free")]
+    );
+    assert_eq!(
+        minor_status.course_bank_requirements[0].credit_completed,
+        6.0
+    );
+    // The primary status is left as it was
+    assert_eq!(
+        primary
+            .get_course_status(&CourseId::new("01140051"))
+            .unwrap()
+            .r#type,
+        Some("free".to_string())
+    );
+}
+
+#[test]
+fn shared_credit_cap() {
+    let primary = primary();
+    let (minor_status, shared_courses) =
+        DegreeStatus::compute_secondary(&primary, minor(), HashMap::new(), Some(3.0));
+
+    // The earlier course fits under the cap, the later one is left out of the minor
+    assert_eq!(shared_courses, vec![shared("02340114", "hova")]);
+    assert_eq!(
+        minor_status.course_bank_requirements[0].credit_completed,
+        3.0
+    );
+    assert!(minor_status
+        .get_course_status(&CourseId::new("01140051"))
+        .is_none());
+}
+
+#[test]
+fn no_shared_credit_without_a_cap() {
+    let (minor_status, shared_courses) =
+        DegreeStatus::compute_secondary(&primary(), minor(), HashMap::new(), None);
+
+    assert!(shared_courses.is_empty());
+    assert_eq!(
+        minor_status.course_bank_requirements[0].credit_completed,
+        0.0
+    );
+    assert!(minor_status
+        .get_course_status(&CourseId::new("02340114"))
+        .is_none());
+}
+
+#[test]
+fn user_choices_are_not_carried_over() {
+    let mut primary = primary();
+    let chosen = primary
+        .get_mut_course_status(&CourseId::new("01140051"))
+        .unwrap();
+    chosen.r#type = Some("hova".to_string());
+    chosen.modified = true;
+
+    let (minor_status, _) =
+        DegreeStatus::compute_secondary(&primary, minor(), HashMap::new(), Some(6.0));
+    let course_status = minor_status
+        .get_course_status(&CourseId::new("01140051"))
+        .unwrap();
+    assert_eq!(course_status.r#type, Some("minor".to_string()));
+    assert!(!course_status.modified);
+}
@@ -0,0 +1,152 @@
+use std::collections::HashMap;
+
+use serde::{Deserialize, Serialize};
+
+use crate::{
+    core::types::Requirement,
+    resources::{
+        catalog::Catalog,
+        course::{AcademicSemester, Course, CourseId, CourseState, CourseStatus},
+    },
+};
+
+use super::DegreeStatus;
+
+/// A course the student intends to take in a future semester.
+#[derive(Clone, Debug, Deserialize, Serialize)]
+pub struct PlannedCourse {
+    pub course_id: CourseId,
+    pub semester: AcademicSemester,
+}
+
+/// How a single bank requirement changes once the planned courses are taken.
+#[derive(Clone, Debug, Default, Deserialize, Serialize)]
+pub struct RequirementDiff {
+    pub course_bank_name: String,
+    pub credit_completed_before: f32,
+    pub credit_completed_after: f32,
+    pub course_completed_before: usize,
+    pub course_completed_after: usize,
+    pub completed_before: bool,
+    pub completed_after: bool,
+}
+
+#[derive(Clone, Debug, Default, Deserialize, Serialize)]
+pub struct Simulation {
+    pub course_bank_requirements: Vec<Requirement>,
+    pub overflow_msgs: Vec<String>,
+    pub total_credit: f32,
+    pub total_credit_before: f32,
+    // The planned courses as they were assigned by the algorithm (bank, specialization group, etc.)
+    pub planned_courses: Vec<CourseStatus>,
+    // Only the requirements that changed
+    pub diff: Vec<RequirementDiff>,
+}
+
+impl DegreeStatus {
+    // Adds the planned courses as completed courses, superseding any earlier attempt which wasn't completed.
+    // Planned courses the student already completed are ignored.
+    fn add_planned_courses(
+        &mut self,
+        planned_courses: &[PlannedCourse],
+        courses: &HashMap<CourseId, Course>,
+    ) {
+        for planned in planned_courses {
+            self.add_planned_course(&planned.course_id, Some(planned.semester.clone()), courses);
+        }
+    }
+
+    pub(super) fn add_planned_course(
+        &mut self,
+        course_id: &CourseId,
+        semester: Option<AcademicSemester>,
+        courses: &HashMap<CourseId, Course>,
+    ) {
+        let already_completed = self
+            .course_statuses
+            .iter()
+            .any(|cs| cs.course.id == *course_id && cs.completed());
+        if already_completed {
+            return;
+        }
+        self.course_statuses.retain(|cs| cs.course.id != *course_id);
+        let course = courses.get(course_id).cloned().unwrap_or_else(|| Course {
+            id: course_id.clone(),
+            ..Default::default()
+        });
+        self.course_statuses.push(CourseStatus {
+            course,
+            state: Some(CourseState::Complete),
+            semester,
+            ..Default::default()
+        });
+    }
+
+    /// Runs the degree computation on a throwaway copy of this status, once as-is and once with the
+    /// planned courses added, and reports the requirements of the latter together with what changed.
+    /// `self` is left untouched.
+    pub fn simulate(
+        &self,
+        planned_courses: &[PlannedCourse],
+        catalog: Catalog,
+        courses: HashMap<CourseId, Course>,
+    ) -> Simulation {
+        let mut before = self.clone();
+        before.compute(catalog.clone(), courses.clone());
+
+        let mut after = self.clone();
+        after.add_planned_courses(planned_courses, &courses);
+        after.compute(catalog, courses);
+
+        let diff = after
+            .course_bank_requirements
+            .iter()
+            .filter_map(|requirement| {
+                let previous = before
+                    .course_bank_requirements
+                    .iter()
+                    .find(|r| r.course_bank_name == requirement.course_bank_name)
+                    .cloned()
+                    .unwrap_or_default();
+                let changed = previous.credit_completed != requirement.credit_completed
+                    || previous.course_completed != requirement.course_completed
+                    || previous.completed != requirement.completed;
+                changed.then(|| RequirementDiff {
+                    course_bank_name: requirement.course_bank_name.clone(),
+                    credit_completed_before: previous.credit_completed,
+                    credit_completed_after: requirement.credit_completed,
+                    course_completed_before: previous.course_completed,
+                    course_completed_after: requirement.course_completed,
+                    completed_before: previous.completed,
+                    completed_after: requirement.completed,
+                })
+            })
+            .collect();
+
+        let planned_courses = after
+            .course_statuses
+            .iter()
+            .filter(|cs| {
+                !cs.is_repetition
+                    && planned_courses.iter().any(|planned| {
+                        planned.course_id == cs.course.id
+                            && cs.semester.as_ref() == Some(&planned.semester)
+                    })
+            })
+            .cloned()
+            .collect();
+
+        Simulation {
+            course_bank_requirements: after.course_bank_requirements,
+            overflow_msgs: after.overflow_msgs,
+            total_credit: after.total_credit,
+            total_credit_before: before.total_credit,
+            planned_courses,
+            diff,
+        }
+    }
+}
+
+#[cfg(test)]
+#[path = "simulation_tests.rs"]
+mod simulation_tests;
@@ -0,0 +1,171 @@
+use std::collections::{HashMap, HashSet};
+
+use crate::{
+    core::types::Rule,
+    resources::{
+        catalog::{Catalog, Faculty},
+        course::*,
+    },
+};
+
+use super::*;
+
+fn course(id: &str, credit: f32) -> Course {
+    Course {
+        id: CourseId::new(id),
+        credit,
+        name: id.to_string(),
+        tags: None,
+        relations: Vec::new(),
+    }
+}
+
+fn graded(id: &str, credit: f32, grade: u32) -> CourseStatus {
+    CourseStatus {
+        course: course(id, credit),
+        state: Some(if grade >= 55 {
+            CourseState::Complete
+        } else {
+            CourseState::NotComplete
+        }),
+        semester: Some(AcademicSemester::new(SemesterSeason::Winter, 2023)),
+        grade: Some(Grade::Numeric(grade)),
+        ..Default::default()
+    }
+}
+
+fn planned(id: &str) -> PlannedCourse {
+    PlannedCourse {
+        course_id: CourseId::new(id),
+        semester: AcademicSemester::new(SemesterSeason::Spring, 2024),
+    }
+}
+
+fn catalog() -> Catalog {
+    Catalog {
+        id: bson::oid::ObjectId::new(),
+        name: "catalog".to_string(),
+        faculty: Faculty::Unknown,
+        total_credit: 10.0,
+        description: String::new(),
+        course_banks: vec![
+            CourseBank {
+                name: "hova".to_string(),
+                rule: Rule::All,
+                credit: Some(6.0),
+                constraints: None,
+            },
+            CourseBank {
+                name: "list a".to_string(),
+                rule: Rule::AccumulateCredit,
+                credit: Some(4.0),
+                constraints: None,
+            },
+        ],
+        credit_overflows: vec![],
+        course_to_bank: HashMap::from([
+            (CourseId::new("h1"), "hova".to_string()),
+            (CourseId::new("h2"), "hova".to_string()),
+            (CourseId::new("a1"), "list a".to_string()),
+            (CourseId::new("a2"), "list a".to_string()),
+        ]),
+        catalog_replacements: HashMap::new(),
+        common_replacements: HashMap::new(),
+        prefix_courses: HashSet::new(),
+    }
+}
+
+fn courses() -> HashMap<CourseId, Course> {
+    HashMap::from([
+        (CourseId::new("h1"), course("h1", 3.0)),
+        (CourseId::new("h2"), course("h2", 3.0)),
+        (CourseId::new("a1"), course("a1", 2.0)),
+        (CourseId::new("a2"), course("a2", 2.0)),
+    ])
+}
+
+fn requirement<'a>(requirements: &'a [Requirement], bank_name: &str) -> &'a Requirement {
+    requirements
+        .iter()
+        .find(|r| r.course_bank_name == bank_name)
+        .unwrap_or_else(|| panic!("bank {bank_name} not found"))
+}
+
+#[test]
+fn simulate_completes_bank_with_planned_courses() {
+    let degree_status = DegreeStatus {
+        course_statuses: vec![
+            graded("h1", 3.0, 90),
+            graded("h2", 3.0, 80),
+            graded("a1", 2.0, 70),
+        ],
+        ..Default::default()
+    };
+
+    let simulation = degree_status.simulate(&[planned("a2")], catalog(), courses());
+
+    assert!(requirement(&simulation.course_bank_requirements, "list a").completed);
+    assert_eq!(simulation.total_credit_before, 8.0);
+    assert_eq!(simulation.total_credit, 10.0);
+
+    // Only "list a" changed
+    assert_eq!(simulation.diff.len(), 1);
+    let diff = &simulation.diff[0];
+    assert_eq!(diff.course_bank_name, "list a");
+    assert_eq!(diff.credit_completed_before, 2.0);
+    assert_eq!(diff.credit_completed_after, 4.0);
+    assert!(!diff.completed_before);
+    assert!(diff.completed_after);
+
+    assert_eq!(simulation.planned_courses.len(), 1);
+    assert_eq!(
+        simulation.planned_courses[0].r#type,
+        Some("list a".to_string())
+    );
+}
+
+#[test]
+fn simulate_does_not_modify_the_stored_status() {
+    let degree_status = DegreeStatus {
+        course_statuses: vec![graded("h1", 3.0, 90)],
+        ..Default::default()
+    };
+
+    let _ = degree_status.simulate(&[planned("h2")], catalog(), courses());
+
+    assert_eq!(degree_status.course_statuses.len(), 1);
+    assert!(degree_status.course_bank_requirements.is_empty());
+    assert_eq!(degree_status.total_credit, 0.0);
+}
+
+#[test]
+fn simulate_planned_retake_supersedes_failed_attempt() {
+    let degree_status = DegreeStatus {
+        course_statuses: vec![graded("h1", 3.0, 90), graded("h2", 3.0, 40)],
+        ..Default::default()
+    };
+
+    let simulation = degree_status.simulate(&[planned("h2")], catalog(), courses());
+
+    let hova = requirement(&simulation.course_bank_requirements, "hova");
+    assert!(hova.completed);
+    assert_eq!(hova.credit_completed, 6.0);
+    assert!(simulation
+        .diff
+        .iter()
+        .any(|diff| diff.course_bank_name == "hova" && diff.completed_after));
+}
+
+#[test]
+fn simulate_ignores_planned_courses_that_were_already_completed() {
+    let degree_status = DegreeStatus {
+        course_statuses: vec![graded("h1", 3.0, 90)],
+        ..Default::default()
+    };
+
+    let simulation = degree_status.simulate(&[planned("h1")], catalog(), courses());
+
+    assert!(simulation.diff.is_empty());
+    assert!(simulation.planned_courses.is_empty());
+    assert_eq!(simulation.total_credit, simulation.total_credit_before);
+}
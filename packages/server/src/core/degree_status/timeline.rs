@@ -0,0 +1,112 @@
+use std::collections::{BTreeMap, HashMap};
+
+use serde::{Deserialize, Serialize};
+
+use crate::resources::course::{AcademicSemester, CourseId, CourseState, Grade, SemesterSeason};
+
+use super::DegreeStatus;
+
+/// What the student did in a single semester, and where it left them.
+#[derive(Default, Clone, Debug, Deserialize, Serialize, PartialEq)]
+pub struct SemesterSummary {
+    pub semester: AcademicSemester,
+    // Credit of every graded course, passed or not
+    pub credit_attempted: f32,
+    // Credit of passed courses, not counting attempts which were retaken later or courses which
+    // overlap ones the student already got credit for
+    pub credit_earned: f32,
+    pub credit_in_progress: f32,
+    pub average: Option<f32>,
+    pub failed_courses: Vec<CourseId>,
+    pub cumulative_credit: f32,
+    // The cumulative credit out of the catalog's total credit, None without a catalog
+    pub progress: Option<f32>,
+    // The student's label for the semester, e.g. reserve duty
+    pub annotation: Option<String>,
+}
+
+// The inverse of `AcademicSemester::order_key`, which is also how timeline annotations are keyed.
+fn semester_from_order_key(order_key: i32) -> AcademicSemester {
+    let season = match order_key.rem_euclid(3) {
+        0 => SemesterSeason::Winter,
+        1 => SemesterSeason::Spring,
+        _ => SemesterSeason::Summer,
+    };
+    AcademicSemester::new(season, order_key.div_euclid(3))
+}
+
+impl DegreeStatus {
+    /// Summarizes the courses semester by semester, in calendar order. Semesters without courses only
+    /// appear when the student annotated them.
+    pub fn timeline(
+        &self,
+        total_credit: Option<f64>,
+        annotations: &HashMap<String, String>,
+    ) -> Vec<SemesterSummary> {
+        let mut semesters: BTreeMap<i32, SemesterSummary> = BTreeMap::new();
+        for course_status in &self.course_statuses {
+            let Some(semester) = &course_status.semester else {
+                continue;
+            };
+            if course_status.state == Some(CourseState::Irrelevant) {
+                continue;
+            }
+            let summary =
+                semesters
+                    .entry(semester.order_key())
+                    .or_insert_with(|| SemesterSummary {
+                        semester: semester.clone(),
+                        ..Default::default()
+                    });
+            let credit = course_status.course.credit;
+            match &course_status.grade {
+                None => summary.credit_in_progress += credit,
+                Some(grade) => {
+                    summary.credit_attempted += credit;
+                    if !course_status.passed() {
+                        summary.failed_courses.push(course_status.course.id.clone());
+                    } else if course_status.completed()
+                        && !course_status.is_repetition
+                        && !course_status.no_additional_credit
+                        && *grade != Grade::ExemptionWithoutCredit
+                    {
+                        summary.credit_earned += credit;
+                    }
+                }
+            }
+        }
+
+        for order_key in annotations.keys().filter_map(|key| key.parse::<i32>().ok()) {
+            semesters
+                .entry(order_key)
+                .or_insert_with(|| SemesterSummary {
+                    semester: semester_from_order_key(order_key),
+                    ..Default::default()
+                });
+        }
+
+        let averages = self.compute_averages();
+        let mut cumulative_credit = 0.0;
+        semesters
+            .into_iter()
+            .map(|(order_key, mut summary)| {
+                cumulative_credit += summary.credit_earned;
+                summary.cumulative_credit = cumulative_credit;
+                summary.progress = total_credit
+                    .filter(|total_credit| *total_credit > 0.0)
+                    .map(|total_credit| cumulative_credit / total_credit as f32);
+                summary.average = averages
+                    .semesters
+                    .iter()
+                    .find(|average| average.semester == summary.semester)
+                    .map(|average| average.average);
+                summary.annotation = annotations.get(&order_key.to_string()).cloned();
+                summary
+            })
+            .collect()
+    }
+}
+
+#[cfg(test)]
+#[path = "timeline_tests.rs"]
+mod timeline_tests;
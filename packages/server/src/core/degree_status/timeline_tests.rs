@@ -0,0 +1,117 @@
+use crate::resources::course::{Course, CourseStatus};
+
+use super::*;
+
+fn course_status(
+    id: &str,
+    credit: f32,
+    semester: AcademicSemester,
+    grade: Option<Grade>,
+) -> CourseStatus {
+    let mut course_status = CourseStatus {
+        course: Course {
+            id: CourseId::new(id),
+            credit,
+            name: id.to_string(),
+            tags: None,
+            relations: Vec::new(),
+        },
+        semester: Some(semester),
+        grade,
+        ..Default::default()
+    };
+    course_status.set_state();
+    course_status
+}
+
+fn winter(start_year: i32) -> AcademicSemester {
+    AcademicSemester::new(SemesterSeason::Winter, start_year)
+}
+
+fn spring(start_year: i32) -> AcademicSemester {
+    AcademicSemester::new(SemesterSeason::Spring, start_year)
+}
+
+#[test]
+fn summarizes_every_semester() {
+    let mut failed = course_status("01040031", 5.0, winter(2022), Some(Grade::Numeric(40)));
+    failed.is_repetition = true;
+    let degree_status = DegreeStatus {
+        course_statuses: vec![
+            course_status("02340114", 4.0, winter(2022), Some(Grade::Numeric(90))),
+            failed,
+            course_status("01040031", 5.0, spring(2022), Some(Grade::Numeric(70))),
+            course_status("02340124", 3.0, winter(2023), None),
+        ],
+        ..Default::default()
+    };
+    let annotations =
+        HashMap::from([(winter(2023).order_key().to_string(), "מילואים".to_string())]);
+
+    let timeline = degree_status.timeline(Some(18.0), &annotations);
+
+    assert_eq!(
+        timeline,
+        vec![
+            SemesterSummary {
+                semester: winter(2022),
+                credit_attempted: 9.0,
+                credit_earned: 4.0,
+                credit_in_progress: 0.0,
+                average: Some((90.0 * 4.0 + 40.0 * 5.0) / 9.0),
+                failed_courses: vec![CourseId::new("01040031")],
+                cumulative_credit: 4.0,
+                progress: Some(4.0 / 18.0),
+                annotation: None,
+            },
+            SemesterSummary {
+                semester: spring(2022),
+                credit_attempted: 5.0,
+                credit_earned: 5.0,
+                credit_in_progress: 0.0,
+                average: Some(70.0),
+                failed_courses: vec![],
+                cumulative_credit: 9.0,
+                progress: Some(0.5),
+                annotation: None,
+            },
+            SemesterSummary {
+                semester: winter(2023),
+                credit_attempted: 0.0,
+                credit_earned: 0.0,
+                credit_in_progress: 3.0,
+                average: None,
+                failed_courses: vec![],
+                cumulative_credit: 9.0,
+                progress: Some(0.5),
+                annotation: Some("מילואים".to_string()),
+            },
+        ]
+    );
+}
+
+#[test]
+fn annotated_gaps_are_included() {
+    let degree_status = DegreeStatus {
+        course_statuses: vec![course_status(
+            "02340114",
+            4.0,
+            winter(2022),
+            Some(Grade::Binary(true)),
+        )],
+        ..Default::default()
+    };
+    let annotations = HashMap::from([
+        (spring(2022).order_key().to_string(), "חופשה".to_string()),
+        ("not a semester".to_string(), "ignored".to_string()),
+    ]);
+
+    let timeline = degree_status.timeline(None, &annotations);
+
+    assert_eq!(timeline.len(), 2);
+    assert_eq!(timeline[0].average, None);
+    assert_eq!(timeline[0].progress, None);
+    assert_eq!(timeline[1].semester, spring(2022));
+    assert_eq!(timeline[1].cumulative_credit, 4.0);
+    assert_eq!(timeline[1].annotation, Some("חופשה".to_string()));
+}
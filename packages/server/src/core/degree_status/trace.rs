@@ -0,0 +1,153 @@
+use std::collections::HashMap;
+
+use serde::{Deserialize, Serialize};
+
+use crate::{
+    core::types::Transfer,
+    resources::{
+        catalog::Catalog,
+        course::{AcademicSemester, CourseId, CourseStatus},
+    },
+};
+
+use super::DegreeStatus;
+
+// Why a course was counted in its bank.
+#[derive(Clone, Debug, PartialEq, Deserialize, Serialize)]
+#[serde(tag = "kind", rename_all = "snake_case")]
+pub enum AssignmentReason {
+    // The student moved the course to the bank
+    ChosenByUser,
+    // The chosen catalog lists the course under the bank
+    Catalog,
+    // The course replaces a catalog course, per the catalog or the common replacements
+    CatalogReplacement { replaces: CourseId },
+    CommonReplacement { replaces: CourseId },
+    // The course covers a catalog course's material, per SAP
+    ContainedCourse { replaces: CourseId },
+    // Another year of the same track lists the course under a bank with the same name
+    SiblingCatalog { catalog: String },
+    // The course isn't in the catalog but belongs to the faculty, so it goes to the default bank
+    FacultyPrefix,
+    // The bank's rule took the course, e.g. sport, malag or elective
+    BankRule { rule: String },
+    SpecializationGroup { group: String },
+}
+
+#[derive(Clone, Debug, PartialEq, Deserialize, Serialize)]
+pub struct CourseTrace {
+    pub course_id: CourseId,
+    pub semester: Option<AcademicSemester>,
+    pub bank: String,
+    pub reasons: Vec<AssignmentReason>,
+}
+
+#[derive(Clone, Debug, PartialEq, Deserialize, Serialize)]
+pub struct TransferTrace {
+    pub kind: Transfer,
+    pub from: String,
+    // None when the bank produced the overflow, Some when another bank took it
+    pub to: Option<String>,
+    pub amount: f32,
+}
+
+/// How the degree computation assigned every course and moved credit between banks. Only recorded
+/// on request, since it isn't stored with the degree status.
+#[derive(Default, Clone, Debug, PartialEq, Deserialize, Serialize)]
+pub struct AssignmentTrace {
+    pub courses: Vec<CourseTrace>,
+    pub transfers: Vec<TransferTrace>,
+    // Where courses which aren't in the chosen catalog itself came from
+    #[serde(skip)]
+    origins: HashMap<CourseId, AssignmentReason>,
+}
+
+impl AssignmentTrace {
+    /// Starts a trace for a catalog enriched with `sibling_courses` (course id → sibling catalog).
+    pub fn new(sibling_courses: HashMap<CourseId, String>) -> Self {
+        AssignmentTrace {
+            origins: sibling_courses
+                .into_iter()
+                .map(|(course_id, catalog)| {
+                    (course_id, AssignmentReason::SiblingCatalog { catalog })
+                })
+                .collect(),
+            ..Default::default()
+        }
+    }
+
+    pub(super) fn record_origin(&mut self, course_id: CourseId, reason: AssignmentReason) {
+        self.origins.insert(course_id, reason);
+    }
+
+    fn explain(&self, course_status: &CourseStatus, catalog: &Catalog) -> Option<CourseTrace> {
+        let bank = course_status.r#type.clone()?;
+        let course_id = &course_status.course.id;
+        let mut reasons = Vec::new();
+        if course_status.modified {
+            reasons.push(AssignmentReason::ChosenByUser);
+        } else if catalog.course_to_bank.get(course_id) == Some(&bank) {
+            // The enriched catalog maps the course, the origin tells how it got there
+            reasons.push(
+                self.origins
+                    .get(course_id)
+                    .cloned()
+                    .unwrap_or(AssignmentReason::Catalog),
+            );
+        } else {
+            reasons.push(AssignmentReason::BankRule {
+                rule: catalog
+                    .get_course_bank_by_name(&bank)
+                    .map(|bank| bank.rule.to_string())
+                    .unwrap_or_default(),
+            });
+        }
+        if let Some(group) = &course_status.specialization_group_name {
+            reasons.push(AssignmentReason::SpecializationGroup {
+                group: group.clone(),
+            });
+        }
+        Some(CourseTrace {
+            course_id: course_id.clone(),
+            semester: course_status.semester.clone(),
+            bank,
+            reasons,
+        })
+    }
+}
+
+impl DegreeStatus {
+    pub(super) fn record_transfer(
+        &mut self,
+        kind: Transfer,
+        from: &str,
+        to: Option<&str>,
+        amount: f32,
+    ) {
+        if let Some(trace) = &mut self.trace {
+            trace.transfers.push(TransferTrace {
+                kind,
+                from: from.to_string(),
+                to: to.map(str::to_string),
+                amount,
+            });
+        }
+    }
+
+    // Explains the final bank of every assigned course.
+    pub(super) fn explain_courses(&mut self, catalog: &Catalog) {
+        let Some(mut trace) = self.trace.take() else {
+            return;
+        };
+        trace.courses = self
+            .course_statuses
+            .iter()
+            .filter_map(|course_status| trace.explain(course_status, catalog))
+            .collect();
+        self.trace = Some(trace);
+    }
+}
+
+#[cfg(test)]
+#[path = "trace_tests.rs"]
+mod trace_tests;
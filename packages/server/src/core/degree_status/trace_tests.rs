@@ -0,0 +1,180 @@
+use std::collections::{HashMap, HashSet};
+
+use crate::{
+    core::types::{CreditOverflow, Rule, Transfer},
+    resources::{
+        catalog::{Catalog, Faculty},
+        course::{Course, CourseBank, CourseId, CourseState, CourseStatus, Grade},
+    },
+};
+
+use super::*;
+
+fn course_status(id: &str, credit: f32) -> CourseStatus {
+    CourseStatus {
+        course: Course {
+            id: CourseId::new(id),
+            credit,
+            name: id.to_string(),
+            tags: None,
+            relations: Vec::new(),
+        },
+        state: Some(CourseState::Complete),
+        grade: Some(Grade::Numeric(90)),
+        ..Default::default()
+    }
+}
+
+fn bank(name: &str, rule: Rule, credit: f32) -> CourseBank {
+    CourseBank {
+        name: name.to_string(),
+        rule,
+        credit: Some(credit),
+        constraints: None,
+    }
+}
+
+fn catalog() -> Catalog {
+    Catalog {
+        id: bson::oid::ObjectId::new(),
+        name: "catalog".to_string(),
+        faculty: Faculty::Unknown,
+        total_credit: 18.0,
+        description: String::new(),
+        course_banks: vec![
+            bank("hova", Rule::All, 6.0),
+            bank("reshima a", Rule::AccumulateCredit, 3.0),
+            bank("free", Rule::Elective, 9.0),
+        ],
+        credit_overflows: vec![
+            CreditOverflow {
+                from: "hova".to_string(),
+                to: "free".to_string(),
+            },
+            CreditOverflow {
+                from: "reshima a".to_string(),
+                to: "free".to_string(),
+            },
+        ],
+        course_to_bank: HashMap::from([
+            (CourseId::new("02340114"), "hova".to_string()),
+            (CourseId::new("02340118"), "hova".to_string()),
+            (CourseId::new("02360501"), "reshima a".to_string()),
+            (CourseId::new("02360756"), "reshima a".to_string()),
+        ]),
+        catalog_replacements: HashMap::from([(
+            CourseId::new("02340118"),
+            vec![CourseId::new("02340117")],
+        )]),
+        common_replacements: HashMap::new(),
+        prefix_courses: HashSet::new(),
+    }
+}
+
+fn compute(trace: Option<AssignmentTrace>) -> DegreeStatus {
+    let mut chosen = course_status("01040031", 3.0);
+    chosen.r#type = Some("free".to_string());
+    chosen.modified = true;
+    let mut degree_status = DegreeStatus {
+        course_statuses: vec![
+            course_status("02340114", 3.0),
+            course_status("02340117", 3.0),
+            course_status("02360501", 3.0),
+            course_status("02360756", 3.0),
+            course_status("01140051", 3.0),
+            chosen,
+        ],
+        trace,
+        ..Default::default()
+    };
+    degree_status.compute(catalog(), HashMap::new());
+    degree_status
+}
+
+fn reasons(trace: &AssignmentTrace, course_id: &str) -> (String, Vec<AssignmentReason>) {
+    let course = trace
+        .courses
+        .iter()
+        .find(|course| course.course_id == CourseId::new(course_id))
+        .unwrap();
+    (course.bank.clone(), course.reasons.clone())
+}
+
+#[test]
+fn explains_every_assigned_course() {
+    let degree_status = compute(Some(AssignmentTrace::new(HashMap::from([(
+        CourseId::new("02360756"),
+        "catalog 2022-2023".to_string(),
+    )]))));
+    let trace = degree_status.trace.unwrap();
+
+    assert_eq!(trace.courses.len(), 6);
+    assert_eq!(
+        reasons(&trace, "02340114"),
+        ("hova".to_string(), vec![AssignmentReason::Catalog])
+    );
+    assert_eq!(
+        reasons(&trace, "02340117"),
+        (
+            "hova".to_string(),
+            vec![AssignmentReason::CatalogReplacement {
+                replaces: CourseId::new("02340118")
+            }]
+        )
+    );
+    assert_eq!(
+        reasons(&trace, "02360756"),
+        (
+            "reshima a".to_string(),
+            vec![AssignmentReason::SiblingCatalog {
+                catalog: "catalog 2022-2023".to_string()
+            }]
+        )
+    );
+    assert_eq!(
+        reasons(&trace, "01140051"),
+        (
+            "free".to_string(),
+            vec![AssignmentReason::BankRule {
+                rule: Rule::Elective.to_string()
+            }]
+        )
+    );
+    assert_eq!(
+        reasons(&trace, "01040031"),
+        ("free".to_string(), vec![AssignmentReason::ChosenByUser])
+    );
+}
+
+#[test]
+fn records_credit_transfers() {
+    let degree_status = compute(Some(AssignmentTrace::default()));
+    let trace = degree_status.trace.unwrap();
+
+    assert_eq!(
+        trace.transfers,
+        vec![
+            TransferTrace {
+                kind: Transfer::CreditOverflow,
+                from: "reshima a".to_string(),
+                to: None,
+                amount: 3.0,
+            },
+            TransferTrace {
+                kind: Transfer::CreditOverflow,
+                from: "reshima a".to_string(),
+                to: Some("free".to_string()),
+                amount: 3.0,
+            },
+        ]
+    );
+}
+
+#[test]
+fn no_trace_unless_requested() {
+    let degree_status = compute(None);
+    assert_eq!(degree_status.trace, None);
+    assert!(!serde_json::to_string(&degree_status)
+        .unwrap()
+        .contains("trace"));
+}
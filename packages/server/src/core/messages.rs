@@ -21,6 +21,20 @@ pub fn catalog_replacement_msg(course: &Course) -> String {
     format!("קורס זה מחליף את הקורס {} ({})", course.name, course.id)
 }
 
+pub fn contained_course_replacement_msg(course: &Course) -> String {
+    format!(
+        "קורס זה מכיל את הקורס {} ({}) ולכן נספר במקומו",
+        course.name, course.id
+    )
+}
+
+pub fn no_additional_credit_msg(course: &Course) -> String {
+    format!(
+        "קורס זה חופף לקורס {} ({}) ולכן אינו מזכה בנקודות נוספות",
+        course.name, course.id
+    )
+}
+
 pub fn credit_overflow_msg(overflow: f32, from: &str, to: &str) -> String {
     if overflow == SINGLE {
         format!("נקודה אחת עברה מ{from} ל{to}")
@@ -151,6 +165,29 @@ pub fn medicine_preclinical_total_repetitions_error_msg(repetitions: usize) -> S
     format!("פסילה: סך הכל, חזרת על קורסים {repetitions} פעמים. לא ניתן לחזור על יותר משני קורסים, או לחזור על קורס אחד יותר מפעמיים")
 }
 
+pub fn course_below_min_grade_msg(min_grade: u32) -> String {
+    format!("הקורס לא נספר בדרישה זו כי נדרש בו ציון של לפחות {min_grade}")
+}
+
+pub fn min_grade_constraint_msg(min_grade: u32, courses: &[String]) -> String {
+    format!(
+        "נדרש ציון של לפחות {min_grade} בקורסי דרישה זו, לכן הקורסים הבאים לא נספרו: {}",
+        courses.join(", ")
+    )
+}
+
+pub fn min_courses_constraint_msg(name: &str, count: usize, completed: usize) -> String {
+    format!("יש להשלים לפחות {count} קורסים מ{name} (הושלמו {completed})")
+}
+
+pub fn max_credit_constraint_msg(name: &str, credit: f32, excess: f32) -> String {
+    if excess == SINGLE {
+        format!("ניתן לצבור בדרישה זו עד {credit} נקודות מ{name}, לכן נקודה אחת לא נספרה בה")
+    } else {
+        format!("ניתן לצבור בדרישה זו עד {credit} נקודות מ{name}, לכן {excess} נקודות לא נספרו בה")
+    }
+}
+
 /////////////////////////////////////////////////////////////////////////////////
 // Error messages
 /////////////////////////////////////////////////////////////////////////////////
@@ -1,12 +1,17 @@
 pub mod bank_rule;
+pub mod course_search;
 pub mod credit_transfer_graph;
 pub mod degree_status;
 pub mod messages;
 pub mod parser;
 pub mod parser_v2;
+pub mod prerequisites;
 pub mod stats;
+pub mod timetable;
+pub mod transcript_pdf;
 pub mod types;
 
+pub mod catalog_diff;
 pub mod catalog_validations;
 #[cfg(test)]
 pub mod tests;
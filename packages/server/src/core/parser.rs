@@ -291,6 +291,7 @@ fn parse_course_status_pdf_format(
             credit,
             name,
             tags: None,
+            relations: Vec::new(),
         },
         grade,
     ))
@@ -9,6 +9,7 @@ fn cs(id: &str, grade: Grade, semester: AcademicSemester) -> CourseStatus {
             credit: 1.0,
             name: id.to_string(),
             tags: None,
+            relations: Vec::new(),
         },
         grade: Some(grade),
         semester: Some(semester),
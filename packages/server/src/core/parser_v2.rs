@@ -1,4 +1,5 @@
 use regex::Regex;
+use serde::{Deserialize, Serialize};
 use std::sync::LazyLock;
 
 use crate::{
@@ -16,9 +17,13 @@ static NUMBER_RE: LazyLock<Regex> = LazyLock::new(|| Regex::new(r"\b(\d+\.?\d*)\
 
 // ── Format Detection ──
 
-enum Format {
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Deserialize, Serialize)]
+#[serde(rename_all = "snake_case")]
+pub enum Format {
     Chrome,
     Edge,
+    // Text extracted from the PDF itself, laid out like Chrome's
+    Pdf,
 }
 
 impl Format {
@@ -38,6 +43,125 @@ fn validate(data: &str) -> Result<(), AppError> {
     Ok(())
 }
 
+// ── Diagnostics ──
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Deserialize, Serialize)]
+#[serde(rename_all = "snake_case")]
+pub enum SkipReason {
+    // Recognized as a header, but it contains a course id
+    Metadata,
+    // Contains a course without the id it belongs to
+    MissingCourseId,
+    Unrecognized,
+}
+
+/// The report is stored on the user, so it points at the line rather than copying it: grade sheet
+/// lines may hold personal details.
+#[derive(Clone, Debug, PartialEq, Deserialize, Serialize)]
+pub struct SkippedLine {
+    pub line_number: usize,
+    pub reason: SkipReason,
+}
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Deserialize, Serialize)]
+#[serde(rename_all = "snake_case")]
+pub enum ParseWarningKind {
+    MissingName,
+    MissingGrade,
+    GradeOutOfRange,
+    MissingCredit,
+    UnusualCredit,
+}
+
+/// A course which was parsed, but with a value that is probably wrong.
+#[derive(Clone, Debug, PartialEq, Deserialize, Serialize)]
+pub struct ParseWarning {
+    pub line_number: usize,
+    pub course_id: String,
+    pub kind: ParseWarningKind,
+}
+
+/// What the parser did with the grade sheet, beyond the courses it found.
+#[derive(Default, Clone, Debug, PartialEq, Deserialize, Serialize)]
+pub struct ParseReport {
+    pub format: Option<Format>,
+    pub skipped_lines: Vec<SkippedLine>,
+    pub warnings: Vec<ParseWarning>,
+}
+
+// Courses are given in half credit points, and no course is worth more than this.
+const MAX_COURSE_CREDIT: f32 = 20.0;
+
+fn warnings(raw_course: &RawCourse) -> Vec<ParseWarningKind> {
+    let mut warnings = Vec::new();
+    if raw_course.name.is_empty() {
+        warnings.push(ParseWarningKind::MissingName);
+    }
+    match raw_course.grade {
+        None => warnings.push(ParseWarningKind::MissingGrade),
+        Some(Grade::Numeric(grade)) if grade > 100 => {
+            warnings.push(ParseWarningKind::GradeOutOfRange)
+        }
+        _ => {}
+    }
+    let credit = raw_course.credit;
+    // Pass/fail courses (e.g. sexual harassment prevention) may have no credit
+    if credit == 0.0 && matches!(raw_course.grade, Some(Grade::Numeric(_))) {
+        warnings.push(ParseWarningKind::MissingCredit);
+    } else if (credit * 2.0).fract() != 0.0 || credit > MAX_COURSE_CREDIT {
+        warnings.push(ParseWarningKind::UnusualCredit);
+    }
+    warnings
+}
+
+fn build_report(
+    format: Format,
+    lines: &[&str],
+    used: &[bool],
+    raw_courses: &[RawCourse],
+    pending_line: Option<usize>,
+) -> ParseReport {
+    let skipped_lines = lines
+        .iter()
+        .zip(used)
+        .enumerate()
+        .filter(|(_, (line, used))| !**used && !line.trim().is_empty())
+        .filter_map(|(index, (line, _))| {
+            let reason = if Some(index) == pending_line {
+                SkipReason::MissingCourseId
+            } else if !is_header_or_meta(line) {
+                SkipReason::Unrecognized
+            } else if extract_id(line).is_some() {
+                SkipReason::Metadata
+            } else {
+                // An ordinary header
+                return None;
+            };
+            Some(SkippedLine {
+                line_number: index + 1,
+                reason,
+            })
+        })
+        .collect();
+
+    let warnings = raw_courses
+        .iter()
+        .flat_map(|raw_course| {
+            warnings(raw_course).into_iter().map(|kind| ParseWarning {
+                line_number: raw_course.line + 1,
+                course_id: raw_course.id.clone(),
+                kind,
+            })
+        })
+        .collect();
+
+    ParseReport {
+        format: Some(format),
+        skipped_lines,
+        warnings,
+    }
+}
+
 // ── Grade Parsing ──
 
 /// Ordered longest-first so multi-word terms match before substrings.
@@ -147,6 +271,8 @@ fn parse_edge_fields(data: &str) -> (String, f32, Option<Grade>) {
 // ── Helpers ──
 
 struct RawCourse {
+    // Index of the line the course starts on
+    line: usize,
     id: String,
     name: String,
     credit: f32,
@@ -187,6 +313,8 @@ fn is_header_or_meta(line: &str) -> bool {
         || l.contains("הנני מאשר")
         || l.contains("מזכיר")
         || l.starts_with("דר'")
+        // Place and date of issue
+        || l.starts_with("חיפה,")
         || (l.contains("הקורס ניתן") && l.contains("E"))
 }
 
@@ -194,7 +322,7 @@ fn is_header_or_meta(line: &str) -> bool {
 
 /// Parse a Chrome record by extracting the ID and semester, removing them,
 /// and parsing the remaining text as NAME [CREDIT] [GRADE].
-fn parse_chrome_record(text: &str) -> Option<RawCourse> {
+fn parse_chrome_record(text: &str, line: usize) -> Option<RawCourse> {
     let id = extract_id(text)?;
     let (year, term) = extract_semester(text)?;
 
@@ -209,6 +337,7 @@ fn parse_chrome_record(text: &str) -> Option<RawCourse> {
     let (name, credit, grade) = parse_chrome_fields(data.trim());
 
     Some(RawCourse {
+        line,
         id,
         name,
         credit,
@@ -218,7 +347,7 @@ fn parse_chrome_record(text: &str) -> Option<RawCourse> {
     })
 }
 
-fn parse_chrome_lines(lines: &[&str]) -> Result<Vec<RawCourse>, AppError> {
+fn parse_chrome_lines(lines: &[&str], used: &mut [bool]) -> Result<Vec<RawCourse>, AppError> {
     let mut courses = Vec::new();
     let mut i = 0;
 
@@ -229,16 +358,19 @@ fn parse_chrome_lines(lines: &[&str]) -> Result<Vec<RawCourse>, AppError> {
             continue;
         }
         // Try single line
-        if let Some(course) = parse_chrome_record(line) {
+        if let Some(course) = parse_chrome_record(line, i) {
             courses.push(course);
+            used[i] = true;
             i += 1;
             continue;
         }
-        // Try combining with next line
-        if i + 1 < lines.len() {
+        // Try combining with next line, unless it's a course of its own
+        if i + 1 < lines.len() && extract_id(lines[i + 1]).is_none() {
             let combined = format!("{} {}", line, lines[i + 1]);
-            if let Some(course) = parse_chrome_record(&combined) {
+            if let Some(course) = parse_chrome_record(&combined, i) {
                 courses.push(course);
+                used[i] = true;
+                used[i + 1] = true;
                 i += 2;
                 continue;
             }
@@ -250,11 +382,15 @@ fn parse_chrome_lines(lines: &[&str]) -> Result<Vec<RawCourse>, AppError> {
 
 // ── Edge: Row-by-Row with Pending Course ──
 
-fn parse_edge_lines(lines: &[&str]) -> Result<Vec<RawCourse>, AppError> {
+// Returns the parsed courses and the line of a trailing course whose ID was never found.
+fn parse_edge_lines(
+    lines: &[&str],
+    used: &mut [bool],
+) -> Result<(Vec<RawCourse>, Option<usize>), AppError> {
     let mut courses = Vec::new();
     let mut pending: Option<RawCourse> = None;
 
-    for line in lines {
+    for (index, line) in lines.iter().enumerate() {
         let line = line.trim();
 
         // Resolve pending course: first ID on this line belongs to the previous course
@@ -263,6 +399,8 @@ fn parse_edge_lines(lines: &[&str]) -> Result<Vec<RawCourse>, AppError> {
             if let Some(id) = extract_id(line) {
                 let mut p = pending.take().unwrap();
                 p.id = id;
+                used[p.line] = true;
+                used[index] = true;
                 courses.push(p);
                 used_id_for_pending = true;
             }
@@ -299,7 +437,9 @@ fn parse_edge_lines(lines: &[&str]) -> Result<Vec<RawCourse>, AppError> {
             if !name_suffix.is_empty() {
                 name1 = format!("{name1} {name_suffix}");
             }
+            used[index] = true;
             courses.push(RawCourse {
+                line: index,
                 id: e_id,
                 name: name1,
                 credit: credit1,
@@ -311,6 +451,7 @@ fn parse_edge_lines(lines: &[&str]) -> Result<Vec<RawCourse>, AppError> {
             // Second course: becomes pending (ID on next line)
             let (name2, credit2, grade2) = parse_edge_fields(data2.trim());
             pending = Some(RawCourse {
+                line: index,
                 id: String::new(),
                 name: name2,
                 credit: credit2,
@@ -349,6 +490,7 @@ fn parse_edge_lines(lines: &[&str]) -> Result<Vec<RawCourse>, AppError> {
         let (name, credit, grade) = parse_edge_fields(data.trim());
 
         let course = RawCourse {
+            line: index,
             id: id.unwrap_or_default(),
             name,
             credit,
@@ -360,10 +502,11 @@ fn parse_edge_lines(lines: &[&str]) -> Result<Vec<RawCourse>, AppError> {
         if course.id.is_empty() {
             pending = Some(course);
         } else {
+            used[index] = true;
             courses.push(course);
         }
     }
-    Ok(courses)
+    Ok((courses, pending.map(|course| course.line)))
 }
 
 // ── Semester Numbering ──
@@ -430,6 +573,7 @@ fn assign_semester_numbers(raw_courses: Vec<RawCourse>) -> Result<Vec<CourseStat
                 credit: raw.credit,
                 name: raw.name,
                 tags: None,
+                relations: Vec::new(),
             },
             semester: if is_zero_credit_exemption {
                 None
@@ -453,16 +597,47 @@ fn assign_semester_numbers(raw_courses: Vec<RawCourse>) -> Result<Vec<CourseStat
 // ── Entry Point ──
 
 pub fn parse_copy_paste_data(data: &str) -> Result<Vec<CourseStatus>, AppError> {
-    validate(data)?;
+    parse_with_report(data).0
+}
 
-    let format = Format::detect(data);
-    let lines: Vec<&str> = data.lines().collect();
+/// Parses the copy-paste data like `parse_copy_paste_data`, and reports the lines that didn't make
+/// it into a course and the values that look off. The report is returned even if parsing fails.
+pub fn parse_with_report(data: &str) -> (Result<Vec<CourseStatus>, AppError>, ParseReport) {
+    parse(data, Format::detect(data))
+}
+
+/// Parses grade sheet text which is already in logical (reading) order, one table row per line,
+/// e.g. text extracted from the PDF itself. Rows are laid out the same way Chrome copies them.
+pub fn parse_logical_text(data: &str) -> (Result<Vec<CourseStatus>, AppError>, ParseReport) {
+    parse(data, Format::Pdf)
+}
 
-    let raw_courses = match format {
-        Format::Chrome => parse_chrome_lines(&lines)?,
-        Format::Edge => parse_edge_lines(&lines)?,
+fn parse(data: &str, format: Format) -> (Result<Vec<CourseStatus>, AppError>, ParseReport) {
+    let lines: Vec<&str> = data.lines().collect();
+    let mut used = vec![false; lines.len()];
+
+    let parsed = validate(data).and_then(|_| match format {
+        Format::Chrome | Format::Pdf => Ok((parse_chrome_lines(&lines, &mut used)?, None)),
+        Format::Edge => parse_edge_lines(&lines, &mut used),
+    });
+    let (raw_courses, pending_line) = match parsed {
+        Ok(parsed) => parsed,
+        Err(e) => {
+            return (
+                Err(e),
+                ParseReport {
+                    format: Some(format),
+                    ..Default::default()
+                },
+            )
+        }
     };
 
+    let report = build_report(format, &lines, &used, &raw_courses, pending_line);
+    (to_course_statuses(raw_courses), report)
+}
+
+fn to_course_statuses(raw_courses: Vec<RawCourse>) -> Result<Vec<CourseStatus>, AppError> {
     let courses = assign_semester_numbers(raw_courses)?;
 
     if courses.is_empty() {
@@ -301,3 +301,122 @@ fn both_formats_produce_same_grades() {
         }
     }
 }
+
+#[test]
+fn report_is_clean_for_valid_data() {
+    for (data, format) in [
+        (get_chrome_data(), Format::Chrome),
+        (get_edge_data(), Format::Edge),
+    ] {
+        let (result, report) = parse_with_report(&data);
+        assert!(result.is_ok());
+        assert_eq!(
+            report,
+            ParseReport {
+                format: Some(format),
+                ..Default::default()
+            }
+        );
+    }
+}
+
+#[test]
+fn report_lists_unparsed_lines() {
+    let data = get_chrome_data().replacen(
+        "00440252 מערכות ספרתיות ומבנה המחשב 5 90 2023-2024 אביב תשפ\"ד",
+        "00440252 מערכות ספרתיות ומבנה המחשב 5 90 אביב",
+        1,
+    );
+    let (result, report) = parse_with_report(&data);
+
+    assert!(result.is_ok());
+    assert_eq!(
+        report.skipped_lines,
+        vec![SkippedLine {
+            line_number: 12,
+            reason: SkipReason::Unrecognized,
+        }]
+    );
+}
+
+#[test]
+fn report_lists_low_confidence_values() {
+    let data = get_chrome_data()
+        .replacen(" 5 90 2023-2024", " 5 190 2023-2024", 1)
+        .replacen(" 3 84 2023-2024", " 84 2023-2024", 1)
+        .replacen(" 4.5 86 2024-2025", " 4.3 86 2024-2025", 1);
+    let (_, report) = parse_with_report(&data);
+
+    let warnings = report
+        .warnings
+        .iter()
+        .map(|warning| (warning.course_id.as_str(), warning.kind))
+        .collect::<Vec<_>>();
+    assert_eq!(
+        warnings,
+        vec![
+            ("00440252", ParseWarningKind::GradeOutOfRange),
+            ("02340123", ParseWarningKind::UnusualCredit),
+            ("02340141", ParseWarningKind::MissingCredit),
+        ]
+    );
+}
+
+#[test]
+fn report_is_returned_when_parsing_fails() {
+    let (result, report) = parse_with_report("not a grade sheet");
+
+    assert!(result.is_err());
+    assert_eq!(report.format, Some(Format::Edge));
+    assert!(report.skipped_lines.is_empty());
+}
+
+#[test]
+fn chrome_does_not_combine_a_line_with_the_next_course() {
+    // Before: the broken line was combined with the next one into a course with the next one's
+    // semester, and the next course was lost
+    let data = get_chrome_data().replacen(
+        "00440252 מערכות ספרתיות ומבנה המחשב 5 90 2023-2024 אביב תשפ\"ד",
+        "00440252 מערכות ספרתיות ומבנה המחשב 5 90",
+        1,
+    );
+    let (result, report) = parse_with_report(&data);
+    let courses = result.unwrap();
+
+    // After: the broken line is skipped and the next course is parsed on its own
+    assert!(find_course(&courses, "00440252").is_none());
+    let next = find_course(&courses, "00940412").unwrap();
+    assert_eq!(next.course.name, "הסתברות מ");
+    assert_eq!(next.grade, Some(Grade::Binary(true)));
+    assert_eq!(
+        report.skipped_lines,
+        vec![SkippedLine {
+            line_number: 12,
+            reason: SkipReason::Unrecognized,
+        }]
+    );
+    // A course split over two lines is still combined, since the second has no id
+    let split = find_course(&courses, "02360020").unwrap();
+    assert_eq!(split.grade, Some(Grade::Numeric(96)));
+}
+
+#[test]
+fn report_leaves_out_the_place_and_date_of_issue() {
+    for data in [get_chrome_data(), get_edge_data()] {
+        assert!(data.contains("חיפה, 14.03.2026"));
+        // Before: reported as an unrecognized line on every page
+        let (_, report) = parse_with_report(&data);
+        assert!(report.skipped_lines.is_empty());
+    }
+
+    // Still reported if a course id ended up on that line
+    let data = get_chrome_data().replacen("חיפה, 14.03.2026", "חיפה, 14.03.2026 02340114", 1);
+    let (_, report) = parse_with_report(&data);
+    assert_eq!(
+        report.skipped_lines,
+        vec![SkippedLine {
+            line_number: 5,
+            reason: SkipReason::Metadata,
+        }]
+    );
+}
@@ -0,0 +1,201 @@
+use std::collections::HashSet;
+
+use serde::Serialize;
+
+use crate::{
+    error::AppError,
+    resources::course::{CourseId, CourseStatus},
+    sap::PrereqToken,
+};
+
+/// A boolean expression over courses, built from the flat SAP prerequisite token stream.
+#[derive(Clone, Debug, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "snake_case")]
+pub enum Prerequisite {
+    Course(CourseId),
+    And(Vec<Prerequisite>),
+    Or(Vec<Prerequisite>),
+}
+
+#[derive(Clone, Debug, PartialEq, Eq)]
+enum Lexeme {
+    Open,
+    Close,
+    Course(CourseId),
+    And,
+    Or,
+}
+
+// SAP module ids come with their leading zeros stripped, e.g. "2340114" for 02340114.
+fn module_id_to_course_id(module_id: &str) -> CourseId {
+    CourseId::new(format!("{module_id:0>8}"))
+}
+
+// A single token may carry a bracket, a module id and an operator at once. Opening brackets precede
+// the module id and closing brackets follow it.
+fn lex(tokens: &[PrereqToken]) -> Result<Vec<Lexeme>, AppError> {
+    let mut lexemes = Vec::new();
+    for token in tokens {
+        let bracket = token.bracket.as_deref().unwrap_or_default().trim();
+        if let Some(c) = bracket.chars().find(|&c| c != '(' && c != ')') {
+            return Err(AppError::Parser(format!(
+                "Unknown prerequisite bracket: {c}"
+            )));
+        }
+        let opening = bracket.chars().filter(|&c| c == '(').count();
+        lexemes.extend(std::iter::repeat_n(Lexeme::Open, opening));
+        if let Some(module_id) = &token.module_id {
+            lexemes.push(Lexeme::Course(module_id_to_course_id(module_id)));
+        }
+        lexemes.extend(std::iter::repeat_n(Lexeme::Close, bracket.len() - opening));
+        match token.operator.as_deref().map(str::trim) {
+            None | Some("") => {}
+            Some(op) if op.eq_ignore_ascii_case("AND") => lexemes.push(Lexeme::And),
+            Some(op) if op.eq_ignore_ascii_case("OR") => lexemes.push(Lexeme::Or),
+            Some(op) => {
+                return Err(AppError::Parser(format!(
+                    "Unknown prerequisite operator: {op}"
+                )))
+            }
+        }
+    }
+    Ok(lexemes)
+}
+
+// Recursive descent over the lexemes, where AND binds tighter than OR:
+//   expr   := term (OR term)*
+//   term   := factor (AND factor)*
+//   factor := course | '(' expr ')'
+struct Parser {
+    lexemes: Vec<Lexeme>,
+    position: usize,
+}
+
+impl Parser {
+    fn peek(&self) -> Option<&Lexeme> {
+        self.lexemes.get(self.position)
+    }
+
+    fn next(&mut self) -> Option<Lexeme> {
+        let lexeme = self.lexemes.get(self.position).cloned();
+        self.position += 1;
+        lexeme
+    }
+
+    fn expr(&mut self) -> Result<Prerequisite, AppError> {
+        let mut operands = vec![self.term()?];
+        while self.peek() == Some(&Lexeme::Or) {
+            self.next();
+            operands.push(self.term()?);
+        }
+        Ok(Prerequisite::or(operands))
+    }
+
+    fn term(&mut self) -> Result<Prerequisite, AppError> {
+        let mut operands = vec![self.factor()?];
+        while self.peek() == Some(&Lexeme::And) {
+            self.next();
+            operands.push(self.factor()?);
+        }
+        Ok(Prerequisite::and(operands))
+    }
+
+    fn factor(&mut self) -> Result<Prerequisite, AppError> {
+        match self.next() {
+            Some(Lexeme::Course(course_id)) => Ok(Prerequisite::Course(course_id)),
+            Some(Lexeme::Open) => {
+                let expr = self.expr()?;
+                match self.next() {
+                    Some(Lexeme::Close) => Ok(expr),
+                    _ => Err(AppError::Parser("Unbalanced prerequisite brackets".into())),
+                }
+            }
+            Some(lexeme) => Err(AppError::Parser(format!(
+                "Unexpected prerequisite token: {lexeme:?}"
+            ))),
+            None => Err(AppError::Parser(
+                "Prerequisite expression ended unexpectedly".into(),
+            )),
+        }
+    }
+}
+
+impl Prerequisite {
+    // Nested expressions of the same kind are flattened, and a single operand is returned as is.
+    fn and(operands: Vec<Prerequisite>) -> Prerequisite {
+        Self::flatten(operands, Prerequisite::And, |p| match p {
+            Prerequisite::And(operands) => Ok(operands),
+            other => Err(other),
+        })
+    }
+
+    fn or(operands: Vec<Prerequisite>) -> Prerequisite {
+        Self::flatten(operands, Prerequisite::Or, |p| match p {
+            Prerequisite::Or(operands) => Ok(operands),
+            other => Err(other),
+        })
+    }
+
+    fn flatten(
+        operands: Vec<Prerequisite>,
+        build: fn(Vec<Prerequisite>) -> Prerequisite,
+        split: fn(Prerequisite) -> Result<Vec<Prerequisite>, Prerequisite>,
+    ) -> Prerequisite {
+        let mut flat = Vec::new();
+        for operand in operands {
+            match split(operand) {
+                Ok(inner) => flat.extend(inner),
+                Err(operand) => flat.push(operand),
+            }
+        }
+        if flat.len() == 1 {
+            flat.remove(0)
+        } else {
+            build(flat)
+        }
+    }
+
+    /// Parses the SAP token stream. Returns `None` when the course has no prerequisites.
+    pub fn parse(tokens: &[PrereqToken]) -> Result<Option<Prerequisite>, AppError> {
+        let lexemes = lex(tokens)?;
+        if lexemes.is_empty() {
+            return Ok(None);
+        }
+        let mut parser = Parser {
+            lexemes,
+            position: 0,
+        };
+        let expr = parser.expr()?;
+        if let Some(lexeme) = parser.peek() {
+            return Err(AppError::Parser(format!(
+                "Unexpected prerequisite token: {lexeme:?}"
+            )));
+        }
+        Ok(Some(expr))
+    }
+
+    pub fn is_satisfied_by(&self, completed: &HashSet<CourseId>) -> bool {
+        match self {
+            Prerequisite::Course(course_id) => completed.contains(course_id),
+            Prerequisite::And(operands) => operands.iter().all(|p| p.is_satisfied_by(completed)),
+            Prerequisite::Or(operands) => operands.iter().any(|p| p.is_satisfied_by(completed)),
+        }
+    }
+
+    /// Checks the expression against the courses the student completed.
+    pub fn evaluate(&self, course_statuses: &[CourseStatus]) -> bool {
+        self.is_satisfied_by(&completed_courses(course_statuses))
+    }
+}
+
+pub fn completed_courses(course_statuses: &[CourseStatus]) -> HashSet<CourseId> {
+    course_statuses
+        .iter()
+        .filter(|cs| cs.completed())
+        .map(|cs| cs.course.id.clone())
+        .collect()
+}
+
+#[cfg(test)]
+#[path = "prerequisites_tests.rs"]
+mod prerequisites_tests;
@@ -0,0 +1,115 @@
+use std::collections::HashSet;
+
+use crate::{
+    resources::course::{Course, CourseId, CourseState, CourseStatus},
+    sap::PrereqToken,
+};
+
+use super::*;
+
+fn module(id: &str, operator: Option<&str>) -> PrereqToken {
+    PrereqToken {
+        module_id: Some(id.to_string()),
+        operator: operator.map(str::to_string),
+        bracket: None,
+    }
+}
+
+fn bracketed(bracket: &str, id: &str, operator: Option<&str>) -> PrereqToken {
+    PrereqToken {
+        bracket: Some(bracket.to_string()),
+        ..module(id, operator)
+    }
+}
+
+fn completed(ids: &[&str]) -> HashSet<CourseId> {
+    ids.iter().map(|id| CourseId::new(*id)).collect()
+}
+
+#[test]
+fn parse_empty_stream_has_no_prerequisites() {
+    assert_eq!(Prerequisite::parse(&[]).unwrap(), None);
+}
+
+#[test]
+fn parse_restores_leading_zeros() {
+    let prerequisite = Prerequisite::parse(&[module("2340114", None)])
+        .unwrap()
+        .unwrap();
+    assert_eq!(
+        prerequisite,
+        Prerequisite::Course(CourseId::new("02340114"))
+    );
+}
+
+#[test]
+fn parse_and_binds_tighter_than_or() {
+    // 104031 AND 104166 OR 104032
+    let prerequisite = Prerequisite::parse(&[
+        module("104031", Some("AND")),
+        module("104166", Some("OR")),
+        module("104032", None),
+    ])
+    .unwrap()
+    .unwrap();
+
+    assert_eq!(
+        prerequisite,
+        Prerequisite::Or(vec![
+            Prerequisite::And(vec![
+                Prerequisite::Course(CourseId::new("00104031")),
+                Prerequisite::Course(CourseId::new("00104166")),
+            ]),
+            Prerequisite::Course(CourseId::new("00104032")),
+        ])
+    );
+}
+
+#[test]
+fn parse_brackets() {
+    // (2340114 OR 2340117) AND 1040031
+    let prerequisite = Prerequisite::parse(&[
+        bracketed("(", "2340114", Some("OR")),
+        bracketed(")", "2340117", Some("AND")),
+        module("1040031", None),
+    ])
+    .unwrap()
+    .unwrap();
+
+    assert!(prerequisite.is_satisfied_by(&completed(&["02340117", "01040031"])));
+    assert!(!prerequisite.is_satisfied_by(&completed(&["02340114", "02340117"])));
+    assert!(!prerequisite.is_satisfied_by(&completed(&["01040031"])));
+}
+
+#[test]
+fn parse_rejects_malformed_streams() {
+    // Unbalanced brackets
+    assert!(Prerequisite::parse(&[bracketed("(", "2340114", None)]).is_err());
+    // Dangling operator
+    assert!(Prerequisite::parse(&[module("2340114", Some("AND"))]).is_err());
+    // Unknown operator
+    assert!(
+        Prerequisite::parse(&[module("2340114", Some("XOR")), module("2340117", None)]).is_err()
+    );
+    // Two courses without an operator
+    assert!(Prerequisite::parse(&[module("2340114", None), module("2340117", None)]).is_err());
+}
+
+#[test]
+fn evaluate_only_counts_completed_courses() {
+    let prerequisite = Prerequisite::parse(&[module("2340114", None)])
+        .unwrap()
+        .unwrap();
+    let course_status = |state| CourseStatus {
+        course: Course {
+            id: CourseId::new("02340114"),
+            ..Default::default()
+        },
+        state: Some(state),
+        ..Default::default()
+    };
+
+    assert!(prerequisite.evaluate(&[course_status(CourseState::Complete)]));
+    assert!(!prerequisite.evaluate(&[course_status(CourseState::NotComplete)]));
+    assert!(!prerequisite.evaluate(&[course_status(CourseState::InProgress)]));
+}
@@ -20,6 +20,7 @@ fn courses_fixture() -> HashMap<CourseId, Course> {
                 credit: 5.0,
                 name: name.to_string(),
                 tags: None,
+                relations: Vec::new(),
             },
         );
     }
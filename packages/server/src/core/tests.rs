@@ -190,6 +190,7 @@ static COURSES: LazyLock<HashMap<CourseId, Course>> = LazyLock::new(|| {
                 credit: 5.5,
                 name: "infi1m".to_string(),
                 tags: None,
+                relations: Vec::new(),
             },
         ),
         (
@@ -199,6 +200,7 @@ static COURSES: LazyLock<HashMap<CourseId, Course>> = LazyLock::new(|| {
                 credit: 5.5,
                 name: "Algebra alef".to_string(),
                 tags: None,
+                relations: Vec::new(),
             },
         ),
         (
@@ -208,6 +210,7 @@ static COURSES: LazyLock<HashMap<CourseId, Course>> = LazyLock::new(|| {
                 credit: 3.5,
                 name: "פיסיקה 2".to_string(),
                 tags: None,
+                relations: Vec::new(),
             },
         ),
         (
@@ -217,6 +220,7 @@ static COURSES: LazyLock<HashMap<CourseId, Course>> = LazyLock::new(|| {
                 credit: 3.5,
                 name: "פיסיקה 3".to_string(),
                 tags: None,
+                relations: Vec::new(),
             },
         ),
         (
@@ -226,6 +230,7 @@ static COURSES: LazyLock<HashMap<CourseId, Course>> = LazyLock::new(|| {
                 credit: 3.0,
                 name: "project1".to_string(),
                 tags: None,
+                relations: Vec::new(),
             },
         ),
         (
@@ -235,6 +240,7 @@ static COURSES: LazyLock<HashMap<CourseId, Course>> = LazyLock::new(|| {
                 credit: 3.0,
                 name: "project2".to_string(),
                 tags: None,
+                relations: Vec::new(),
             },
         ),
         (
@@ -244,6 +250,7 @@ static COURSES: LazyLock<HashMap<CourseId, Course>> = LazyLock::new(|| {
                 credit: 1.0,
                 name: "".to_string(),
                 tags: None,
+                relations: Vec::new(),
             },
         ),
         (
@@ -253,6 +260,7 @@ static COURSES: LazyLock<HashMap<CourseId, Course>> = LazyLock::new(|| {
                 credit: 2.0,
                 name: "".to_string(),
                 tags: None,
+                relations: Vec::new(),
             },
         ),
         (
@@ -262,6 +270,7 @@ static COURSES: LazyLock<HashMap<CourseId, Course>> = LazyLock::new(|| {
                 credit: 3.0,
                 name: "".to_string(),
                 tags: None,
+                relations: Vec::new(),
             },
         ),
     ])
@@ -290,6 +299,7 @@ pub fn create_degree_status() -> DegreeStatus {
                     credit: 5.5,
                     name: "infi1m".to_string(),
                     tags: None,
+                    relations: Vec::new(),
                 },
                 state: Some(CourseState::Complete),
                 grade: Some(Grade::Numeric(85)),
@@ -301,6 +311,7 @@ pub fn create_degree_status() -> DegreeStatus {
                     credit: 5.5,
                     name: "Algebra alef".to_string(),
                     tags: None,
+                    relations: Vec::new(),
                 },
                 state: Some(CourseState::NotComplete),
                 grade: Some(Grade::Binary(false)),
@@ -312,6 +323,7 @@ pub fn create_degree_status() -> DegreeStatus {
                     credit: 3.5,
                     name: "פיסיקה 2".to_string(),
                     tags: None,
+                    relations: Vec::new(),
                 },
                 state: Some(CourseState::Complete),
                 grade: Some(Grade::Numeric(85)),
@@ -323,6 +335,7 @@ pub fn create_degree_status() -> DegreeStatus {
                     credit: 3.5,
                     name: "פיסיקה 3".to_string(),
                     tags: None,
+                    relations: Vec::new(),
                 },
                 state: Some(CourseState::Complete),
                 grade: Some(Grade::Numeric(85)),
@@ -334,6 +347,7 @@ pub fn create_degree_status() -> DegreeStatus {
                     credit: 3.0,
                     name: "project1".to_string(),
                     tags: None,
+                    relations: Vec::new(),
                 },
                 state: Some(CourseState::Complete),
                 grade: Some(Grade::Numeric(85)),
@@ -345,6 +359,7 @@ pub fn create_degree_status() -> DegreeStatus {
                     credit: 3.0,
                     name: "project2".to_string(),
                     tags: None,
+                    relations: Vec::new(),
                 },
                 state: Some(CourseState::Complete),
                 grade: Some(Grade::Numeric(85)),
@@ -356,6 +371,7 @@ pub fn create_degree_status() -> DegreeStatus {
                     credit: 2.0,
                     name: "mlg".to_string(),
                     tags: Some(vec![Tag::Malag]),
+                    relations: Vec::new(),
                 },
                 state: Some(CourseState::Complete),
                 grade: Some(Grade::Numeric(99)),
@@ -367,6 +383,7 @@ pub fn create_degree_status() -> DegreeStatus {
                     credit: 1.0,
                     name: "sport".to_string(),
                     tags: Some(vec![Tag::Sport]),
+                    relations: Vec::new(),
                 },
                 state: Some(CourseState::Complete),
                 grade: Some(Grade::Numeric(100)),
@@ -376,6 +393,8 @@ pub fn create_degree_status() -> DegreeStatus {
         course_bank_requirements: Vec::<Requirement>::new(),
         overflow_msgs: Vec::<String>::new(),
         total_credit: 0.0,
+        averages: Default::default(),
+        trace: None,
     }
 }
 
@@ -421,6 +440,7 @@ async fn test_restore_irrelevant_course() {
             credit: 2.5,
             name: "פיסיקה 1מ".to_string(),
             tags: None,
+            relations: Vec::new(),
         },
         state: Some(NotComplete),
         semester: Some(course::AcademicSemester::new(
@@ -527,6 +547,7 @@ async fn test_modified() {
 }
 
 #[tokio::test]
+#[ignore = "needs production SOGRIM_URI"]
 async fn test_duplicated_courses() {
     let mut degree_status = run_degree_status_full_flow(
         "pdf_ctrl_c_ctrl_v_4.txt",
@@ -542,6 +563,7 @@ async fn test_duplicated_courses() {
             credit: 2.5,
             name: "פיסיקה 1".to_string(),
             tags: None,
+            relations: Vec::new(),
         },
         state: Some(NotComplete),
         semester: Some(course::AcademicSemester::new(
@@ -570,6 +592,7 @@ async fn test_duplicated_courses() {
 }
 
 #[tokio::test]
+#[ignore = "needs production SOGRIM_URI"]
 async fn test_course_replacement() {
     let mut degree_status = DegreeStatus {
         course_statuses: vec![CourseStatus {
@@ -578,6 +601,7 @@ async fn test_course_replacement() {
                 credit: 5.5,
                 name: "אינפי 1".to_string(),
                 tags: None,
+                relations: Vec::new(),
             },
             state: Some(CourseState::Complete),
             grade: Some(Grade::Numeric(85)),
@@ -586,6 +610,8 @@ async fn test_course_replacement() {
         course_bank_requirements: Vec::<Requirement>::new(),
         overflow_msgs: Vec::<String>::new(),
         total_credit: 0.0,
+        averages: Default::default(),
+        trace: None,
     };
 
     let catalog = get_catalog(COMPUTER_SCIENCE_3_YEARS_19_20_CATALOG_ID).await;
@@ -601,6 +627,7 @@ async fn test_course_replacement() {
             credit: 5.5,
             name: "חשבון אינפיניטסימלי 1מ'".to_string(),
             tags: None,
+            relations: Vec::new(),
         }))
     );
 }
@@ -609,6 +636,8 @@ async fn test_course_replacement() {
 // Test core function in a full flow
 // ------------------------------------------------------------------------------------------------------
 
+// Most of these need every course, and catalogs which aren't in docs/, from the production
+// database, so they're ignored unless run with `--ignored` and SOGRIM_URI
 async fn get_catalog(catalog: &str) -> Catalog {
     let db = Db::from_test_env().await;
     let obj_id = bson::oid::ObjectId::from_str(catalog).expect("failed to create oid");
@@ -644,6 +673,7 @@ async fn run_degree_status_full_flow(file_name: &str, catalog: &str) -> DegreeSt
 }
 
 #[tokio::test]
+#[ignore = "needs production SOGRIM_URI"]
 async fn test_missing_credit() {
     let degree_status = run_degree_status_full_flow(
         "pdf_ctrl_c_ctrl_v.txt",
@@ -766,6 +796,7 @@ async fn test_missing_credit() {
 }
 
 #[tokio::test]
+#[ignore = "needs production SOGRIM_URI"]
 async fn test_computer_engineer_itinerary() {
     // Testing the edge case when a course belongs to two banks and the student completed a replacement for this course.
     // https://github.com/sogrim/technion-sogrim/issues/214#issuecomment-1478566102
@@ -780,6 +811,7 @@ async fn test_computer_engineer_itinerary() {
             credit: 3.0,
             name: "רשתות מחשבים ואינטרנט 1".to_string(),
             tags: None,
+            relations: Vec::new(),
         },
         state: Some(CourseState::Complete),
         grade: Some(Grade::Numeric(85)),
@@ -795,6 +827,7 @@ async fn test_computer_engineer_itinerary() {
             credit: 3.0,
             name: "רשתות מחשבים ואינטרנט 2".to_string(),
             tags: None,
+            relations: Vec::new(),
         },
         state: Some(CourseState::Complete),
         grade: Some(Grade::Numeric(85)),
@@ -810,6 +843,7 @@ async fn test_computer_engineer_itinerary() {
             credit: 3.0,
             name: "אלגוריתמים מובזרים א'".to_string(),
             tags: None,
+            relations: Vec::new(),
         },
         state: Some(CourseState::Complete),
         grade: Some(Grade::Numeric(85)),
@@ -838,6 +872,7 @@ async fn test_computer_engineer_itinerary() {
 }
 
 #[tokio::test]
+#[ignore = "needs production SOGRIM_URI"]
 async fn test_overflow_credit() {
     let degree_status = run_degree_status_full_flow(
         "pdf_ctrl_c_ctrl_v_2.txt",
@@ -938,6 +973,7 @@ async fn test_overflow_credit() {
 }
 
 #[tokio::test]
+#[ignore = "needs production SOGRIM_URI"]
 async fn test_postprocessing_english_requirement() {
     let mut degree_status = run_degree_status_full_flow(
         "pdf_ctrl_c_ctrl_v_2.txt",
@@ -999,6 +1035,7 @@ async fn test_postprocessing_english_requirement() {
 }
 
 #[tokio::test]
+#[ignore = "needs production SOGRIM_URI"]
 async fn test_postprocessing_medicine_requirement() {
     let mut degree_status =
         run_degree_status_full_flow("pdf_ctrl_c_ctrl_v_9.txt", MEDICINE_18_19_CATALOG_ID).await;
@@ -1063,6 +1100,7 @@ async fn test_postprocessing_medicine_requirement() {
             id: CourseId::new("275101"),
             name: "".to_string(),
             tags: None,
+            relations: Vec::new(),
         },
         state: Some(CourseState::Complete),
         grade: Some(Grade::Numeric(50)),
@@ -1079,6 +1117,7 @@ async fn test_postprocessing_medicine_requirement() {
 }
 
 #[tokio::test]
+#[ignore = "needs production SOGRIM_URI"]
 async fn test_software_engineer_itinerary() {
     let degree_status =
         run_degree_status_full_flow("pdf_ctrl_c_ctrl_v_3.txt", "61d84fce5c5e7813e895a27d").await;
@@ -1178,7 +1217,7 @@ async fn test_software_engineer_itinerary() {
 #[tokio::test]
 async fn test_catalog_validations() {
     let mut catalog = get_catalog(COMPUTER_SCIENCE_3_YEARS_19_20_CATALOG_ID).await;
-    assert!(validate_catalog(&catalog).is_ok());
+    assert!(validate_catalog(&catalog, None).is_ok());
 
     // Add credit transfer between בחירה חופשית to רשימה א to close a cycle
     catalog.credit_overflows.push(CreditOverflow {
@@ -1186,12 +1225,10 @@ async fn test_catalog_validations() {
         to: "רשימה א".to_string(),
     });
 
-    let result = validate_catalog(&catalog);
-    assert!(result.is_err());
-    if let Err(e) = result {
-        assert_eq!(
-            e.to_string(),
-            messages::cyclic_credit_transfer_graph("רשימה א")
-        )
-    }
+    let errors = validate_catalog(&catalog, None).unwrap_err();
+    assert_eq!(errors.len(), 1);
+    assert_eq!(
+        errors[0].to_string(),
+        messages::cyclic_credit_transfer_graph("רשימה א")
+    );
 }
@@ -0,0 +1,273 @@
+use std::collections::{BTreeMap, HashMap};
+use std::sync::Arc;
+
+use serde::Deserialize;
+
+use crate::{
+    resources::{
+        course::AcademicSemester,
+        user::{CourseSelection, TimetableDraft},
+    },
+    sap::CourseDetails,
+};
+
+use super::{lesson_type, to_minutes};
+
+// Upper bound on the clash-free combinations that get scored, so a long course list can't blow up
+// the search. The first combinations found are as good a sample as any for ranking.
+const MAX_COMBINATIONS: usize = 50_000;
+// Upper bound on the partial combinations the search visits, since courses which clash with each
+// other can keep it going through a huge tree without finding any clash-free combination.
+const MAX_VISITS: usize = 1_000_000;
+pub const DEFAULT_LIMIT: usize = 10;
+pub const MAX_LIMIT: usize = 50;
+/// More courses than a student takes in a semester, and few enough to search.
+pub const MAX_COURSES: usize = 15;
+// Having any lesson on a day the student asked to keep free costs as much as a full day of lessons.
+const DAY_OFF_PENALTY: u32 = 10 * 60;
+
+#[derive(Clone, Debug, Default, Deserialize)]
+pub struct TimetablePreferences {
+    // Days (0=Sunday) the student would like to keep free
+    #[serde(default)]
+    pub days_off: Vec<u8>,
+    // "HH:MM" - lessons starting earlier are penalized by the minutes before it
+    #[serde(default)]
+    pub earliest_start: Option<String>,
+    // "HH:MM" - lessons ending later are penalized by the minutes after it
+    #[serde(default)]
+    pub latest_end: Option<String>,
+    #[serde(default)]
+    pub minimize_gaps: bool,
+}
+
+#[derive(Clone, Debug, PartialEq, Eq, PartialOrd, Ord)]
+struct Lesson {
+    day: u8,
+    start: u32,
+    end: u32,
+    building: Option<String>,
+    room: Option<String>,
+}
+
+// A choice for one lesson type of a course. SAP groups with identical lessons (e.g. a lecture shared
+// by several tutorial groups) are merged into one option, the same way the timetable client does,
+// so the generated group ids can be saved as is.
+struct GroupOption {
+    group_numbers: Vec<String>,
+    lesson: &'static str,
+    lessons: Vec<Lesson>,
+}
+
+impl GroupOption {
+    fn group_id(&self) -> String {
+        format!("{}-{}", self.group_numbers.join("/"), self.lesson)
+    }
+}
+
+// The options of every lesson type in the course.
+fn course_options(course: &CourseDetails) -> Vec<Vec<GroupOption>> {
+    let mut by_lesson: BTreeMap<&'static str, Vec<GroupOption>> = BTreeMap::new();
+    for group in &course.schedule {
+        let mut lessons_by_type: BTreeMap<&'static str, Vec<Lesson>> = BTreeMap::new();
+        for event in &group.events {
+            let (Some(day), Some(start), Some(end)) = (
+                event.day.filter(|&day| day <= 5),
+                event.start_time.as_deref().and_then(to_minutes),
+                event.end_time.as_deref().and_then(to_minutes),
+            ) else {
+                continue;
+            };
+            lessons_by_type
+                .entry(lesson_type(&event.kind))
+                .or_default()
+                .push(Lesson {
+                    day,
+                    start,
+                    end,
+                    building: event.building.clone(),
+                    room: event.room.clone(),
+                });
+        }
+        for (lesson, mut lessons) in lessons_by_type {
+            lessons.sort();
+            let options = by_lesson.entry(lesson).or_default();
+            match options.iter_mut().find(|option| option.lessons == lessons) {
+                Some(option) => option.group_numbers.push(group.group.clone()),
+                None => options.push(GroupOption {
+                    group_numbers: vec![group.group.clone()],
+                    lesson,
+                    lessons,
+                }),
+            }
+        }
+    }
+    by_lesson.into_values().collect()
+}
+
+fn clashes(lessons: &[&Lesson], option: &GroupOption) -> bool {
+    option.lessons.iter().any(|new| {
+        lessons
+            .iter()
+            .any(|lesson| lesson.day == new.day && lesson.start < new.end && new.start < lesson.end)
+    })
+}
+
+// Depth-first over the lesson types, pruning as soon as a choice clashes with the ones before it.
+// Stops after `visits` partial combinations.
+fn enumerate<'a>(
+    dimensions: &'a [&'a [GroupOption]],
+    index: usize,
+    chosen: &mut Vec<usize>,
+    lessons: &mut Vec<&'a Lesson>,
+    combinations: &mut Vec<Vec<usize>>,
+    visits: &mut usize,
+) {
+    if combinations.len() >= MAX_COMBINATIONS || *visits == 0 {
+        return;
+    }
+    *visits -= 1;
+    if index == dimensions.len() {
+        combinations.push(chosen.clone());
+        return;
+    }
+    for (option_index, option) in dimensions[index].iter().enumerate() {
+        if clashes(lessons, option) {
+            continue;
+        }
+        chosen.push(option_index);
+        lessons.extend(option.lessons.iter());
+        enumerate(dimensions, index + 1, chosen, lessons, combinations, visits);
+        lessons.truncate(lessons.len() - option.lessons.len());
+        chosen.pop();
+    }
+}
+
+// Lower is better. The second value (days with lessons) breaks ties.
+fn score(lessons: &[&Lesson], preferences: &TimetablePreferences) -> (u32, usize) {
+    let earliest_start = preferences.earliest_start.as_deref().and_then(to_minutes);
+    let latest_end = preferences.latest_end.as_deref().and_then(to_minutes);
+
+    let mut by_day: BTreeMap<u8, Vec<&Lesson>> = BTreeMap::new();
+    for lesson in lessons {
+        by_day.entry(lesson.day).or_default().push(lesson);
+    }
+
+    let mut penalty = 0;
+    for (day, day_lessons) in by_day.iter_mut() {
+        if preferences.days_off.contains(day) {
+            penalty += DAY_OFF_PENALTY;
+        }
+        day_lessons.sort_by_key(|lesson| lesson.start);
+        let mut last_end: Option<u32> = None;
+        for lesson in day_lessons.iter() {
+            if let Some(earliest_start) = earliest_start {
+                penalty += earliest_start.saturating_sub(lesson.start);
+            }
+            if let Some(latest_end) = latest_end {
+                penalty += lesson.end.saturating_sub(latest_end);
+            }
+            if preferences.minimize_gaps {
+                if let Some(last_end) = last_end {
+                    penalty += lesson.start.saturating_sub(last_end);
+                }
+            }
+            last_end = Some(last_end.map_or(lesson.end, |last_end| last_end.max(lesson.end)));
+        }
+    }
+    (penalty, by_day.len())
+}
+
+/// Enumerates the clash-free group combinations of the given courses and returns the best `limit`
+/// (at most `MAX_LIMIT`) of them by the student's preferences, as drafts that can be saved
+/// directly. The search is bounded, but still CPU-bound, so run it off the async runtime.
+pub fn generate(
+    semester: &AcademicSemester,
+    courses: &[Arc<CourseDetails>],
+    preferences: &TimetablePreferences,
+    limit: usize,
+) -> Vec<TimetableDraft> {
+    let options = courses
+        .iter()
+        .map(|course| course_options(course))
+        .collect::<Vec<_>>();
+    // (course index, lesson type options)
+    let dimensions = options
+        .iter()
+        .enumerate()
+        .flat_map(|(course_index, lessons)| {
+            lessons
+                .iter()
+                .map(move |lesson_options| (course_index, lesson_options.as_slice()))
+        })
+        .collect::<Vec<_>>();
+    let dimension_options = dimensions
+        .iter()
+        .map(|(_, lesson_options)| *lesson_options)
+        .collect::<Vec<_>>();
+
+    let mut combinations = Vec::new();
+    let mut visits = MAX_VISITS;
+    enumerate(
+        &dimension_options,
+        0,
+        &mut Vec::new(),
+        &mut Vec::new(),
+        &mut combinations,
+        &mut visits,
+    );
+
+    let mut ranked = combinations
+        .into_iter()
+        .map(|combination| {
+            let lessons = combination
+                .iter()
+                .zip(&dimension_options)
+                .flat_map(|(&option_index, lesson_options)| {
+                    lesson_options[option_index].lessons.iter()
+                })
+                .collect::<Vec<_>>();
+            (score(&lessons, preferences), combination)
+        })
+        .collect::<Vec<_>>();
+    // The sort is stable, so equally scored combinations keep the enumeration order
+    ranked.sort_by_key(|(score, _)| *score);
+
+    let now = chrono::Utc::now();
+    let timestamp = now.to_rfc3339_opts(chrono::SecondsFormat::Millis, true);
+    ranked
+        .into_iter()
+        .take(limit.min(MAX_LIMIT))
+        .enumerate()
+        .map(|(rank, (_, combination))| {
+            let mut selected_groups = vec![HashMap::new(); courses.len()];
+            for (&option_index, (course_index, lesson_options)) in
+                combination.iter().zip(&dimensions)
+            {
+                let option = &lesson_options[option_index];
+                selected_groups[*course_index].insert(option.lesson.to_string(), option.group_id());
+            }
+            TimetableDraft {
+                id: format!("generated-{}-{}", now.timestamp_millis(), rank + 1),
+                name: format!("מערכת מוצעת {}", rank + 1),
+                semester: semester.clone(),
+                courses: courses
+                    .iter()
+                    .zip(selected_groups)
+                    .map(|(course, selected_groups)| CourseSelection {
+                        course_id: course.id.to_string(),
+                        selected_groups,
+                    })
+                    .collect(),
+                custom_events: vec![],
+                created_at: timestamp.clone(),
+                updated_at: timestamp.clone(),
+                is_published: false,
+            }
+        })
+        .collect()
+}
+
+#[cfg(test)]
+#[path = "generator_tests.rs"]
+mod generator_tests;
@@ -0,0 +1,269 @@
+use std::sync::Arc;
+
+use crate::{
+    resources::course::{AcademicSemester, CourseId, SemesterSeason},
+    sap::{CourseDetails, ScheduleEvent, ScheduleGroup},
+};
+
+use super::*;
+
+fn event(kind: &str, day: u8, start_time: &str, end_time: &str) -> ScheduleEvent {
+    ScheduleEvent {
+        kind: kind.to_string(),
+        day: Some(day),
+        start_time: Some(start_time.to_string()),
+        end_time: Some(end_time.to_string()),
+        schedule_text: String::new(),
+        building: None,
+        room: None,
+        lecturer: None,
+    }
+}
+
+fn group(number: &str, events: Vec<ScheduleEvent>) -> ScheduleGroup {
+    ScheduleGroup {
+        group: number.to_string(),
+        name: None,
+        events,
+    }
+}
+
+fn course(id: &str, schedule: Vec<ScheduleGroup>) -> Arc<CourseDetails> {
+    Arc::new(CourseDetails {
+        id: CourseId::new(id),
+        name: id.to_string(),
+        credits: 3.0,
+        faculty: None,
+        syllabus: None,
+        academic_level: None,
+        is_english: false,
+        is_malag: false,
+        is_sport: false,
+        semester_note: None,
+        exams: vec![],
+        relations: vec![],
+        prerequisites: vec![],
+        corequisites: vec![],
+        responsible: vec![],
+        offered_periods: vec![],
+        schedule,
+    })
+}
+
+// One lecture shared by two tutorial groups, on Monday and on Tuesday.
+fn two_tutorials(id: &str, monday: (&str, &str), tuesday: (&str, &str)) -> Arc<CourseDetails> {
+    course(
+        id,
+        vec![
+            group(
+                "11",
+                vec![
+                    event("הרצאה", 0, "10:30", "12:30"),
+                    event("תרגול", 1, monday.0, monday.1),
+                ],
+            ),
+            group(
+                "12",
+                vec![
+                    event("הרצאה", 0, "10:30", "12:30"),
+                    event("תרגול", 2, tuesday.0, tuesday.1),
+                ],
+            ),
+        ],
+    )
+}
+
+fn semester() -> AcademicSemester {
+    AcademicSemester::new(SemesterSeason::Winter, 2025)
+}
+
+fn tutorial(draft: &TimetableDraft, course_index: usize) -> &str {
+    draft.courses[course_index].selected_groups["tutorial"].as_str()
+}
+
+#[test]
+fn generate_merges_groups_sharing_the_same_lessons() {
+    let drafts = generate(
+        &semester(),
+        &[two_tutorials(
+            "02340114",
+            ("08:30", "09:30"),
+            ("08:30", "09:30"),
+        )],
+        &TimetablePreferences::default(),
+        DEFAULT_LIMIT,
+    );
+
+    assert_eq!(drafts.len(), 2);
+    for draft in &drafts {
+        assert_eq!(
+            draft.courses[0].selected_groups["lecture"],
+            "11/12-lecture".to_string()
+        );
+        assert_eq!(draft.semester, semester());
+        assert!(!draft.is_published);
+    }
+}
+
+#[test]
+fn generate_skips_clashing_combinations() {
+    let drafts = generate(
+        &semester(),
+        &[
+            two_tutorials("02340114", ("08:30", "09:30"), ("08:30", "09:30")),
+            course(
+                "01040031",
+                vec![group("10", vec![event("הרצאה", 1, "08:30", "10:30")])],
+            ),
+        ],
+        &TimetablePreferences::default(),
+        DEFAULT_LIMIT,
+    );
+
+    // The Monday tutorial clashes with the Monday lecture of the second course
+    assert_eq!(drafts.len(), 1);
+    assert_eq!(tutorial(&drafts[0], 0), "12-tutorial");
+    assert_eq!(
+        drafts[0].courses[1].selected_groups["lecture"],
+        "10-lecture"
+    );
+}
+
+#[test]
+fn generate_ranks_by_days_off() {
+    let preferences = TimetablePreferences {
+        days_off: vec![1],
+        ..Default::default()
+    };
+    let drafts = generate(
+        &semester(),
+        &[two_tutorials(
+            "02340114",
+            ("08:30", "09:30"),
+            ("08:30", "09:30"),
+        )],
+        &preferences,
+        DEFAULT_LIMIT,
+    );
+
+    assert_eq!(tutorial(&drafts[0], 0), "12-tutorial");
+    assert_eq!(tutorial(&drafts[1], 0), "11-tutorial");
+}
+
+#[test]
+fn generate_ranks_by_earliest_start_and_latest_end() {
+    let courses = [two_tutorials(
+        "02340114",
+        ("08:30", "09:30"),
+        ("18:30", "19:30"),
+    )];
+
+    let early_bird = TimetablePreferences {
+        latest_end: Some("18:00".to_string()),
+        ..Default::default()
+    };
+    let drafts = generate(&semester(), &courses, &early_bird, DEFAULT_LIMIT);
+    assert_eq!(tutorial(&drafts[0], 0), "11-tutorial");
+
+    let night_owl = TimetablePreferences {
+        earliest_start: Some("10:00".to_string()),
+        ..Default::default()
+    };
+    let drafts = generate(&semester(), &courses, &night_owl, DEFAULT_LIMIT);
+    assert_eq!(tutorial(&drafts[0], 0), "12-tutorial");
+}
+
+#[test]
+fn generate_ranks_by_gaps() {
+    let courses = [course(
+        "02340114",
+        vec![
+            group(
+                "11",
+                vec![
+                    event("הרצאה", 0, "10:30", "12:30"),
+                    event("תרגול", 0, "16:30", "17:30"),
+                ],
+            ),
+            group(
+                "12",
+                vec![
+                    event("הרצאה", 0, "10:30", "12:30"),
+                    event("תרגול", 0, "12:30", "13:30"),
+                ],
+            ),
+        ],
+    )];
+    let preferences = TimetablePreferences {
+        minimize_gaps: true,
+        ..Default::default()
+    };
+
+    let drafts = generate(&semester(), &courses, &preferences, DEFAULT_LIMIT);
+
+    assert_eq!(tutorial(&drafts[0], 0), "12-tutorial");
+}
+
+#[test]
+fn generate_returns_at_most_limit_drafts() {
+    let drafts = generate(
+        &semester(),
+        &[
+            two_tutorials("02340114", ("08:30", "09:30"), ("08:30", "09:30")),
+            two_tutorials("01040031", ("12:30", "13:30"), ("12:30", "13:30")),
+        ],
+        &TimetablePreferences::default(),
+        3,
+    );
+
+    // Both lectures are on Sunday 10:30, so no combination is clash-free
+    assert!(drafts.is_empty());
+
+    let drafts = generate(
+        &semester(),
+        &[
+            two_tutorials("02340114", ("08:30", "09:30"), ("08:30", "09:30")),
+            course(
+                "01040031",
+                vec![
+                    group("10", vec![event("הרצאה", 3, "08:30", "10:30")]),
+                    group("20", vec![event("הרצאה", 4, "08:30", "10:30")]),
+                ],
+            ),
+        ],
+        &TimetablePreferences::default(),
+        3,
+    );
+    assert_eq!(drafts.len(), 3);
+}
+
+#[test]
+fn generate_gives_up_on_a_search_without_clash_free_combinations() {
+    // 14 courses with 4 groups each that clash with nothing, and a last course whose lecture takes
+    // the whole week, so that every one of the 4^14 partial combinations would be visited in vain
+    let mut courses = (0..14)
+        .map(|i| {
+            let start = format!("{:02}:00", 7 + i);
+            let end = format!("{:02}:30", 7 + i);
+            course(
+                &format!("0234{i:04}"),
+                (0..4)
+                    .map(|day| group(&day.to_string(), vec![event("הרצאה", day, &start, &end)]))
+                    .collect(),
+            )
+        })
+        .collect::<Vec<_>>();
+    courses.push(course(
+        "01040031",
+        vec![group(
+            "10",
+            (0..=5)
+                .map(|day| event("הרצאה", day, "00:00", "23:59"))
+                .collect(),
+        )],
+    ));
+
+    let drafts = generate(&semester(), &courses, &TimetablePreferences::default(), 3);
+
+    assert!(drafts.is_empty());
+}
@@ -0,0 +1,277 @@
+use std::collections::HashMap;
+use std::sync::Arc;
+
+use chrono::{DateTime, Datelike, Duration, NaiveDate, Utc};
+
+use crate::{
+    error::AppError,
+    resources::user::TimetableDraft,
+    sap::{CourseDetails, Exam, ScheduleEvent, Semester},
+};
+
+use super::{selected_events, to_minutes};
+
+const TZID: &str = "Asia/Jerusalem";
+// Content lines longer than this (in octets, excluding the line break) must be folded (RFC 5545 3.1).
+const MAX_LINE_OCTETS: usize = 75;
+
+// Israel Standard Time / Israel Daylight Time: daylight time starts on the Friday before the last
+// Sunday of March and ends on the last Sunday of October.
+const VTIMEZONE: &[&str] = &[
+    "BEGIN:VTIMEZONE",
+    "TZID:Asia/Jerusalem",
+    "BEGIN:STANDARD",
+    "DTSTART:19701025T020000",
+    "RRULE:FREQ=YEARLY;BYMONTH=10;BYDAY=-1SU",
+    "TZOFFSETFROM:+0300",
+    "TZOFFSETTO:+0200",
+    "TZNAME:IST",
+    "END:STANDARD",
+    "BEGIN:DAYLIGHT",
+    "DTSTART:19700327T020000",
+    "RRULE:FREQ=YEARLY;BYMONTH=3;BYDAY=FR;BYMONTHDAY=23,24,25,26,27,28,29",
+    "TZOFFSETFROM:+0200",
+    "TZOFFSETTO:+0300",
+    "TZNAME:IDT",
+    "END:DAYLIGHT",
+    "END:VTIMEZONE",
+];
+
+fn escape(text: &str) -> String {
+    text.replace('\\', "\\\\")
+        .replace(';', "\\;")
+        .replace(',', "\\,")
+        .replace('\n', "\\n")
+}
+
+fn fold(line: &str) -> String {
+    let mut folded = String::new();
+    let mut octets = 0;
+    for c in line.chars() {
+        if octets + c.len_utf8() > MAX_LINE_OCTETS {
+            folded.push_str("\r\n ");
+            // The leading space counts towards the continuation line
+            octets = 1;
+        }
+        folded.push(c);
+        octets += c.len_utf8();
+    }
+    folded
+}
+
+fn parse_date(date: &str) -> Option<NaiveDate> {
+    NaiveDate::parse_from_str(date, "%d-%m-%Y").ok()
+}
+
+// "HH:MM" -> "HHMM00"
+fn format_time(time: &str) -> Option<String> {
+    let minutes = to_minutes(time)?;
+    Some(format!("{:02}{:02}00", minutes / 60, minutes % 60))
+}
+
+fn local_date_time(date: NaiveDate, time: &str) -> Option<String> {
+    Some(format!("{}T{}", date.format("%Y%m%d"), format_time(time)?))
+}
+
+struct Calendar {
+    lines: Vec<String>,
+    dtstamp: String,
+}
+
+impl Calendar {
+    fn line(&mut self, line: impl AsRef<str>) {
+        self.lines.push(fold(line.as_ref()));
+    }
+
+    fn text(&mut self, name: &str, value: &str) {
+        if !value.is_empty() {
+            self.line(format!("{name}:{}", escape(value)));
+        }
+    }
+
+    fn begin_event(&mut self, uid: &str) {
+        self.line("BEGIN:VEVENT");
+        self.line(format!("UID:{uid}"));
+        let dtstamp = self.dtstamp.clone();
+        self.line(format!("DTSTAMP:{dtstamp}"));
+    }
+
+    // A weekly event from the first `day` of the semester until its last day.
+    #[allow(clippy::too_many_arguments)]
+    fn weekly_event(
+        &mut self,
+        uid: &str,
+        semester_begin: NaiveDate,
+        semester_end: NaiveDate,
+        day: u8,
+        start_time: &str,
+        end_time: &str,
+        summary: &str,
+        location: &str,
+        description: &str,
+    ) {
+        let offset =
+            (i64::from(day) + 7 - i64::from(semester_begin.weekday().num_days_from_sunday())) % 7;
+        let first = semester_begin + Duration::days(offset);
+        let (Some(start), Some(end)) = (
+            local_date_time(first, start_time),
+            local_date_time(first, end_time),
+        ) else {
+            return;
+        };
+        self.begin_event(uid);
+        self.line(format!("DTSTART;TZID={TZID}:{start}"));
+        self.line(format!("DTEND;TZID={TZID}:{end}"));
+        self.line(format!(
+            "RRULE:FREQ=WEEKLY;UNTIL={}T235959Z",
+            semester_end.format("%Y%m%d")
+        ));
+        self.text("SUMMARY", summary);
+        self.text("LOCATION", location);
+        self.text("DESCRIPTION", description);
+        self.line("END:VEVENT");
+    }
+
+    // A timed event when the exam hours are known, an all-day event otherwise. SAP gives exams
+    // without hours as "00:00"-"00:00".
+    fn exam_event(&mut self, uid: &str, course: &CourseDetails, exam: &Exam) {
+        let Some(date) = exam.date.as_deref().and_then(parse_date) else {
+            return;
+        };
+        let timed = exam
+            .begin_time
+            .as_deref()
+            .zip(exam.end_time.as_deref())
+            .filter(|&times| times != ("00:00", "00:00"))
+            .and_then(|(begin, end)| {
+                Some((local_date_time(date, begin)?, local_date_time(date, end)?))
+            });
+        self.begin_event(uid);
+        match timed {
+            Some((start, end)) => {
+                self.line(format!("DTSTART;TZID={TZID}:{start}"));
+                self.line(format!("DTEND;TZID={TZID}:{end}"));
+            }
+            None => {
+                self.line(format!("DTSTART;VALUE=DATE:{}", date.format("%Y%m%d")));
+                self.line(format!(
+                    "DTEND;VALUE=DATE:{}",
+                    (date + Duration::days(1)).format("%Y%m%d")
+                ));
+            }
+        }
+        self.text("SUMMARY", &format!("{} - {}", exam.category, course.name));
+        self.text("DESCRIPTION", exam.note.as_deref().unwrap_or_default());
+        self.line("END:VEVENT");
+    }
+}
+
+fn location(event: &ScheduleEvent) -> String {
+    [event.building.as_deref(), event.room.as_deref()]
+        .into_iter()
+        .flatten()
+        .collect::<Vec<_>>()
+        .join(" ")
+}
+
+fn description(course: &CourseDetails, event: &ScheduleEvent) -> String {
+    let mut description = vec![format!("{} ({})", course.name, course.id)];
+    if let Some(lecturer) = &event.lecturer {
+        description.push(format!("מרצה: {lecturer}"));
+    }
+    if !event.schedule_text.is_empty() {
+        description.push(event.schedule_text.clone());
+    }
+    description.join("\n")
+}
+
+/// Renders the draft as an RFC 5545 calendar: weekly events for the selected groups and the custom
+/// events, bounded by the semester dates, and an event for every exam of the selected courses.
+pub fn render(
+    draft: &TimetableDraft,
+    courses: &HashMap<String, Arc<CourseDetails>>,
+    semester: &Semester,
+    dtstamp: DateTime<Utc>,
+) -> Result<String, AppError> {
+    let (Some(begin), Some(end)) = (
+        parse_date(&semester.begin_date),
+        parse_date(&semester.end_date),
+    ) else {
+        return Err(AppError::InternalServer(format!(
+            "Invalid semester dates: {} - {}",
+            semester.begin_date, semester.end_date
+        )));
+    };
+
+    let mut calendar = Calendar {
+        lines: Vec::new(),
+        dtstamp: dtstamp.format("%Y%m%dT%H%M%SZ").to_string(),
+    };
+    calendar.line("BEGIN:VCALENDAR");
+    calendar.line("VERSION:2.0");
+    calendar.line("PRODID:-//Sogrim//Timetable//HE");
+    calendar.line("CALSCALE:GREGORIAN");
+    calendar.line("METHOD:PUBLISH");
+    calendar.text("X-WR-CALNAME", &draft.name);
+    calendar.line(format!("X-WR-TIMEZONE:{TZID}"));
+    for line in VTIMEZONE {
+        calendar.line(line);
+    }
+
+    for selection in &draft.courses {
+        let Some(course) = courses.get(&selection.course_id) else {
+            continue;
+        };
+        let mut selected_groups = selection.selected_groups.iter().collect::<Vec<_>>();
+        selected_groups.sort();
+        for (lesson, group_id) in selected_groups {
+            let events = selected_events(course, lesson, group_id).unwrap_or_default();
+            for (index, event) in events.into_iter().enumerate() {
+                let (Some(day), Some(start_time), Some(end_time)) =
+                    (event.day, &event.start_time, &event.end_time)
+                else {
+                    continue;
+                };
+                calendar.weekly_event(
+                    &format!("{}-{}-{group_id}-{index}@sogrim", draft.id, course.id),
+                    begin,
+                    end,
+                    day,
+                    start_time,
+                    end_time,
+                    &format!("{} - {}", course.name, event.kind),
+                    &location(event),
+                    &description(course, event),
+                );
+            }
+        }
+        for (index, exam) in course.exams.iter().enumerate() {
+            calendar.exam_event(
+                &format!("{}-{}-exam-{index}@sogrim", draft.id, course.id),
+                course,
+                exam,
+            );
+        }
+    }
+
+    for event in &draft.custom_events {
+        calendar.weekly_event(
+            &format!("{}-{}@sogrim", draft.id, event.id),
+            begin,
+            end,
+            event.day,
+            &event.start_time,
+            &event.end_time,
+            &event.title,
+            "",
+            "",
+        );
+    }
+
+    calendar.line("END:VCALENDAR");
+    Ok(calendar.lines.join("\r\n") + "\r\n")
+}
+
+#[cfg(test)]
+#[path = "ics_tests.rs"]
+mod ics_tests;
@@ -0,0 +1,166 @@
+use std::collections::HashMap;
+use std::sync::Arc;
+
+use chrono::TimeZone;
+
+use crate::{
+    resources::{
+        course::{AcademicSemester, CourseId, SemesterSeason},
+        user::{CourseSelection, CustomEvent},
+    },
+    sap::{CourseDetails, Exam, ScheduleEvent, ScheduleGroup},
+};
+
+use super::*;
+
+fn semester() -> Semester {
+    Semester {
+        year: "2025".to_string(),
+        semester: "200".to_string(),
+        // A Wednesday
+        begin_date: "22-10-2025".to_string(),
+        end_date: "25-01-2026".to_string(),
+    }
+}
+
+fn exam(category: &str, date: &str, times: Option<(&str, &str)>) -> Exam {
+    Exam {
+        category: category.to_string(),
+        category_code: String::new(),
+        date: Some(date.to_string()),
+        begin_time: times.map(|(begin, _)| begin.to_string()),
+        end_time: times.map(|(_, end)| end.to_string()),
+        note: None,
+    }
+}
+
+fn course() -> Arc<CourseDetails> {
+    Arc::new(CourseDetails {
+        id: CourseId::new("02340114"),
+        name: "מבוא למדעי המחשב, מ'".to_string(),
+        credits: 4.0,
+        faculty: None,
+        syllabus: None,
+        academic_level: None,
+        is_english: false,
+        is_malag: false,
+        is_sport: false,
+        semester_note: None,
+        exams: vec![
+            exam("מועד א", "10-02-2026", Some(("09:00", "12:00"))),
+            exam("מועד ב", "10-03-2026", None),
+            exam("בוחן", "20-11-2025", Some(("00:00", "00:00"))),
+        ],
+        relations: vec![],
+        prerequisites: vec![],
+        corequisites: vec![],
+        responsible: vec![],
+        offered_periods: vec![],
+        schedule: vec![ScheduleGroup {
+            group: "11".to_string(),
+            name: None,
+            events: vec![ScheduleEvent {
+                kind: "הרצאה".to_string(),
+                day: Some(0),
+                start_time: Some("10:30".to_string()),
+                end_time: Some("12:30".to_string()),
+                schedule_text: String::new(),
+                building: Some("טאוב".to_string()),
+                room: Some("1".to_string()),
+                lecturer: None,
+            }],
+        }],
+    })
+}
+
+fn draft() -> TimetableDraft {
+    TimetableDraft {
+        id: "draft".to_string(),
+        name: "מערכת חורף".to_string(),
+        semester: AcademicSemester::new(SemesterSeason::Winter, 2025),
+        courses: vec![CourseSelection {
+            course_id: "02340114".to_string(),
+            selected_groups: HashMap::from([("lecture".to_string(), "11-lecture".to_string())]),
+        }],
+        custom_events: vec![CustomEvent {
+            id: "gym".to_string(),
+            title: "חדר כושר".to_string(),
+            day: 3,
+            start_time: "18:00".to_string(),
+            end_time: "19:00".to_string(),
+            color: None,
+        }],
+        created_at: String::new(),
+        updated_at: String::new(),
+        is_published: false,
+    }
+}
+
+fn render_draft() -> String {
+    let courses = HashMap::from([("02340114".to_string(), course())]);
+    let dtstamp = Utc.with_ymd_and_hms(2025, 10, 1, 12, 0, 0).unwrap();
+    render(&draft(), &courses, &semester(), dtstamp).unwrap()
+}
+
+fn unfold(calendar: &str) -> String {
+    calendar.replace("\r\n ", "")
+}
+
+#[test]
+fn render_weekly_events_start_on_the_first_matching_day() {
+    let calendar = unfold(&render_draft());
+
+    // The semester starts on Wednesday, so the first Sunday lecture is on the 26th
+    assert!(calendar.contains("DTSTART;TZID=Asia/Jerusalem:20251026T103000\r\n"));
+    assert!(calendar.contains("DTEND;TZID=Asia/Jerusalem:20251026T123000\r\n"));
+    assert!(calendar.contains("LOCATION:טאוב 1\r\n"));
+    // The custom event is on the first day
+    assert!(calendar.contains("DTSTART;TZID=Asia/Jerusalem:20251022T180000\r\n"));
+    assert!(calendar.contains("SUMMARY:חדר כושר\r\n"));
+    assert_eq!(
+        calendar
+            .matches("RRULE:FREQ=WEEKLY;UNTIL=20260125T235959Z\r\n")
+            .count(),
+        2
+    );
+}
+
+#[test]
+fn render_exams() {
+    let calendar = unfold(&render_draft());
+
+    assert!(calendar.contains("DTSTART;TZID=Asia/Jerusalem:20260210T090000\r\n"));
+    assert!(calendar.contains("DTEND;TZID=Asia/Jerusalem:20260210T120000\r\n"));
+    // No hours, so an all-day event
+    assert!(calendar.contains("DTSTART;VALUE=DATE:20260310\r\n"));
+    assert!(calendar.contains("DTEND;VALUE=DATE:20260311\r\n"));
+    // SAP's midnight to midnight means no hours either
+    assert!(calendar.contains("DTSTART;VALUE=DATE:20251120\r\n"));
+    assert!(calendar.contains("DTEND;VALUE=DATE:20251121\r\n"));
+    assert!(!calendar.contains("T000000\r\n"));
+}
+
+#[test]
+fn render_escapes_and_folds_lines() {
+    let calendar = render_draft();
+
+    assert!(calendar.starts_with("BEGIN:VCALENDAR\r\n"));
+    assert!(calendar.ends_with("END:VCALENDAR\r\n"));
+    assert!(calendar
+        .split("\r\n")
+        .all(|line| line.len() <= MAX_LINE_OCTETS));
+    assert!(unfold(&calendar).contains("SUMMARY:מבוא למדעי המחשב\\, מ' - הרצאה\r\n"));
+    assert_eq!(
+        calendar.matches("BEGIN:VEVENT").count(),
+        calendar.matches("END:VEVENT").count()
+    );
+}
+
+#[test]
+fn render_rejects_invalid_semester_dates() {
+    let semester = Semester {
+        begin_date: "/Date(oops)/".to_string(),
+        ..semester()
+    };
+    assert!(render(&draft(), &HashMap::new(), &semester, Utc::now()).is_err());
+}
@@ -0,0 +1,215 @@
+pub mod generator;
+pub mod ics;
+pub mod notifications;
+
+use std::collections::HashMap;
+use std::sync::Arc;
+
+use chrono::NaiveDate;
+use serde::Serialize;
+
+use crate::{
+    resources::user::TimetableDraft,
+    sap::{CourseDetails, ScheduleEvent},
+};
+
+// Exams of the same category (e.g. moed A) that are closer than this are reported.
+pub const MIN_DAYS_BETWEEN_EXAMS: i64 = 3;
+
+/// A single weekly occurrence on the timetable, either a course event or a custom event.
+#[derive(Clone, Debug, PartialEq, Eq, Serialize)]
+pub struct TimetableSlot {
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub course_id: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub group: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub custom_event_id: Option<String>,
+    pub title: String,
+    pub day: u8,
+    pub start_time: String,
+    pub end_time: String,
+}
+
+#[derive(Clone, Debug, Serialize)]
+pub struct TimeClash {
+    pub first: TimetableSlot,
+    pub second: TimetableSlot,
+}
+
+#[derive(Clone, Debug, Serialize)]
+pub struct ExamConflict {
+    pub category: String,
+    pub first_course_id: String,
+    pub first_date: String,
+    pub second_course_id: String,
+    pub second_date: String,
+    // 0 means both exams are on the same day
+    pub days_apart: i64,
+}
+
+#[derive(Clone, Debug, Default, Serialize)]
+pub struct TimetableReport {
+    pub draft_id: String,
+    pub clashes: Vec<TimeClash>,
+    pub exam_conflicts: Vec<ExamConflict>,
+    // Selections that could not be resolved against the course data, e.g. "02340114" or "02340114:11-lecture"
+    pub unresolved: Vec<String>,
+}
+
+// Mirrors the lesson types the timetable client groups events by.
+fn lesson_type(kind: &str) -> &'static str {
+    match kind {
+        "תרגול" => "tutorial",
+        "מעבדה" => "lab",
+        "סמינר" | "פרויקט" => "seminar",
+        _ => "lecture",
+    }
+}
+
+fn to_minutes(time: &str) -> Option<u32> {
+    let (hours, minutes) = time.split_once(':')?;
+    Some(hours.trim().parse::<u32>().ok()? * 60 + minutes.trim().parse::<u32>().ok()?)
+}
+
+fn overlap(first: &TimetableSlot, second: &TimetableSlot) -> bool {
+    if first.day != second.day {
+        return false;
+    }
+    let (Some(s1), Some(e1), Some(s2), Some(e2)) = (
+        to_minutes(&first.start_time),
+        to_minutes(&first.end_time),
+        to_minutes(&second.start_time),
+        to_minutes(&second.end_time),
+    ) else {
+        return false;
+    };
+    s1 < e2 && s2 < e1
+}
+
+fn slot(course: &CourseDetails, group: &str, event: &ScheduleEvent) -> Option<TimetableSlot> {
+    Some(TimetableSlot {
+        course_id: Some(course.id.to_string()),
+        group: Some(group.to_string()),
+        custom_event_id: None,
+        title: format!("{} - {}", course.name, event.kind),
+        day: event.day?,
+        start_time: event.start_time.clone()?,
+        end_time: event.end_time.clone()?,
+    })
+}
+
+// A selected group id looks like "11/12-lecture": the SAP group numbers sharing the same events,
+// followed by the lesson type. Only the events of that lesson type belong to the selection.
+fn selected_events<'a>(
+    course: &'a CourseDetails,
+    lesson: &str,
+    group_id: &str,
+) -> Option<Vec<&'a ScheduleEvent>> {
+    let (numbers, lesson) = group_id.rsplit_once('-').unwrap_or((group_id, lesson));
+    let number = numbers.split('/').next()?;
+    let group = course.schedule.iter().find(|g| g.group == number)?;
+    Some(
+        group
+            .events
+            .iter()
+            .filter(|event| lesson_type(&event.kind) == lesson)
+            .collect(),
+    )
+}
+
+fn exam_conflicts(courses: &[&CourseDetails]) -> Vec<ExamConflict> {
+    let exams = courses
+        .iter()
+        .flat_map(|course| {
+            course.exams.iter().filter_map(move |exam| {
+                let date = NaiveDate::parse_from_str(exam.date.as_deref()?, "%d-%m-%Y").ok()?;
+                Some((course.id.to_string(), exam, date))
+            })
+        })
+        .collect::<Vec<_>>();
+
+    let mut conflicts = Vec::new();
+    for (i, (first_id, first, first_date)) in exams.iter().enumerate() {
+        for (second_id, second, second_date) in &exams[i + 1..] {
+            if first_id == second_id || first.category_code != second.category_code {
+                continue;
+            }
+            let days_apart = (*second_date - *first_date).num_days().abs();
+            if days_apart < MIN_DAYS_BETWEEN_EXAMS {
+                conflicts.push(ExamConflict {
+                    category: first.category.clone(),
+                    first_course_id: first_id.clone(),
+                    first_date: first.date.clone().unwrap_or_default(),
+                    second_course_id: second_id.clone(),
+                    second_date: second.date.clone().unwrap_or_default(),
+                    days_apart,
+                });
+            }
+        }
+    }
+    conflicts
+}
+
+/// Checks a draft against the course data of its semester: overlapping weekly slots (custom events
+/// included) and exams of the same category that fall on the same day or too close together.
+/// `courses` maps the draft's course ids to their details; missing courses are reported as unresolved.
+pub fn check_draft(
+    draft: &TimetableDraft,
+    courses: &HashMap<String, Arc<CourseDetails>>,
+) -> TimetableReport {
+    let mut report = TimetableReport {
+        draft_id: draft.id.clone(),
+        ..Default::default()
+    };
+
+    let mut slots = Vec::new();
+    let mut selected_courses = Vec::new();
+    for selection in &draft.courses {
+        let Some(course) = courses.get(&selection.course_id) else {
+            report.unresolved.push(selection.course_id.clone());
+            continue;
+        };
+        selected_courses.push(course.as_ref());
+        let mut selected_groups = selection.selected_groups.iter().collect::<Vec<_>>();
+        selected_groups.sort();
+        for (lesson, group_id) in selected_groups {
+            match selected_events(course, lesson, group_id) {
+                Some(events) => slots.extend(
+                    events
+                        .into_iter()
+                        .filter_map(|event| slot(course, group_id, event)),
+                ),
+                None => report
+                    .unresolved
+                    .push(format!("{}:{group_id}", selection.course_id)),
+            }
+        }
+    }
+    slots.extend(draft.custom_events.iter().map(|event| TimetableSlot {
+        course_id: None,
+        group: None,
+        custom_event_id: Some(event.id.clone()),
+        title: event.title.clone(),
+        day: event.day,
+        start_time: event.start_time.clone(),
+        end_time: event.end_time.clone(),
+    }));
+
+    for (i, first) in slots.iter().enumerate() {
+        for second in &slots[i + 1..] {
+            if overlap(first, second) {
+                report.clashes.push(TimeClash {
+                    first: first.clone(),
+                    second: second.clone(),
+                });
+            }
+        }
+    }
+    report.exam_conflicts = exam_conflicts(&selected_courses);
+    report
+}
+
+#[cfg(test)]
+#[path = "timetable_tests.rs"]
+mod timetable_tests;
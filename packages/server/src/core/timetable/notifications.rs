@@ -0,0 +1,140 @@
+//! Notifications about course changes which affect students' published timetables.
+//!
+//! The incremental fetcher records what changed in every run, see `sap::changes`. A change reaches
+//! every student whose published draft of that semester has the course, trimmed to what matters on
+//! a timetable: exams, meeting times, rooms and groups, the latter three only for the groups the
+//! student picked.
+
+use std::collections::HashSet;
+
+use crate::{
+    resources::{
+        course::CourseId,
+        user::{CourseSelection, Notification, TimetableDraft, User},
+    },
+    sap::changes::{Changelog, CourseChange, FieldChange},
+};
+
+// The inbox keeps this many notifications, dropping the oldest.
+pub const MAX_NOTIFICATIONS: usize = 100;
+
+// The group numbers of a selection, e.g. "11" for "11-lecture" and "11", "12" for "11/12".
+fn selected_groups(selection: &CourseSelection) -> HashSet<&str> {
+    selection
+        .selected_groups
+        .values()
+        .flat_map(|group_id| {
+            let numbers = group_id
+                .rsplit_once('-')
+                .map_or(group_id.as_str(), |(numbers, _)| numbers);
+            numbers.split('/')
+        })
+        .collect()
+}
+
+/// The part of a change which affects a course selection, if any. A selection without groups is
+/// affected by changes to any group.
+pub fn affecting(change: &CourseChange, selection: &CourseSelection) -> Option<CourseChange> {
+    let groups = selected_groups(selection);
+    let selected = |group: &String| groups.is_empty() || groups.contains(group.as_str());
+    match change {
+        CourseChange::Added { .. } => None,
+        CourseChange::Removed { .. } => Some(change.clone()),
+        CourseChange::Changed {
+            course_id,
+            name,
+            changes,
+        } => {
+            let changes = changes
+                .iter()
+                .filter(|change| match change {
+                    FieldChange::Exams { .. } => true,
+                    FieldChange::Times { group, .. } | FieldChange::Rooms { group, .. } => {
+                        selected(group)
+                    }
+                    FieldChange::Groups { removed, .. } => removed.iter().any(selected),
+                    FieldChange::Name { .. }
+                    | FieldChange::Credits { .. }
+                    | FieldChange::Lecturers { .. }
+                    | FieldChange::Other => false,
+                })
+                .cloned()
+                .collect::<Vec<_>>();
+            (!changes.is_empty()).then(|| CourseChange::Changed {
+                course_id: course_id.clone(),
+                name: name.clone(),
+                changes,
+            })
+        }
+    }
+}
+
+/// The notifications a changelog holds for the owner of the given drafts. Only published drafts
+/// count, and a course on several of them is notified once.
+pub fn notifications_for(drafts: &[TimetableDraft], changelog: &Changelog) -> Vec<Notification> {
+    let mut notifications = Vec::new();
+    let mut seen = HashSet::new();
+    for semester in &changelog.semesters {
+        let drafts = drafts.iter().filter(|draft| {
+            draft.is_published
+                && draft.semester.start_year.to_string() == semester.year
+                && draft.semester.season.sap_code() == semester.semester
+        });
+        for draft in drafts {
+            for selection in &draft.courses {
+                let course_id = CourseId::new(&selection.course_id);
+                for change in semester
+                    .changes
+                    .iter()
+                    .filter(|change| *change.course_id() == course_id)
+                {
+                    let Some(change) = affecting(change, selection) else {
+                        continue;
+                    };
+                    let id = format!(
+                        "{}-{}-{}@{}",
+                        semester.year, semester.semester, &*course_id, changelog.fetched_at
+                    );
+                    if seen.insert(id.clone()) {
+                        notifications.push(Notification {
+                            id,
+                            created_at: changelog.fetched_at.clone(),
+                            semester: draft.semester.clone(),
+                            draft_id: draft.id.clone(),
+                            change,
+                            read: false,
+                        });
+                    }
+                }
+            }
+        }
+    }
+    notifications
+}
+
+/// Adds the notifications the user doesn't have yet to their inbox, and returns them so that
+/// they can be stored with `User::push_notifications`.
+pub fn deliver(user: &mut User, notifications: Vec<Notification>) -> Vec<Notification> {
+    let mut known = user
+        .notifications
+        .iter()
+        .map(|notification| notification.id.clone())
+        .collect::<HashSet<_>>();
+    let added = notifications
+        .into_iter()
+        .filter(|notification| known.insert(notification.id.clone()))
+        .collect::<Vec<_>>();
+    if added.is_empty() {
+        return added;
+    }
+    user.notifications.extend(added.iter().cloned());
+    // Newest first. The timestamps are all RFC 3339 in UTC, so they sort as strings.
+    user.notifications
+        .sort_by(|a, b| b.created_at.cmp(&a.created_at));
+    user.notifications.truncate(MAX_NOTIFICATIONS);
+    added
+}
+
+#[cfg(test)]
+#[path = "notifications_tests.rs"]
+mod notifications_tests;
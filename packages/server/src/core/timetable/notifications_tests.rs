@@ -0,0 +1,210 @@
+use std::collections::HashMap;
+
+use crate::{
+    resources::course::{AcademicSemester, SemesterSeason},
+    sap::changes::SemesterChanges,
+};
+
+use super::*;
+
+fn draft(id: &str, is_published: bool, courses: Vec<CourseSelection>) -> TimetableDraft {
+    TimetableDraft {
+        id: id.to_string(),
+        name: id.to_string(),
+        semester: AcademicSemester::new(SemesterSeason::Winter, 2025),
+        courses,
+        is_published,
+        ..Default::default()
+    }
+}
+
+fn selection(course_id: &str, groups: &[(&str, &str)]) -> CourseSelection {
+    CourseSelection {
+        course_id: course_id.to_string(),
+        selected_groups: groups
+            .iter()
+            .map(|(lesson, group)| (lesson.to_string(), group.to_string()))
+            .collect::<HashMap<_, _>>(),
+    }
+}
+
+fn rooms(group: &str) -> FieldChange {
+    FieldChange::Rooms {
+        group: group.to_string(),
+        from: vec!["טאוב 2".to_string()],
+        to: vec!["טאוב 4".to_string()],
+    }
+}
+
+fn changed(course_id: &str, changes: Vec<FieldChange>) -> CourseChange {
+    CourseChange::Changed {
+        course_id: CourseId::new(course_id),
+        name: course_id.to_string(),
+        changes,
+    }
+}
+
+fn changelog(
+    fetched_at: &str,
+    year: &str,
+    semester: &str,
+    changes: Vec<CourseChange>,
+) -> Changelog {
+    Changelog {
+        fetched_at: fetched_at.to_string(),
+        semesters: vec![SemesterChanges {
+            year: year.to_string(),
+            semester: semester.to_string(),
+            changes,
+        }],
+    }
+}
+
+// Changes to groups the student may not have picked yet
+fn unpicked_change() -> CourseChange {
+    changed(
+        "02340114",
+        vec![
+            rooms("11"),
+            FieldChange::Groups {
+                added: vec![],
+                removed: vec!["12".to_string()],
+            },
+            FieldChange::Other,
+        ],
+    )
+}
+
+#[test]
+fn keeps_only_changes_to_the_selected_groups() {
+    let change = changed(
+        "02340114",
+        vec![
+            FieldChange::Name {
+                from: "a".to_string(),
+                to: "b".to_string(),
+            },
+            rooms("11"),
+            rooms("12"),
+            FieldChange::Groups {
+                added: vec!["13".to_string()],
+                removed: vec![],
+            },
+        ],
+    );
+
+    let picked = selection("02340114", &[("lecture", "10-lecture"), ("tutorial", "11")]);
+    assert_eq!(
+        affecting(&change, &picked),
+        Some(changed("02340114", vec![rooms("11")]))
+    );
+
+    // Without groups, every group counts
+    let unpicked = selection("02340114", &[]);
+    let Some(CourseChange::Changed { changes, .. }) = affecting(&unpicked_change(), &unpicked)
+    else {
+        panic!("expected a change");
+    };
+    assert_eq!(changes.len(), 2);
+
+    let elsewhere = selection("02340114", &[("lecture", "20-lecture")]);
+    assert_eq!(affecting(&change, &elsewhere), None);
+    assert_eq!(
+        affecting(
+            &CourseChange::Added {
+                course_id: CourseId::new("02340114"),
+                name: String::new(),
+            },
+            &picked
+        ),
+        None
+    );
+}
+
+#[test]
+fn notifies_published_drafts_of_the_semester() {
+    let drafts = vec![
+        draft("unpublished", false, vec![selection("02340114", &[])]),
+        draft("a", true, vec![selection("234114", &[])]),
+        draft("b", true, vec![selection("02340114", &[])]),
+    ];
+    let log = changelog(
+        "2025-11-02T10:00:00+00:00",
+        "2025",
+        "200",
+        vec![
+            changed("02340114", vec![rooms("11")]),
+            changed("01040031", vec![rooms("11")]),
+        ],
+    );
+
+    let notifications = notifications_for(&drafts, &log);
+    assert_eq!(notifications.len(), 1);
+    assert_eq!(
+        notifications[0].id,
+        "2025-200-02340114@2025-11-02T10:00:00+00:00"
+    );
+    assert_eq!(notifications[0].draft_id, "a");
+    assert!(!notifications[0].read);
+
+    let other_semester = changelog(
+        "2025-11-02T10:00:00+00:00",
+        "2025",
+        "201",
+        vec![changed("02340114", vec![rooms("11")])],
+    );
+    assert!(notifications_for(&drafts, &other_semester).is_empty());
+}
+
+#[test]
+fn delivers_each_notification_once_newest_first() {
+    let drafts = vec![draft("a", true, vec![selection("02340114", &[])])];
+    let older = changelog(
+        "2025-11-01T10:00:00+00:00",
+        "2025",
+        "200",
+        vec![changed("02340114", vec![rooms("11")])],
+    );
+    let newer = changelog(
+        "2025-11-02T10:00:00+00:00",
+        "2025",
+        "200",
+        vec![CourseChange::Removed {
+            course_id: CourseId::new("02340114"),
+            name: String::new(),
+        }],
+    );
+
+    let mut user = User::default();
+    assert_eq!(
+        deliver(&mut user, notifications_for(&drafts, &older)).len(),
+        1
+    );
+    assert_eq!(
+        deliver(&mut user, notifications_for(&drafts, &newer)).len(),
+        1
+    );
+    assert!(deliver(&mut user, notifications_for(&drafts, &older)).is_empty());
+    assert_eq!(user.notifications.len(), 2);
+    assert_eq!(
+        user.notifications[0].created_at,
+        "2025-11-02T10:00:00+00:00"
+    );
+
+    let many = (0..MAX_NOTIFICATIONS + 5)
+        .map(|i| Notification {
+            id: i.to_string(),
+            created_at: format!("2026-01-01T00:00:{:02}+00:00", i % 60),
+            semester: AcademicSemester::new(SemesterSeason::Winter, 2025),
+            draft_id: "a".to_string(),
+            change: changed("02340114", vec![rooms("11")]),
+            read: false,
+        })
+        .collect();
+    deliver(&mut user, many);
+    assert_eq!(user.notifications.len(), MAX_NOTIFICATIONS);
+    assert!(user
+        .notifications
+        .iter()
+        .all(|notification| notification.created_at.starts_with("2026")));
+}
@@ -0,0 +1,243 @@
+use std::collections::HashMap;
+use std::sync::Arc;
+
+use crate::{
+    resources::{
+        course::CourseId,
+        user::{CourseSelection, CustomEvent, TimetableDraft},
+    },
+    sap::{CourseDetails, Exam, ScheduleEvent, ScheduleGroup},
+};
+
+use super::*;
+
+fn event(kind: &str, day: u8, start_time: &str, end_time: &str) -> ScheduleEvent {
+    ScheduleEvent {
+        kind: kind.to_string(),
+        day: Some(day),
+        start_time: Some(start_time.to_string()),
+        end_time: Some(end_time.to_string()),
+        schedule_text: String::new(),
+        building: None,
+        room: None,
+        lecturer: None,
+    }
+}
+
+fn exam(category_code: &str, date: &str) -> Exam {
+    Exam {
+        category: category_code.to_string(),
+        category_code: category_code.to_string(),
+        date: Some(date.to_string()),
+        begin_time: None,
+        end_time: None,
+        note: None,
+    }
+}
+
+fn course(id: &str, schedule: Vec<ScheduleGroup>, exams: Vec<Exam>) -> Arc<CourseDetails> {
+    Arc::new(CourseDetails {
+        id: CourseId::new(id),
+        name: id.to_string(),
+        credits: 3.0,
+        faculty: None,
+        syllabus: None,
+        academic_level: None,
+        is_english: false,
+        is_malag: false,
+        is_sport: false,
+        semester_note: None,
+        exams,
+        relations: vec![],
+        prerequisites: vec![],
+        corequisites: vec![],
+        responsible: vec![],
+        offered_periods: vec![],
+        schedule,
+    })
+}
+
+fn group(number: &str, events: Vec<ScheduleEvent>) -> ScheduleGroup {
+    ScheduleGroup {
+        group: number.to_string(),
+        name: None,
+        events,
+    }
+}
+
+fn selection(course_id: &str, groups: &[(&str, &str)]) -> CourseSelection {
+    CourseSelection {
+        course_id: course_id.to_string(),
+        selected_groups: groups
+            .iter()
+            .map(|(lesson, group_id)| (lesson.to_string(), group_id.to_string()))
+            .collect(),
+    }
+}
+
+fn draft(courses: Vec<CourseSelection>, custom_events: Vec<CustomEvent>) -> TimetableDraft {
+    TimetableDraft {
+        id: "draft".to_string(),
+        courses,
+        custom_events,
+        ..Default::default()
+    }
+}
+
+fn courses() -> HashMap<String, Arc<CourseDetails>> {
+    HashMap::from([
+        (
+            "02340114".to_string(),
+            course(
+                "02340114",
+                vec![
+                    group(
+                        "11",
+                        vec![
+                            event("הרצאה", 0, "10:30", "12:30"),
+                            event("תרגול", 1, "08:30", "09:30"),
+                        ],
+                    ),
+                    group(
+                        "12",
+                        vec![
+                            event("הרצאה", 0, "10:30", "12:30"),
+                            event("תרגול", 2, "08:30", "09:30"),
+                        ],
+                    ),
+                ],
+                vec![exam("FI", "01-02-2026"), exam("FB", "01-03-2026")],
+            ),
+        ),
+        (
+            "01040031".to_string(),
+            course(
+                "01040031",
+                vec![group(
+                    "10",
+                    vec![
+                        event("הרצאה", 0, "12:00", "14:00"),
+                        event("תרגול", 2, "09:00", "10:00"),
+                    ],
+                )],
+                vec![exam("FI", "03-02-2026"), exam("FB", "10-03-2026")],
+            ),
+        ),
+    ])
+}
+
+#[test]
+fn check_draft_reports_overlapping_groups() {
+    let draft = draft(
+        vec![
+            selection(
+                "02340114",
+                &[("lecture", "11/12-lecture"), ("tutorial", "12-tutorial")],
+            ),
+            selection(
+                "01040031",
+                &[("lecture", "10-lecture"), ("tutorial", "10-tutorial")],
+            ),
+        ],
+        vec![],
+    );
+
+    let report = check_draft(&draft, &courses());
+
+    // Sunday lectures (10:30-12:30 and 12:00-14:00) and Tuesday tutorials (08:30-09:30 and 09:00-10:00)
+    assert_eq!(report.clashes.len(), 2);
+    assert!(report.clashes.iter().all(|clash| {
+        clash.first.day == clash.second.day && clash.first.course_id != clash.second.course_id
+    }));
+    assert!(report.unresolved.is_empty());
+}
+
+#[test]
+fn check_draft_only_uses_the_selected_lesson_type() {
+    // Group 11 tutorial is on Monday, so only the lectures clash
+    let draft = draft(
+        vec![
+            selection("02340114", &[("tutorial", "11-tutorial")]),
+            selection("01040031", &[("tutorial", "10-tutorial")]),
+        ],
+        vec![],
+    );
+
+    let report = check_draft(&draft, &courses());
+
+    assert!(report.clashes.is_empty());
+}
+
+#[test]
+fn check_draft_reports_custom_event_clashes() {
+    let draft = draft(
+        vec![selection("02340114", &[("tutorial", "11-tutorial")])],
+        vec![CustomEvent {
+            id: "work".to_string(),
+            title: "עבודה".to_string(),
+            day: 1,
+            start_time: "09:00".to_string(),
+            end_time: "17:00".to_string(),
+            color: None,
+        }],
+    );
+
+    let report = check_draft(&draft, &courses());
+
+    assert_eq!(report.clashes.len(), 1);
+    assert_eq!(
+        report.clashes[0].second.custom_event_id,
+        Some("work".to_string())
+    );
+}
+
+#[test]
+fn check_draft_adjacent_slots_do_not_clash() {
+    let draft = draft(
+        vec![selection("02340114", &[("tutorial", "11-tutorial")])],
+        vec![CustomEvent {
+            id: "gym".to_string(),
+            title: "חדר כושר".to_string(),
+            day: 1,
+            start_time: "09:30".to_string(),
+            end_time: "10:30".to_string(),
+            color: None,
+        }],
+    );
+
+    assert!(check_draft(&draft, &courses()).clashes.is_empty());
+}
+
+#[test]
+fn check_draft_reports_close_exams_of_the_same_category() {
+    let draft = draft(
+        vec![selection("02340114", &[]), selection("01040031", &[])],
+        vec![],
+    );
+
+    let report = check_draft(&draft, &courses());
+
+    // Moed A exams are two days apart, moed B exams are nine days apart
+    assert_eq!(report.exam_conflicts.len(), 1);
+    let conflict = &report.exam_conflicts[0];
+    assert_eq!(conflict.category, "FI");
+    assert_eq!(conflict.days_apart, 2);
+}
+
+#[test]
+fn check_draft_reports_unresolved_selections() {
+    let draft = draft(
+        vec![
+            selection("02340114", &[("lecture", "99-lecture")]),
+            selection("00940345", &[]),
+        ],
+        vec![],
+    );
+
+    let report = check_draft(&draft, &courses());
+
+    assert_eq!(
+        report.unresolved,
+        vec!["02340114:99-lecture".to_string(), "00940345".to_string()]
+    );
+}
@@ -0,0 +1,210 @@
+use std::panic::{self, AssertUnwindSafe};
+
+use pdf_extract::{Document, MediaBox, OutputDev, OutputError, Transform};
+
+use crate::{
+    core::parser_v2::{self, Format, ParseReport},
+    error::AppError,
+    resources::course::CourseStatus,
+};
+
+// Horizontal gap, relative to the font size, above which two glyphs belong to different words.
+const WORD_GAP: f64 = 0.15;
+// Vertical distance, relative to the font size, below which two glyphs sit on the same row.
+const ROW_TOLERANCE: f64 = 0.5;
+
+// A glyph as drawn on the page. The origin is the top-left corner of the page.
+#[derive(Clone, Debug)]
+struct Glyph {
+    text: String,
+    x: f64,
+    y: f64,
+    width: f64,
+    size: f64,
+}
+
+fn is_hebrew(word: &str) -> bool {
+    word.chars().any(|c| ('\u{0590}'..='\u{05FF}').contains(&c))
+}
+
+fn is_latin(word: &str) -> bool {
+    word.chars().any(|c| c.is_ascii_alphabetic())
+}
+
+// Brackets are drawn mirrored in right-to-left text.
+fn mirror(c: char) -> char {
+    match c {
+        '(' => ')',
+        ')' => '(',
+        '[' => ']',
+        ']' => '[',
+        '{' => '}',
+        '}' => '{',
+        '<' => '>',
+        '>' => '<',
+        c => c,
+    }
+}
+
+// Turns a row of glyphs from visual (left-to-right) order back into reading order. The grade sheet
+// is a right-to-left document: Hebrew words and the columns between them read right to left, while
+// numbers and runs of Latin words keep reading left to right.
+fn row_text(mut row: Vec<Glyph>) -> String {
+    row.sort_by(|a, b| a.x.total_cmp(&b.x));
+
+    let mut words: Vec<String> = Vec::new();
+    let mut last_end: Option<f64> = None;
+    for glyph in row {
+        match (last_end, words.last_mut()) {
+            (Some(end), Some(word)) if glyph.x <= end + glyph.size * WORD_GAP => {
+                word.push_str(&glyph.text)
+            }
+            _ => words.push(glyph.text.clone()),
+        }
+        last_end = Some(glyph.x + glyph.width);
+    }
+
+    let mut words = words
+        .into_iter()
+        .map(|word| {
+            if is_hebrew(&word) {
+                word.chars().rev().map(mirror).collect()
+            } else {
+                word
+            }
+        })
+        .collect::<Vec<_>>();
+    words.reverse();
+
+    let mut start = 0;
+    while start < words.len() {
+        let run = words[start..]
+            .iter()
+            .take_while(|word| is_latin(word) && !is_hebrew(word))
+            .count();
+        words[start..start + run].reverse();
+        start += run.max(1);
+    }
+    words.join(" ")
+}
+
+// Groups the glyphs of a page into rows, top to bottom.
+fn page_lines(mut glyphs: Vec<Glyph>) -> Vec<String> {
+    glyphs.retain(|glyph| !glyph.text.trim().is_empty());
+    glyphs.sort_by(|a, b| a.y.total_cmp(&b.y));
+
+    let mut rows: Vec<Vec<Glyph>> = Vec::new();
+    for glyph in glyphs {
+        match rows.last_mut() {
+            Some(row) if (glyph.y - row[0].y).abs() <= glyph.size * ROW_TOLERANCE => {
+                row.push(glyph)
+            }
+            _ => rows.push(vec![glyph]),
+        }
+    }
+    rows.into_iter().map(row_text).collect()
+}
+
+struct GlyphCollector {
+    flip_ctm: Transform,
+    glyphs: Vec<Glyph>,
+    lines: Vec<String>,
+}
+
+impl OutputDev for GlyphCollector {
+    fn begin_page(
+        &mut self,
+        _page_num: u32,
+        media_box: &MediaBox,
+        _art_box: Option<(f64, f64, f64, f64)>,
+    ) -> Result<(), OutputError> {
+        self.flip_ctm = Transform::row_major(1., 0., 0., -1., 0., media_box.ury - media_box.lly);
+        Ok(())
+    }
+
+    fn end_page(&mut self) -> Result<(), OutputError> {
+        self.lines
+            .extend(page_lines(std::mem::take(&mut self.glyphs)));
+        Ok(())
+    }
+
+    fn output_character(
+        &mut self,
+        trm: &Transform,
+        width: f64,
+        _spacing: f64,
+        font_size: f64,
+        char: &str,
+    ) -> Result<(), OutputError> {
+        let position = trm.post_transform(&self.flip_ctm);
+        // The font size as scaled by the text matrix, assuming the text isn't rotated
+        let size = font_size * (trm.m11 * trm.m22).abs().sqrt();
+        self.glyphs.push(Glyph {
+            text: char.to_string(),
+            x: position.m31,
+            y: position.m32,
+            width: width * size,
+            size,
+        });
+        Ok(())
+    }
+
+    fn begin_word(&mut self) -> Result<(), OutputError> {
+        Ok(())
+    }
+
+    fn end_word(&mut self) -> Result<(), OutputError> {
+        Ok(())
+    }
+
+    fn end_line(&mut self) -> Result<(), OutputError> {
+        Ok(())
+    }
+}
+
+fn extract_lines(pdf: &[u8]) -> Result<Vec<String>, AppError> {
+    let invalid =
+        |e: &dyn std::fmt::Display| AppError::Parser(format!("Invalid grade sheet PDF: {e}"));
+
+    let mut document = Document::load_mem(pdf).map_err(|e| invalid(&e))?;
+    if document.is_encrypted() {
+        // The grade sheet is only protected against editing, with an empty user password
+        document.decrypt("").map_err(|e| invalid(&e))?;
+    }
+    let mut collector = GlyphCollector {
+        flip_ctm: Transform::identity(),
+        glyphs: Vec::new(),
+        lines: Vec::new(),
+    };
+    // The extractor panics on some malformed fonts, and the file comes straight from the user
+    panic::catch_unwind(AssertUnwindSafe(|| {
+        pdf_extract::output_doc(&document, &mut collector)
+    }))
+    .map_err(|_| invalid(&"unsupported content"))?
+    .map_err(|e| invalid(&e))?;
+    Ok(collector.lines)
+}
+
+/// Parses the grade sheet PDF as downloaded from the Technion, by rebuilding the table rows from
+/// the position of every glyph on the page rather than from text a PDF viewer copied.
+pub fn parse_pdf(pdf: &[u8]) -> (Result<Vec<CourseStatus>, AppError>, ParseReport) {
+    let lines = if pdf.starts_with(b"%PDF") {
+        extract_lines(pdf)
+    } else {
+        Err(AppError::Parser("Invalid grade sheet PDF".into()))
+    };
+    match lines {
+        Ok(lines) => parser_v2::parse_logical_text(&lines.join("\n")),
+        Err(e) => (
+            Err(e),
+            ParseReport {
+                format: Some(Format::Pdf),
+                ..Default::default()
+            },
+        ),
+    }
+}
+
+#[cfg(test)]
+#[path = "transcript_pdf_tests.rs"]
+mod transcript_pdf_tests;
@@ -65,7 +65,7 @@ pub enum Rule {
     Elective,
     Chains(Vec<Chain>), // למשל שרשרת מדעית.
     SpecializationGroups(SpecializationGroups),
-    Wildcard(bool), // קלף משוגע עבור להתמודד עם
+    Wildcard(bool), // קלף משוגע: כל קורס שלא שובץ בבנק אחר, עד לנקודות הבנק. true - גם קורסים מחוץ לפקולטה
 }
 
 impl Rule {
@@ -91,12 +91,44 @@ impl fmt::Display for Rule {
     }
 }
 
+// At least `count` of the courses in `course_list` must be completed in the bank, e.g. 2 labs out of
+// list B. `name` labels the list in messages.
+#[derive(PartialEq, Clone, Debug, Deserialize, Serialize)]
+pub struct CourseCountConstraint {
+    pub name: String,
+    pub course_list: Vec<CourseId>,
+    pub count: usize,
+}
+
+// At most `credit` of the bank may come from courses whose number starts with one of `prefixes`,
+// e.g. courses outside the faculty. The rest moves on like any other credit overflow.
+#[derive(PartialEq, Clone, Debug, Deserialize, Serialize)]
+pub struct CreditCapConstraint {
+    pub name: String,
+    pub prefixes: Vec<String>,
+    pub credit: f32,
+}
+
+// Requirements a bank has on top of its rule.
+#[derive(Default, PartialEq, Clone, Debug, Deserialize, Serialize)]
+pub struct BankConstraints {
+    // Courses with a lower numeric grade don't count toward the bank
+    #[serde(default)]
+    pub min_grade: Option<u32>,
+    #[serde(default)]
+    pub min_courses: Vec<CourseCountConstraint>,
+    #[serde(default)]
+    pub max_credit: Vec<CreditCapConstraint>,
+}
+
 #[derive(Clone, Debug, Deserialize, Serialize)]
 pub struct CreditOverflow {
     pub from: String,
     pub to: String,
 }
 
+#[derive(Clone, Copy, Debug, PartialEq, Deserialize, Serialize)]
+#[serde(rename_all = "snake_case")]
 pub enum Transfer {
     CreditOverflow,
     MissingCredit,
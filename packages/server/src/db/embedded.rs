@@ -0,0 +1,636 @@
+//! An embedded document store in a single redb file, for running without a MongoDB cluster.
+//!
+//! Each collection of a profile is a table named "{profile}/{collection}" which maps the document's
+//! `_id` to the document as BSON. Queries scan the whole table, which is fine for the few thousand
+//! documents of a small deployment. Aggregation pipelines are MongoDB only.
+
+use std::path::Path;
+use std::sync::Arc;
+
+use bson::{Bson, Document};
+use redb::{
+    backends::InMemoryBackend, Database, ReadableDatabase, ReadableTable, TableDefinition,
+    TableError,
+};
+use regex::Regex;
+
+use super::storage::Storage;
+use crate::error::AppError;
+
+/// Bumped whenever the layout of the file changes; files of another version are refused.
+pub const FORMAT_VERSION: u64 = 1;
+
+const META_TABLE: TableDefinition<&str, u64> = TableDefinition::new("_meta");
+const FORMAT_VERSION_KEY: &str = "format_version";
+
+fn storage_error(err: impl Into<redb::Error>) -> AppError {
+    AppError::from(err.into())
+}
+
+#[derive(Clone)]
+pub(super) struct EmbeddedStorage {
+    db: Arc<Database>,
+    profile: String,
+}
+
+impl std::fmt::Debug for EmbeddedStorage {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("EmbeddedStorage")
+            .field("profile", &self.profile)
+            .finish_non_exhaustive()
+    }
+}
+
+impl EmbeddedStorage {
+    /// Opens the file at `path`, creating it if it doesn't exist.
+    pub(super) fn open(path: &Path, profile: &str) -> Result<Self, AppError> {
+        let db = Database::create(path).map_err(storage_error)?;
+        Self::init(db, profile)
+    }
+
+    pub(super) fn in_memory(profile: &str) -> Result<Self, AppError> {
+        let db = Database::builder()
+            .create_with_backend(InMemoryBackend::new())
+            .map_err(storage_error)?;
+        Self::init(db, profile)
+    }
+
+    fn init(db: Database, profile: &str) -> Result<Self, AppError> {
+        let txn = db.begin_write().map_err(storage_error)?;
+        {
+            let mut meta = txn.open_table(META_TABLE).map_err(storage_error)?;
+            let version = meta
+                .get(FORMAT_VERSION_KEY)
+                .map_err(storage_error)?
+                .map(|version| version.value());
+            match version {
+                None => {
+                    meta.insert(FORMAT_VERSION_KEY, FORMAT_VERSION)
+                        .map_err(storage_error)?;
+                }
+                Some(FORMAT_VERSION) => {}
+                Some(version) => {
+                    return Err(AppError::Storage(format!(
+                        "the file has format version {version}, expected {FORMAT_VERSION}"
+                    )))
+                }
+            }
+        }
+        txn.commit().map_err(storage_error)?;
+        Ok(Self {
+            db: Arc::new(db),
+            profile: profile.to_owned(),
+        })
+    }
+
+    // Runs a transaction on the blocking thread pool, since redb reads and the fsync of every
+    // commit would otherwise stall a runtime worker and the requests queued on it.
+    async fn blocking<T, F>(&self, transaction: F) -> Result<T, AppError>
+    where
+        T: Send + 'static,
+        F: FnOnce(&EmbeddedStorage) -> Result<T, AppError> + Send + 'static,
+    {
+        let storage = self.clone();
+        tokio::task::spawn_blocking(move || transaction(&storage))
+            .await
+            .map_err(|e| AppError::Storage(e.to_string()))?
+    }
+
+    fn table_name(&self, collection: &str) -> String {
+        format!("{}/{collection}", self.profile)
+    }
+
+    fn get_by_key(&self, collection: &str, key: &str) -> Result<Option<Document>, AppError> {
+        let name = self.table_name(collection);
+        let txn = self.db.begin_read().map_err(storage_error)?;
+        let table = match txn.open_table(TableDefinition::<&str, &[u8]>::new(&name)) {
+            Ok(table) => table,
+            Err(TableError::TableDoesNotExist(_)) => return Ok(None),
+            Err(e) => return Err(storage_error(e)),
+        };
+        table
+            .get(key)
+            .map_err(storage_error)?
+            .map(|bytes| Ok(Document::from_reader(bytes.value())?))
+            .transpose()
+    }
+
+    fn scan(&self, collection: &str, filter: &Document) -> Result<Vec<Document>, AppError> {
+        let name = self.table_name(collection);
+        let txn = self.db.begin_read().map_err(storage_error)?;
+        let table = match txn.open_table(TableDefinition::<&str, &[u8]>::new(&name)) {
+            Ok(table) => table,
+            Err(TableError::TableDoesNotExist(_)) => return Ok(Vec::new()),
+            Err(e) => return Err(storage_error(e)),
+        };
+        let filter = Filter::parse(filter)?;
+        let mut documents = Vec::new();
+        for entry in table.iter().map_err(storage_error)? {
+            let (_, bytes) = entry.map_err(storage_error)?;
+            let document = Document::from_reader(bytes.value())?;
+            if filter.matches(&document) {
+                documents.push(document);
+            }
+        }
+        Ok(documents)
+    }
+}
+
+// The `_id` of a key or filter document, if it's a plain equality on `_id`.
+fn id_key(filter: &Document) -> Option<String> {
+    match (filter.len(), filter.get("_id")) {
+        (1, Some(Bson::Document(_))) | (_, None) => None,
+        (1, Some(id)) => Some(match id {
+            Bson::String(id) => id.clone(),
+            Bson::ObjectId(id) => id.to_hex(),
+            id => id.to_string(),
+        }),
+        _ => None,
+    }
+}
+
+enum Condition {
+    Eq(Bson),
+    In(Vec<Bson>),
+    Regex(Regex),
+}
+
+/// The subset of MongoDB's query syntax `Db` uses: conditions on (dotted) fields, all of which must
+/// hold. As in MongoDB, a condition on an array field holds if it holds for any element.
+struct Filter(Vec<(String, Condition)>);
+
+impl Filter {
+    fn parse(filter: &Document) -> Result<Self, AppError> {
+        let mut conditions = Vec::new();
+        for (path, condition) in filter {
+            let operators = match condition {
+                Bson::Document(operators) if operators.keys().any(|key| key.starts_with('$')) => {
+                    operators
+                }
+                value => {
+                    conditions.push((path.clone(), Condition::Eq(value.clone())));
+                    continue;
+                }
+            };
+            for (operator, value) in operators {
+                let condition = match (operator.as_str(), value) {
+                    ("$eq", value) => Condition::Eq(value.clone()),
+                    ("$in", Bson::Array(values)) => Condition::In(values.clone()),
+                    ("$regex", Bson::String(pattern)) => {
+                        Condition::Regex(Regex::new(pattern).map_err(|e| {
+                            AppError::BadRequest(format!("Invalid regex {pattern:?}: {e}"))
+                        })?)
+                    }
+                    (operator, _) => {
+                        return Err(AppError::Storage(format!(
+                            "unsupported query operator {operator} on {path}"
+                        )))
+                    }
+                };
+                conditions.push((path.clone(), condition));
+            }
+        }
+        Ok(Filter(conditions))
+    }
+
+    fn matches(&self, document: &Document) -> bool {
+        self.0
+            .iter()
+            .all(|(path, condition)| condition.holds(document, path))
+    }
+
+    // The index of the first element of the array at `path` which the conditions on its fields hold
+    // for, which is what MongoDB's positional `$` stands for.
+    fn position(&self, document: &Document, path: &str) -> Option<usize> {
+        let prefix = format!("{path}.");
+        let mut values = Vec::new();
+        field_values(document, path, &mut values);
+        let Some(Bson::Array(elements)) = values.first() else {
+            return None;
+        };
+        elements.iter().position(|element| {
+            let Bson::Document(element) = element else {
+                return false;
+            };
+            self.0.iter().all(|(path, condition)| {
+                path.strip_prefix(&prefix)
+                    .is_none_or(|path| condition.holds(element, path))
+            })
+        })
+    }
+}
+
+impl Condition {
+    fn holds(&self, document: &Document, path: &str) -> bool {
+        let mut values = Vec::new();
+        field_values(document, path, &mut values);
+        // As in MongoDB, a missing field is null
+        if values.is_empty() {
+            values.push(&Bson::Null);
+        }
+        values.into_iter().any(|value| match self {
+            Condition::Eq(expected) => value == expected,
+            Condition::In(expected) => expected.contains(value),
+            Condition::Regex(regex) => {
+                matches!(value, Bson::String(value) if regex.is_match(value))
+            }
+        })
+    }
+}
+
+// The values at a dotted path, descending into arrays: both an array and its elements are values.
+fn field_values<'a>(document: &'a Document, path: &str, values: &mut Vec<&'a Bson>) {
+    let (field, rest) = match path.split_once('.') {
+        Some((field, rest)) => (field, Some(rest)),
+        None => (path, None),
+    };
+    let Some(value) = document.get(field) else {
+        return;
+    };
+    match (value, rest) {
+        (Bson::Array(elements), None) => {
+            values.push(value);
+            values.extend(elements);
+        }
+        (value, None) => values.push(value),
+        (Bson::Document(inner), Some(rest)) => field_values(inner, rest, values),
+        (Bson::Array(elements), Some(rest)) => {
+            for element in elements {
+                if let Bson::Document(inner) = element {
+                    field_values(inner, rest, values);
+                }
+            }
+        }
+        (_, Some(_)) => {}
+    }
+}
+
+// Sets the value at a dotted path, creating the documents along it. Elements of arrays are
+// selected with `$[]` for all of them, or `$` for the one at `position`.
+fn set_path(
+    document: &mut Document,
+    path: &str,
+    value: Bson,
+    position: Option<usize>,
+) -> Result<(), AppError> {
+    let Some((field, rest)) = path.split_once('.') else {
+        document.insert(path, value);
+        return Ok(());
+    };
+    let inner = document
+        .entry(field.to_string())
+        .or_insert_with(|| Bson::Document(Document::new()));
+    let elements = match inner {
+        Bson::Document(inner) => return set_path(inner, rest, value, position),
+        Bson::Array(elements) => elements,
+        _ => {
+            return Err(AppError::Storage(format!(
+                "can't set {path}, {field} isn't a document"
+            )))
+        }
+    };
+    let (selector, rest) = match rest.split_once('.') {
+        Some((selector, rest)) => (selector, Some(rest)),
+        None => (rest, None),
+    };
+    let indices = match (selector, position) {
+        ("$[]", _) => (0..elements.len()).collect::<Vec<_>>(),
+        ("$", Some(position)) => vec![position],
+        _ => {
+            return Err(AppError::Storage(format!(
+                "can't set {path}, elements of {field} are selected with $[] or a matching $"
+            )))
+        }
+    };
+    for index in indices {
+        match (&mut elements[index], rest) {
+            (element, None) => *element = value.clone(),
+            (Bson::Document(element), Some(rest)) => {
+                set_path(element, rest, value.clone(), position)?
+            }
+            (_, Some(_)) => {
+                return Err(AppError::Storage(format!(
+                    "can't set {path}, elements of {field} aren't documents"
+                )))
+            }
+        }
+    }
+    Ok(())
+}
+
+// Compares the values of a field for `$sort`, which only sorts by strings and numbers here.
+fn compare(a: Option<&Bson>, b: Option<&Bson>) -> std::cmp::Ordering {
+    let number = |value: &Bson| match value {
+        Bson::Int32(n) => Some(*n as f64),
+        Bson::Int64(n) => Some(*n as f64),
+        Bson::Double(n) => Some(*n),
+        _ => None,
+    };
+    match (a, b) {
+        (Some(Bson::String(a)), Some(Bson::String(b))) => a.cmp(b),
+        (Some(a), Some(b)) => number(a)
+            .partial_cmp(&number(b))
+            .unwrap_or(std::cmp::Ordering::Equal),
+        _ => std::cmp::Ordering::Equal,
+    }
+}
+
+// Appends `value` to `elements`, or its `$each` with the `$sort` (by a field) and `$slice`
+// modifiers.
+fn push(elements: &mut Vec<Bson>, value: &Bson) -> Result<(), AppError> {
+    let Some(modifiers) = value
+        .as_document()
+        .filter(|modifiers| modifiers.contains_key("$each"))
+    else {
+        elements.push(value.clone());
+        return Ok(());
+    };
+    let each = modifiers
+        .get_array("$each")
+        .map_err(|_| AppError::Storage(format!("invalid $each in {modifiers}")))?;
+    elements.extend(each.iter().cloned());
+    if let Some(sort) = modifiers.get("$sort") {
+        let Some((field, order)) = sort.as_document().and_then(|sort| sort.iter().next()) else {
+            return Err(AppError::Storage(format!(
+                "unsupported $sort in {modifiers}"
+            )));
+        };
+        fn field_of<'a>(element: &'a Bson, field: &str) -> Option<&'a Bson> {
+            element.as_document().and_then(|element| element.get(field))
+        }
+        elements.sort_by(|a, b| compare(field_of(a, field), field_of(b, field)));
+        if matches!(order, Bson::Int32(-1) | Bson::Int64(-1)) {
+            elements.reverse();
+        }
+    }
+    match modifiers.get("$slice") {
+        None => {}
+        Some(Bson::Int32(n)) if *n >= 0 => elements.truncate(*n as usize),
+        Some(Bson::Int64(n)) if *n >= 0 => elements.truncate(*n as usize),
+        Some(slice) => {
+            return Err(AppError::Storage(format!("unsupported $slice {slice}")));
+        }
+    }
+    Ok(())
+}
+
+// Applies `$set`, `$inc` and `$push` to a document which `filter` matched.
+fn apply_update(
+    document: &mut Document,
+    filter: &Filter,
+    update: &Document,
+) -> Result<(), AppError> {
+    for (operator, fields) in update {
+        let Bson::Document(fields) = fields else {
+            return Err(AppError::Storage(format!("invalid {operator} in {update}")));
+        };
+        for (path, value) in fields {
+            let mut current = Vec::new();
+            field_values(document, path, &mut current);
+            let value = match operator.as_str() {
+                "$set" => value.clone(),
+                "$inc" => match (current.first(), value) {
+                    (None, value) => value.clone(),
+                    (Some(Bson::Int64(a)), Bson::Int64(b)) => Bson::Int64(a + b),
+                    (Some(Bson::Int32(a)), Bson::Int64(b)) => Bson::Int64(*a as i64 + b),
+                    (Some(Bson::Int64(a)), Bson::Int32(b)) => Bson::Int64(a + *b as i64),
+                    (Some(Bson::Int32(a)), Bson::Int32(b)) => Bson::Int32(a + b),
+                    _ => {
+                        return Err(AppError::Storage(format!(
+                            "can't increment {path} by {value}"
+                        )))
+                    }
+                },
+                "$push" => {
+                    let mut elements = match current.first() {
+                        None => Vec::new(),
+                        Some(Bson::Array(elements)) => elements.clone(),
+                        Some(_) => {
+                            return Err(AppError::Storage(format!(
+                                "can't push to {path}, it isn't an array"
+                            )))
+                        }
+                    };
+                    push(&mut elements, value)?;
+                    Bson::Array(elements)
+                }
+                operator => {
+                    return Err(AppError::Storage(format!(
+                        "unsupported update operator {operator}"
+                    )))
+                }
+            };
+            let position = path
+                .split_once(".$.")
+                .map(|(array, _)| array)
+                .or_else(|| path.strip_suffix(".$"))
+                .and_then(|array| filter.position(document, array));
+            set_path(document, path, value, position)?;
+        }
+    }
+    Ok(())
+}
+
+// The transactions behind `Storage`, which block on the file.
+impl EmbeddedStorage {
+    fn find_one_blocking(
+        &self,
+        collection: &str,
+        filter: Document,
+    ) -> Result<Option<Document>, AppError> {
+        match id_key(&filter) {
+            Some(key) => self.get_by_key(collection, &key),
+            None => Ok(self.scan(collection, &filter)?.into_iter().next()),
+        }
+    }
+
+    fn find_blocking(&self, collection: &str, filter: Document) -> Result<Vec<Document>, AppError> {
+        self.scan(collection, &filter)
+    }
+
+    fn upsert_blocking(
+        &self,
+        collection: &str,
+        key: Document,
+        update: Document,
+    ) -> Result<Document, AppError> {
+        let id = id_key(&key).ok_or_else(|| {
+            AppError::Storage(format!(
+                "documents are keyed by _id, got {key} in {collection}"
+            ))
+        })?;
+        let (operator, fields) = match update.iter().next() {
+            Some((operator, Bson::Document(fields))) if update.len() == 1 => (operator, fields),
+            _ => {
+                return Err(AppError::Storage(format!(
+                    "unsupported update {update} in {collection}"
+                )))
+            }
+        };
+
+        let name = self.table_name(collection);
+        let txn = self.db.begin_write().map_err(storage_error)?;
+        let document = {
+            let mut table = txn
+                .open_table(TableDefinition::<&str, &[u8]>::new(&name))
+                .map_err(storage_error)?;
+            let existing = table
+                .get(id.as_str())
+                .map_err(storage_error)?
+                .map(|bytes| Document::from_reader(bytes.value()))
+                .transpose()?;
+            let document = match (operator.as_str(), existing) {
+                ("$setOnInsert", Some(existing)) => return Ok(existing),
+                ("$set", Some(mut existing)) => {
+                    existing.extend(fields.clone());
+                    existing
+                }
+                ("$set" | "$setOnInsert", None) => {
+                    let mut document = key;
+                    document.extend(fields.clone());
+                    document
+                }
+                (operator, _) => {
+                    return Err(AppError::Storage(format!(
+                        "unsupported update operator {operator} in {collection}"
+                    )))
+                }
+            };
+            table
+                .insert(id.as_str(), document.to_vec()?.as_slice())
+                .map_err(storage_error)?;
+            document
+        };
+        txn.commit().map_err(storage_error)?;
+        Ok(document)
+    }
+
+    fn update_one_blocking(
+        &self,
+        collection: &str,
+        filter: Document,
+        update: Document,
+    ) -> Result<Option<Document>, AppError> {
+        let id = filter
+            .get("_id")
+            .and_then(|id| id_key(&bson::doc! {"_id": id.clone()}))
+            .ok_or_else(|| {
+                AppError::Storage(format!("updates need an _id, got {filter} in {collection}"))
+            })?;
+        let matcher = Filter::parse(&filter)?;
+
+        let name = self.table_name(collection);
+        let txn = self.db.begin_write().map_err(storage_error)?;
+        let document = {
+            let mut table = txn
+                .open_table(TableDefinition::<&str, &[u8]>::new(&name))
+                .map_err(storage_error)?;
+            let existing = table
+                .get(id.as_str())
+                .map_err(storage_error)?
+                .map(|bytes| Document::from_reader(bytes.value()))
+                .transpose()?;
+            let Some(mut document) = existing.filter(|existing| matcher.matches(existing)) else {
+                return Ok(None);
+            };
+            apply_update(&mut document, &matcher, &update)?;
+            table
+                .insert(id.as_str(), document.to_vec()?.as_slice())
+                .map_err(storage_error)?;
+            document
+        };
+        txn.commit().map_err(storage_error)?;
+        Ok(Some(document))
+    }
+
+    fn delete_blocking(&self, collection: &str, key: Document) -> Result<bool, AppError> {
+        let Some(id) = id_key(&key) else {
+            return Err(AppError::Storage(format!(
+                "documents are keyed by _id, got {key} in {collection}"
+            )));
+        };
+        let name = self.table_name(collection);
+        let txn = self.db.begin_write().map_err(storage_error)?;
+        let deleted = {
+            let mut table = txn
+                .open_table(TableDefinition::<&str, &[u8]>::new(&name))
+                .map_err(storage_error)?;
+            let removed = table.remove(id.as_str()).map_err(storage_error)?;
+            removed.is_some()
+        };
+        txn.commit().map_err(storage_error)?;
+        Ok(deleted)
+    }
+}
+
+impl Storage for EmbeddedStorage {
+    async fn find_one(
+        &self,
+        collection: &str,
+        filter: Document,
+    ) -> Result<Option<Document>, AppError> {
+        let collection = collection.to_owned();
+        self.blocking(move |storage| storage.find_one_blocking(&collection, filter))
+            .await
+    }
+
+    async fn find(&self, collection: &str, filter: Document) -> Result<Vec<Document>, AppError> {
+        let collection = collection.to_owned();
+        self.blocking(move |storage| storage.find_blocking(&collection, filter))
+            .await
+    }
+
+    async fn upsert(
+        &self,
+        collection: &str,
+        key: Document,
+        update: Document,
+    ) -> Result<Document, AppError> {
+        let collection = collection.to_owned();
+        self.blocking(move |storage| storage.upsert_blocking(&collection, key, update))
+            .await
+    }
+
+    async fn update_one(
+        &self,
+        collection: &str,
+        filter: Document,
+        update: Document,
+    ) -> Result<Option<Document>, AppError> {
+        let collection = collection.to_owned();
+        self.blocking(move |storage| storage.update_one_blocking(&collection, filter, update))
+            .await
+    }
+
+    async fn delete(&self, collection: &str, key: Document) -> Result<bool, AppError> {
+        let collection = collection.to_owned();
+        self.blocking(move |storage| storage.delete_blocking(&collection, key))
+            .await
+    }
+
+    async fn aggregate(
+        &self,
+        collection: &str,
+        _pipeline: Vec<Document>,
+    ) -> Result<Vec<Document>, AppError> {
+        Err(AppError::Storage(format!(
+            "aggregating {collection} needs the MongoDB backend"
+        )))
+    }
+
+    async fn create_index(&self, _collection: &str, _field: &str) -> Result<(), AppError> {
+        Ok(())
+    }
+
+    async fn ping(&self) -> Result<(), AppError> {
+        self.blocking(|storage| {
+            storage.db.begin_read().map_err(storage_error)?;
+            Ok(())
+        })
+        .await
+    }
+}
+
+#[cfg(test)]
+#[path = "embedded_tests.rs"]
+mod embedded_tests;
@@ -0,0 +1,259 @@
+use crate::{
+    core::timetable::notifications::MAX_NOTIFICATIONS,
+    db::{Db, FilterOption},
+    resources::{
+        course::{AcademicSemester, Course, CourseId, SemesterSeason},
+        user::{Notification, TimetableDraft, User},
+    },
+    sap::changes::CourseChange,
+};
+
+use super::*;
+
+fn course(id: &str, name: &str) -> Course {
+    Course {
+        id: CourseId::new(id),
+        name: name.to_string(),
+        ..Default::default()
+    }
+}
+
+#[tokio::test]
+async fn stores_resources_by_key() {
+    let db = Db::in_memory("test").unwrap();
+    assert!(db.get_all::<Course>().await.unwrap().is_empty());
+    assert!(matches!(
+        db.get::<Course>("01040031").await,
+        Err(AppError::NotFound(_))
+    ));
+
+    db.create_or_update(course("01040031", "חשבון אינפיניטסימלי 1מ'"))
+        .await
+        .unwrap();
+    // Only sets the fields of a new resource
+    let kept = db
+        .create_or_update(course("01040031", "renamed"))
+        .await
+        .unwrap();
+    assert_eq!(kept.name, "חשבון אינפיניטסימלי 1מ'");
+    let updated = db.update(course("01040031", "renamed")).await.unwrap();
+    assert_eq!(updated.name, "renamed");
+    assert_eq!(db.get::<Course>("01040031").await.unwrap().name, "renamed");
+
+    db.update(course("02340114", "מבוא למדעי המחשב מ'"))
+        .await
+        .unwrap();
+    assert_eq!(db.get_all::<Course>().await.unwrap().len(), 2);
+
+    assert!(db.delete_one::<Course>("01040031").await.unwrap());
+    assert!(!db.delete_one::<Course>("01040031").await.unwrap());
+    assert_eq!(db.get_all::<Course>().await.unwrap().len(), 1);
+}
+
+#[tokio::test]
+async fn filters_like_mongodb() {
+    let db = Db::in_memory("test").unwrap();
+    for (id, name) in [
+        ("01040031", "חשבון אינפיניטסימלי 1מ'"),
+        ("01040166", "אלגברה אמ'"),
+        ("02340114", "מבוא למדעי המחשב מ'"),
+    ] {
+        db.update(course(id, name)).await.unwrap();
+    }
+
+    let courses = db
+        .get_filtered::<Course>(FilterOption::Regex, "name", "^אלגברה")
+        .await
+        .unwrap();
+    assert_eq!(courses.len(), 1);
+    assert_eq!(*courses[0].id, *"01040166");
+
+    let courses = db
+        .get_filtered::<Course>(FilterOption::In, "_id", vec!["01040031", "02340114", "1"])
+        .await
+        .unwrap();
+    assert_eq!(courses.len(), 2);
+
+    // Conditions on fields inside arrays hold if they hold for any element
+    let published = User {
+        sub: "published".to_string(),
+        timetable: crate::resources::user::TimetableState {
+            drafts: vec![
+                TimetableDraft::default(),
+                TimetableDraft {
+                    is_published: true,
+                    ..Default::default()
+                },
+            ],
+            ..Default::default()
+        },
+        ..Default::default()
+    };
+    db.update(published).await.unwrap();
+    db.update(User {
+        sub: "unpublished".to_string(),
+        ..Default::default()
+    })
+    .await
+    .unwrap();
+    let users = db
+        .get_filtered::<User>(
+            FilterOption::In,
+            "timetable.drafts.is_published",
+            vec![true],
+        )
+        .await
+        .unwrap();
+    assert_eq!(users.len(), 1);
+    assert_eq!(users[0].sub, "published");
+}
+
+#[tokio::test]
+async fn keeps_profiles_apart_and_persists() {
+    let path = std::env::temp_dir().join(format!("sogrim_embedded_{}.redb", std::process::id()));
+    let _ = std::fs::remove_file(&path);
+    {
+        let db = Db::open_embedded(&path, "debug").unwrap();
+        db.update(course("01040031", "debug")).await.unwrap();
+        db.ping().await.unwrap();
+        assert!(matches!(
+            db.aggregate::<Course>("Courses", vec![]).await,
+            Err(AppError::Storage(_))
+        ));
+    }
+
+    let db = Db::connect(&format!("redb://{}", path.display()), "debug")
+        .await
+        .unwrap();
+    assert_eq!(db.get::<Course>("01040031").await.unwrap().name, "debug");
+    drop(db);
+    let db = Db::open_embedded(&path, "production").unwrap();
+    assert!(db.get_all::<Course>().await.unwrap().is_empty());
+    drop(db);
+
+    // Refuses files of another format version
+    {
+        let file = Database::create(&path).unwrap();
+        let txn = file.begin_write().unwrap();
+        txn.open_table(META_TABLE)
+            .unwrap()
+            .insert(FORMAT_VERSION_KEY, FORMAT_VERSION + 1)
+            .unwrap();
+        txn.commit().unwrap();
+    }
+    assert!(matches!(
+        Db::open_embedded(&path, "debug"),
+        Err(AppError::Storage(_))
+    ));
+    std::fs::remove_file(&path).unwrap();
+}
+
+#[tokio::test]
+async fn updates_fields_of_the_expected_revision() {
+    let db = Db::in_memory("test").unwrap();
+    // Saved before users had a revision
+    db.update(User {
+        sub: "bugo".to_string(),
+        ..Default::default()
+    })
+    .await
+    .unwrap();
+
+    let user = db
+        .update_fields::<User>(
+            "bugo",
+            Some(0),
+            bson::doc! {"settings.dark_mode": true},
+            true,
+        )
+        .await
+        .unwrap();
+    assert_eq!(user.revision, 1);
+    assert!(user.settings.dark_mode);
+
+    assert!(matches!(
+        db.update_fields::<User>("bugo", Some(0), bson::doc! {"details.modified": true}, true)
+            .await,
+        Err(AppError::Conflict(_))
+    ));
+    let user = db
+        .update_fields::<User>("bugo", None, bson::doc! {"details.modified": true}, false)
+        .await
+        .unwrap();
+    assert_eq!(user.revision, 1);
+    assert!(user.details.modified);
+    assert!(user.settings.dark_mode);
+
+    assert!(matches!(
+        db.update_fields::<User>("nobody", None, bson::doc! {"details.modified": true}, true)
+            .await,
+        Err(AppError::NotFound(_))
+    ));
+}
+
+#[tokio::test]
+async fn updates_notifications_element_by_element() {
+    let notification = |i: usize| Notification {
+        id: i.to_string(),
+        created_at: format!("2026-01-01T00:{:02}:{:02}+00:00", i / 60, i % 60),
+        semester: AcademicSemester::new(SemesterSeason::Winter, 2025),
+        draft_id: "a".to_string(),
+        change: CourseChange::Removed {
+            course_id: CourseId::new("02340114"),
+            name: "מבוא למדעי המחשב מ'".to_string(),
+        },
+        read: false,
+    };
+    let db = Db::in_memory("test").unwrap();
+    let user = db
+        .update(User {
+            sub: "bugo".to_string(),
+            ..Default::default()
+        })
+        .await
+        .unwrap();
+
+    // Nothing to mark read yet, and no notifications array for `$[]` either
+    assert!(!user.mark_read(&db, None).await.unwrap());
+    user.push_notifications(&db, vec![notification(1), notification(3)])
+        .await
+        .unwrap();
+    assert!(user.mark_read(&db, Some("3")).await.unwrap());
+    assert!(!user.mark_read(&db, Some("2")).await.unwrap());
+
+    // Pushing more keeps the read one read, sorted newest first
+    user.push_notifications(&db, vec![notification(2)])
+        .await
+        .unwrap();
+    let stored = db.get::<User>("bugo").await.unwrap();
+    let inbox = stored
+        .notifications
+        .iter()
+        .map(|notification| (notification.id.as_str(), notification.read))
+        .collect::<Vec<_>>();
+    assert_eq!(inbox, vec![("3", true), ("2", false), ("1", false)]);
+
+    assert!(user.mark_read(&db, None).await.unwrap());
+    let stored = db.get::<User>("bugo").await.unwrap();
+    assert!(stored
+        .notifications
+        .iter()
+        .all(|notification| notification.read));
+
+    user.push_notifications(
+        &db,
+        (10..MAX_NOTIFICATIONS + 10).map(notification).collect(),
+    )
+    .await
+    .unwrap();
+    let stored = db.get::<User>("bugo").await.unwrap();
+    assert_eq!(stored.notifications.len(), MAX_NOTIFICATIONS);
+    assert_eq!(
+        stored.notifications[0].id,
+        (MAX_NOTIFICATIONS + 9).to_string()
+    );
+    assert!(stored
+        .notifications
+        .iter()
+        .all(|notification| !notification.read));
+}
@@ -1,74 +1,119 @@
-use bson::{doc, Document};
-use mongodb::{Client, Collection};
+use std::path::Path;
+
+use bson::Document;
+use mongodb::Client;
 use serde::{de::DeserializeOwned, Serialize};
 
 use crate::error::AppError;
 
+mod embedded;
+mod mongo;
 pub mod services;
+mod storage;
 
 #[cfg(test)]
 pub mod tests;
 
+pub use storage::Storage;
+
+use embedded::EmbeddedStorage;
+use mongo::MongoStorage;
+
+/// URIs with this scheme open an embedded database file instead of connecting to MongoDB, e.g.
+/// `redb:///var/lib/sogrim/sogrim.redb` or `redb://sogrim.redb` (relative to the working directory).
+pub const EMBEDDED_URI_SCHEME: &str = "redb://";
+
+/// The catalogs and grade sheets the tests use, relative to `packages/server`.
+const TEST_FIXTURES_DIR: &str = "../docs";
+
+#[derive(Debug, Clone)]
+enum Backend {
+    Mongo(MongoStorage),
+    Embedded(EmbeddedStorage),
+}
+
+// Dispatches a `Storage` call to the backend in use.
+macro_rules! dispatch {
+    ($db:expr, $storage:ident => $call:expr) => {
+        match &$db.backend {
+            Backend::Mongo($storage) => $call,
+            Backend::Embedded($storage) => $call,
+        }
+    };
+}
+pub(crate) use dispatch;
+
 #[derive(Debug, Clone)]
 pub struct Db {
-    client: Client,
-    profile: String,
+    backend: Backend,
 }
 
 impl Db {
+    /// Connects to MongoDB, or opens an embedded database for a `redb://` URI. The profile names
+    /// the MongoDB database, or prefixes the tables of the embedded one.
     pub async fn connect(uri: &str, profile: &str) -> Result<Self, AppError> {
+        if let Some(path) = uri.strip_prefix(EMBEDDED_URI_SCHEME) {
+            return Self::open_embedded(Path::new(path), profile);
+        }
         let client = Client::with_uri_str(uri)
             .await
             .map_err(|e| AppError::InternalServer(format!("MongoDB connection failed: {e}")))?;
-        Ok(Self {
-            client,
-            profile: profile.to_owned(),
-        })
-    }
-
-    pub fn collection<R: Resource + Send + Sync>(&self) -> Collection<R> {
-        self.client
-            .database(&self.profile)
-            .collection(R::collection_name())
+        Ok(Self::with_client(client, profile))
     }
 
     pub fn with_client(client: Client, profile: &str) -> Self {
         Self {
-            client,
-            profile: profile.to_owned(),
+            backend: Backend::Mongo(MongoStorage {
+                client,
+                profile: profile.to_owned(),
+            }),
         }
     }
 
-    pub async fn ping(&self) -> Result<(), AppError> {
-        self.client()
-            .database("admin")
-            .run_command(doc! {"ping": 1})
-            .await
-            .map_err(|e| AppError::InternalServer(e.to_string()))
-            .map(|_| ())
+    pub fn open_embedded(path: &Path, profile: &str) -> Result<Self, AppError> {
+        Ok(Self {
+            backend: Backend::Embedded(EmbeddedStorage::open(path, profile)?),
+        })
     }
 
-    pub fn client(&self) -> &Client {
-        &self.client
+    /// An embedded database which lives only as long as this `Db` and its clones.
+    pub fn in_memory(profile: &str) -> Result<Self, AppError> {
+        Ok(Self {
+            backend: Backend::Embedded(EmbeddedStorage::in_memory(profile)?),
+        })
+    }
+
+    pub async fn ping(&self) -> Result<(), AppError> {
+        dispatch!(self, storage => storage.ping().await)
     }
 
-    pub fn profile(&self) -> &str {
-        &self.profile
+    /// Creates an ascending index on `field`. The embedded backend has no indexes.
+    pub async fn create_index(&self, collection: &str, field: &str) -> Result<(), AppError> {
+        dispatch!(self, storage => storage.create_index(collection, field).await)
     }
 
     /// Gracefully drain the connection pool. Call before the tokio runtime exits.
     pub async fn shutdown(self) {
-        self.client.shutdown().await;
+        if let Backend::Mongo(storage) = self.backend {
+            storage.client.shutdown().await;
+        }
     }
 
-    /// Convenience constructor for tests: reads SOGRIM_URI and SOGRIM_PROFILE from env.
+    /// Convenience constructor for tests: reads SOGRIM_URI and SOGRIM_PROFILE from env. Without
+    /// SOGRIM_URI, it's an in-memory embedded database seeded with the catalogs in `packages/docs`.
     pub async fn from_test_env() -> Self {
         let _ = dotenvy::dotenv();
-        let uri = std::env::var("SOGRIM_URI").expect("SOGRIM_URI must be set for tests");
         let profile = std::env::var("SOGRIM_PROFILE").unwrap_or_else(|_| "debug".into());
-        Self::connect(&uri, &profile)
+        if let Ok(uri) = std::env::var("SOGRIM_URI") {
+            return Self::connect(&uri, &profile)
+                .await
+                .expect("Failed to connect to the database for tests");
+        }
+        let db = Self::in_memory(&profile).expect("Failed to create an in-memory database");
+        crate::cli::catalog::seed(&db, Path::new(TEST_FIXTURES_DIR))
             .await
-            .expect("Failed to connect to MongoDB for tests")
+            .expect("Failed to seed the catalogs for tests");
+        db
     }
 }
 
@@ -0,0 +1,106 @@
+use bson::{doc, Document};
+use futures_util::TryStreamExt;
+use mongodb::{options::ReturnDocument, Client, Collection};
+
+use super::storage::Storage;
+use crate::error::AppError;
+
+#[derive(Debug, Clone)]
+pub(super) struct MongoStorage {
+    pub(super) client: Client,
+    pub(super) profile: String,
+}
+
+impl MongoStorage {
+    fn collection(&self, name: &str) -> Collection<Document> {
+        self.client.database(&self.profile).collection(name)
+    }
+}
+
+impl Storage for MongoStorage {
+    async fn find_one(
+        &self,
+        collection: &str,
+        filter: Document,
+    ) -> Result<Option<Document>, AppError> {
+        Ok(self.collection(collection).find_one(filter).await?)
+    }
+
+    async fn find(&self, collection: &str, filter: Document) -> Result<Vec<Document>, AppError> {
+        Ok(self
+            .collection(collection)
+            .find(filter)
+            .await?
+            .try_collect()
+            .await?)
+    }
+
+    async fn upsert(
+        &self,
+        collection: &str,
+        key: Document,
+        update: Document,
+    ) -> Result<Document, AppError> {
+        self.collection(collection)
+            .find_one_and_update(key, update)
+            .upsert(true)
+            .return_document(ReturnDocument::After)
+            .await?
+            .ok_or_else(|| {
+                // This should never happen, but to avoid unwrapping we return an explicit error
+                AppError::NotFound(collection.to_string())
+            })
+    }
+
+    async fn update_one(
+        &self,
+        collection: &str,
+        filter: Document,
+        update: Document,
+    ) -> Result<Option<Document>, AppError> {
+        Ok(self
+            .collection(collection)
+            .find_one_and_update(filter, update)
+            .return_document(ReturnDocument::After)
+            .await?)
+    }
+
+    async fn delete(&self, collection: &str, key: Document) -> Result<bool, AppError> {
+        let result = self.collection(collection).delete_one(key).await?;
+        Ok(result.deleted_count > 0)
+    }
+
+    async fn aggregate(
+        &self,
+        collection: &str,
+        pipeline: Vec<Document>,
+    ) -> Result<Vec<Document>, AppError> {
+        Ok(self
+            .collection(collection)
+            .aggregate(pipeline)
+            .await?
+            .try_collect()
+            .await?)
+    }
+
+    async fn create_index(&self, collection: &str, field: &str) -> Result<(), AppError> {
+        // A no-op if an equivalent index already exists
+        self.collection(collection)
+            .create_index(
+                mongodb::IndexModel::builder()
+                    .keys(doc! { field: 1 })
+                    .build(),
+            )
+            .await?;
+        Ok(())
+    }
+
+    async fn ping(&self) -> Result<(), AppError> {
+        self.client
+            .database("admin")
+            .run_command(doc! {"ping": 1})
+            .await
+            .map_err(|e| AppError::InternalServer(e.to_string()))
+            .map(|_| ())
+    }
+}
@@ -1,17 +1,15 @@
 use crate::error::AppError;
+use bson::{deserialize_from_document, serialize_to_bson, serialize_to_document};
 pub use bson::{doc, Bson};
-use bson::{serialize_to_bson, serialize_to_document};
-use futures_util::TryStreamExt;
-use mongodb::options::ReturnDocument;
 use serde::{de::DeserializeOwned, Serialize};
 
-use super::{Db, FilterOption, InsertOption, Resource};
+use super::{dispatch, Backend, Db, FilterOption, InsertOption, Resource, Storage};
 
 impl Db {
     /// Run an aggregation pipeline against an arbitrary collection and deserialize
     /// each result document into `R`. This is the generic escape hatch for read-only
     /// analytics pipelines (e.g. the admin BI `$facet`) that do not map onto a single
-    /// `Resource`. The raw client + profile drive the collection handle directly.
+    /// `Resource`. Only the MongoDB backend runs pipelines.
     pub async fn aggregate<R>(
         &self,
         collection: &str,
@@ -20,26 +18,21 @@ impl Db {
     where
         R: DeserializeOwned + Send + Sync,
     {
-        let docs: Vec<bson::Document> = self
-            .client()
-            .database(self.profile())
-            .collection::<bson::Document>(collection)
-            .aggregate(pipeline)
-            .await?
-            .try_collect()
-            .await?;
+        let docs = dispatch!(self, storage => storage.aggregate(collection, pipeline).await)?;
         docs.into_iter()
-            .map(|d| bson::deserialize_from_document(d).map_err(AppError::from))
+            .map(|d| deserialize_from_document(d).map_err(AppError::from))
             .collect()
     }
+
     pub async fn get<R>(&self, id: impl Serialize) -> Result<R, AppError>
     where
         R: Resource + Send + Sync + Unpin,
     {
         let id = serialize_to_bson(&id)?;
-        self.collection::<R>()
-            .find_one(doc! {"_id": id.clone()})
-            .await?
+        let filter = doc! {"_id": id.clone()};
+        dispatch!(self, storage => storage.find_one(R::collection_name(), filter).await)?
+            .map(deserialize_from_document)
+            .transpose()?
             .ok_or_else(|| AppError::NotFound(format!("{}: {}", R::collection_name(), id)))
     }
 
@@ -47,12 +40,10 @@ impl Db {
     where
         R: Resource + Send + Sync + Unpin,
     {
-        Ok(self
-            .collection::<R>()
-            .find(doc! {})
-            .await?
-            .try_collect::<Vec<R>>()
-            .await?)
+        let docs = dispatch!(self, storage => storage.find(R::collection_name(), doc! {}).await)?;
+        docs.into_iter()
+            .map(|d| deserialize_from_document(d).map_err(AppError::from))
+            .collect()
     }
 
     pub async fn get_filtered<R>(
@@ -64,30 +55,22 @@ impl Db {
     where
         R: Resource + DeserializeOwned + Send + Sync + Unpin,
     {
-        Ok(self
-            .collection::<R>()
-            .find(doc! {field_to_filter.as_ref(): { filter_option.as_ref(): filter.into()}})
-            .await?
-            .try_collect::<Vec<R>>()
-            .await?)
+        let filter = doc! {field_to_filter.as_ref(): { filter_option.as_ref(): filter.into()}};
+        let docs = dispatch!(self, storage => storage.find(R::collection_name(), filter).await)?;
+        docs.into_iter()
+            .map(|d| deserialize_from_document(d).map_err(AppError::from))
+            .collect()
     }
 
     async fn _update<R>(&self, resource: R, insert_option: InsertOption) -> Result<R, AppError>
     where
         R: Resource + Send + Sync + Unpin,
     {
-        self.collection::<R>()
-            .find_one_and_update(
-                resource.key(),
-                doc! { insert_option.as_ref(): serialize_to_document(&resource)? },
-            )
-            .upsert(true)
-            .return_document(ReturnDocument::After)
-            .await?
-            .ok_or_else(|| {
-                // This should never happen, but to avoid unwrapping we return an explicit error
-                AppError::NotFound(R::collection_name().to_string())
-            })
+        let key = resource.key();
+        let update = doc! { insert_option.as_ref(): serialize_to_document(&resource)? };
+        let doc =
+            dispatch!(self, storage => storage.upsert(R::collection_name(), key, update).await)?;
+        Ok(deserialize_from_document(doc)?)
     }
 
     pub async fn update<R>(&self, resource: R) -> Result<R, AppError>
@@ -104,15 +87,90 @@ impl Db {
         self._update::<R>(resource, InsertOption::SetOnInsert).await
     }
 
+    /// Sets the given fields (dotted paths allowed) of the resource with `id`, leaving the rest of
+    /// it as stored, and returns the resource. With `revision`, only if the stored `revision` field
+    /// still has that value, failing with `AppError::Conflict` otherwise. `bump_revision`
+    /// increments the stored revision along with the update.
+    pub async fn update_fields<R>(
+        &self,
+        id: impl Serialize,
+        revision: Option<u64>,
+        fields: bson::Document,
+        bump_revision: bool,
+    ) -> Result<R, AppError>
+    where
+        R: Resource + Send + Sync + Unpin,
+    {
+        let id = serialize_to_bson(&id)?;
+        let mut filter = doc! {"_id": id.clone()};
+        match revision {
+            // Documents saved before they had a revision are at 0
+            Some(0) => filter.insert("revision", doc! {"$in": [0_i64, Bson::Null]}),
+            Some(revision) => filter.insert("revision", revision as i64),
+            None => None,
+        };
+        let mut update = doc! {"$set": fields};
+        if bump_revision {
+            update.insert("$inc", doc! {"revision": 1_i64});
+        }
+
+        let updated = dispatch!(self, storage => {
+            storage.update_one(R::collection_name(), filter, update).await
+        })?;
+        match updated {
+            Some(doc) => Ok(deserialize_from_document(doc)?),
+            None => {
+                // Either it's gone, or someone else saved it since `revision`
+                let key = doc! {"_id": id.clone()};
+                let exists = dispatch!(self, storage => {
+                    storage.find_one(R::collection_name(), key).await
+                })?
+                .is_some();
+                Err(if exists {
+                    AppError::Conflict(format!(
+                        "{} {id} was changed since revision {}, reload it and try again",
+                        R::collection_name(),
+                        revision.unwrap_or_default()
+                    ))
+                } else {
+                    AppError::NotFound(format!("{}: {}", R::collection_name(), id))
+                })
+            }
+        }
+    }
+
+    /// Applies `update` to the resource with `id` if it also matches `filter`, and returns the
+    /// resource as stored. None if there's no such resource.
+    pub async fn update_matching<R>(
+        &self,
+        id: impl Serialize,
+        mut filter: bson::Document,
+        update: bson::Document,
+    ) -> Result<Option<R>, AppError>
+    where
+        R: Resource + Send + Sync + Unpin,
+    {
+        filter.insert("_id", serialize_to_bson(&id)?);
+        let updated = dispatch!(self, storage => {
+            storage.update_one(R::collection_name(), filter, update).await
+        })?;
+        Ok(updated.map(deserialize_from_document).transpose()?)
+    }
+
     pub async fn delete<R>(&self, id: impl Serialize) -> Result<(), AppError>
+    where
+        R: Resource + Send + Sync + Unpin,
+    {
+        self.delete_one::<R>(id).await.map(|_| ()) // Discard whether there was one
+    }
+
+    /// Like `delete`, but returns whether there was such a resource.
+    pub async fn delete_one<R>(&self, id: impl Serialize) -> Result<bool, AppError>
     where
         R: Resource + Send + Sync + Unpin,
     {
         let id = serialize_to_bson(&id)?;
-        Ok(self
-            .collection::<R>()
-            .delete_one(doc! {"_id": id.clone()})
-            .await
-            .map(|_| ())?) // Discard the result of the delete operation
+        let key = doc! {"_id": id};
+        dispatch!(self, storage => storage.delete(R::collection_name(), key).await)
     }
 }
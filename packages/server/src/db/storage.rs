@@ -0,0 +1,64 @@
+use std::future::Future;
+
+use bson::Document;
+
+use crate::error::AppError;
+
+/// The document store behind `Db`. Documents are addressed by collection name and filtered with
+/// MongoDB's query syntax; backends other than MongoDB support the subset `Db` uses: equality,
+/// `$in` and `$regex` on a (dotted) field.
+pub trait Storage: Send + Sync {
+    fn find_one(
+        &self,
+        collection: &str,
+        filter: Document,
+    ) -> impl Future<Output = Result<Option<Document>, AppError>> + Send;
+
+    fn find(
+        &self,
+        collection: &str,
+        filter: Document,
+    ) -> impl Future<Output = Result<Vec<Document>, AppError>> + Send;
+
+    /// Applies `update` (`$set` or `$setOnInsert`) to the document matching `key`, inserting it if
+    /// there's none, and returns the document as stored.
+    fn upsert(
+        &self,
+        collection: &str,
+        key: Document,
+        update: Document,
+    ) -> impl Future<Output = Result<Document, AppError>> + Send;
+
+    /// Applies `update` (`$set`, with dotted paths and the `$` and `$[]` array selectors, `$inc`,
+    /// and `$push`, with `$each`, `$sort` by a field and `$slice`) to the document matching
+    /// `filter`, which includes its `_id`, and returns the document as stored. None if there's no
+    /// match.
+    fn update_one(
+        &self,
+        collection: &str,
+        filter: Document,
+        update: Document,
+    ) -> impl Future<Output = Result<Option<Document>, AppError>> + Send;
+
+    /// Deletes the document matching `key`. Returns whether there was one.
+    fn delete(
+        &self,
+        collection: &str,
+        key: Document,
+    ) -> impl Future<Output = Result<bool, AppError>> + Send;
+
+    fn aggregate(
+        &self,
+        collection: &str,
+        pipeline: Vec<Document>,
+    ) -> impl Future<Output = Result<Vec<Document>, AppError>> + Send;
+
+    /// Creates an ascending index on `field`, if the backend has indexes.
+    fn create_index(
+        &self,
+        collection: &str,
+        field: &str,
+    ) -> impl Future<Output = Result<(), AppError>> + Send;
+
+    fn ping(&self) -> impl Future<Output = Result<(), AppError>> + Send;
+}
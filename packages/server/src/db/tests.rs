@@ -9,9 +9,11 @@ use mongodb::{
     Client,
 };
 
+// These test the MongoDB backend against the production data, so they're ignored unless run with
+// `--ignored` and SOGRIM_URI
 fn test_uri() -> String {
     let _ = dotenvy::dotenv();
-    std::env::var("SOGRIM_URI").expect("SOGRIM_URI must be set for tests")
+    std::env::var("SOGRIM_URI").expect("SOGRIM_URI must be set to a MongoDB URI")
 }
 
 fn test_profile() -> String {
@@ -19,9 +21,11 @@ fn test_profile() -> String {
 }
 
 #[tokio::test]
+#[ignore = "needs production SOGRIM_URI"]
 pub async fn test_db_internal_error() {
+    let uri = test_uri();
     // Create explicit client options and update it manually
-    let mut client_options = ClientOptions::parse(test_uri())
+    let mut client_options = ClientOptions::parse(uri)
         .await
         .expect("Failed to parse client options");
 
@@ -83,8 +87,10 @@ pub async fn test_db_internal_error() {
 }
 
 #[tokio::test]
+#[ignore = "needs production SOGRIM_URI"]
 pub async fn test_get_courses_by_filters() {
-    let db = Db::connect(&test_uri(), &test_profile())
+    let uri = test_uri();
+    let db = Db::connect(&uri, &test_profile())
         .await
         .expect("Failed to connect to MongoDB");
 
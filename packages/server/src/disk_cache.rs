@@ -5,20 +5,28 @@
 //!
 //! Directory structure:
 //!   {cache_dir}/{year}/{semester}/_index.json
+//!   {cache_dir}/{year}/{semester}/_semester.json
 //!   {cache_dir}/{year}/{semester}/{course_id}.json
+//!
+//! Everything loaded from disk lives in a single snapshot, which a reload builds from scratch and
+//! swaps in at once. Requests hold on to the snapshot they started with, so they never see a mix of
+//! old and new data.
 
 use std::collections::HashMap;
 use std::fs;
-use std::path::PathBuf;
+use std::path::{Path, PathBuf};
 use std::sync::Arc;
-use std::time::Duration;
+use std::time::{Duration, SystemTime};
 
 use moka::future::Cache;
 use serde::Serialize;
-use tokio::sync::RwLock;
+use tokio::sync::{Mutex, RwLock};
 
+use sogrim_server::core::course_search::{
+    CourseSearchIndex, SearchEntry, SearchQuery, SearchResults,
+};
 use sogrim_server::resources::course::{Course, CourseId};
-use sogrim_server::sap::{CourseDetails, CourseIndexEntry};
+use sogrim_server::sap::{CourseDetails, CourseIndexEntry, Semester};
 
 const CACHE_TTL_HOURS: u64 = 6;
 
@@ -31,30 +39,74 @@ pub struct DiskSemester {
     pub name: String,
 }
 
-/// Disk-backed course data with moka caching.
-pub struct DiskCourseCache {
-    cache_dir: PathBuf,
-    courses: Cache<String, Arc<CourseDetails>>,
+/// Course details along with the modification time of the file they were read from.
+#[derive(Debug, Clone)]
+pub struct CachedCourse {
+    pub details: Arc<CourseDetails>,
+    pub modified: SystemTime,
+}
+
+/// What a (re)load found on disk.
+#[derive(Debug, Clone, Serialize)]
+pub struct LoadStats {
+    pub semesters: usize,
+    pub courses: usize,
+    pub unique_courses: usize,
+}
+
+// The modification time of every semester directory. The fetcher renames each file into place,
+// which touches the directory, so any change to a semester changes its fingerprint.
+type Fingerprint = Vec<(String, Option<SystemTime>)>;
+
+/// Everything loaded from disk at one point in time.
+struct Snapshot {
+    courses: Cache<String, CachedCourse>,
     indexes: Cache<String, Arc<Vec<CourseIndexEntry>>>,
     /// Flat deduplicated course list built from all semesters.
     /// Most recent semester takes precedence for each course id.
-    all_courses: RwLock<HashMap<CourseId, Course>>,
+    all_courses: HashMap<CourseId, Course>,
+    /// Full-text index over the same courses.
+    search_index: CourseSearchIndex,
+    fingerprint: Fingerprint,
 }
 
-impl DiskCourseCache {
-    pub fn new(cache_dir: PathBuf) -> Self {
+impl Snapshot {
+    fn empty() -> Self {
         let ttl = Duration::from_secs(CACHE_TTL_HOURS * 3600);
         Self {
-            cache_dir,
             courses: Cache::builder()
                 .time_to_live(ttl)
                 .max_capacity(20_000)
                 .build(),
             indexes: Cache::builder().time_to_live(ttl).max_capacity(20).build(),
-            all_courses: RwLock::new(HashMap::new()),
+            all_courses: HashMap::new(),
+            search_index: CourseSearchIndex::default(),
+            fingerprint: Vec::new(),
+        }
+    }
+}
+
+/// Disk-backed course data with moka caching.
+pub struct DiskCourseCache {
+    cache_dir: PathBuf,
+    snapshot: RwLock<Arc<Snapshot>>,
+    // Held while a snapshot is built, so that concurrent reloads don't read the disk twice
+    reloading: Mutex<()>,
+}
+
+impl DiskCourseCache {
+    pub fn new(cache_dir: PathBuf) -> Self {
+        Self {
+            cache_dir,
+            snapshot: RwLock::new(Arc::new(Snapshot::empty())),
+            reloading: Mutex::new(()),
         }
     }
 
+    async fn snapshot(&self) -> Arc<Snapshot> {
+        self.snapshot.read().await.clone()
+    }
+
     /// Discover available semesters from the cache directory.
     pub fn discover_semesters(&self) -> Vec<DiskSemester> {
         let mut semesters = Vec::new();
@@ -107,9 +159,19 @@ impl DiskCourseCache {
         &self,
         year: &str,
         semester: &str,
+    ) -> Option<Arc<Vec<CourseIndexEntry>>> {
+        self.get_index_in(&*self.snapshot().await, year, semester)
+            .await
+    }
+
+    async fn get_index_in(
+        &self,
+        snapshot: &Snapshot,
+        year: &str,
+        semester: &str,
     ) -> Option<Arc<Vec<CourseIndexEntry>>> {
         let key = format!("{year}/{semester}");
-        if let Some(cached) = self.indexes.get(&key).await {
+        if let Some(cached) = snapshot.indexes.get(&key).await {
             return Some(cached);
         }
 
@@ -117,7 +179,7 @@ impl DiskCourseCache {
         let data = fs::read_to_string(&path).ok()?;
         let index: Vec<CourseIndexEntry> = serde_json::from_str(&data).ok()?;
         let arc = Arc::new(index);
-        self.indexes.insert(key, arc.clone()).await;
+        snapshot.indexes.insert(key, arc.clone()).await;
         Some(arc)
     }
 
@@ -128,8 +190,21 @@ impl DiskCourseCache {
         semester: &str,
         course_id: &str,
     ) -> Option<Arc<CourseDetails>> {
+        self.get_cached_course(year, semester, course_id)
+            .await
+            .map(|cached| cached.details)
+    }
+
+    /// Like `get_course`, along with when the course's file was last written.
+    pub async fn get_cached_course(
+        &self,
+        year: &str,
+        semester: &str,
+        course_id: &str,
+    ) -> Option<CachedCourse> {
+        let snapshot = self.snapshot().await;
         let key = format!("{year}/{semester}/{course_id}");
-        if let Some(cached) = self.courses.get(&key).await {
+        if let Some(cached) = snapshot.courses.get(&key).await {
             return Some(cached);
         }
 
@@ -138,73 +213,203 @@ impl DiskCourseCache {
             .join(year)
             .join(semester)
             .join(format!("{course_id}.json"));
+        let modified = modified_time(&path);
         let data = fs::read_to_string(&path).ok()?;
         let details: CourseDetails = serde_json::from_str(&data).ok()?;
-        let arc = Arc::new(details);
-        self.courses.insert(key, arc.clone()).await;
-        Some(arc)
+        let cached = CachedCourse {
+            details: Arc::new(details),
+            modified,
+        };
+        snapshot.courses.insert(key, cached.clone()).await;
+        Some(cached)
     }
 
-    /// Load all courses from all semesters on disk into memory.
-    pub async fn load_all(&self) {
+    /// Get the semester's begin and end dates, if the fetcher recorded them.
+    pub fn get_semester(&self, year: &str, semester: &str) -> Option<Semester> {
+        let path = self
+            .cache_dir
+            .join(year)
+            .join(semester)
+            .join("_semester.json");
+        let data = fs::read_to_string(&path).ok()?;
+        serde_json::from_str(&data).ok()
+    }
+
+    fn fingerprint(&self, semesters: &[DiskSemester]) -> Fingerprint {
+        fingerprint(&self.cache_dir, semesters)
+    }
+
+    /// Load all courses from all semesters on disk into a new snapshot and swap it in.
+    pub async fn load_all(&self) -> LoadStats {
+        let _reloading = self.reloading.lock().await;
+        self.load_snapshot(self.discover_semesters()).await
+    }
+
+    /// Like `load_all`, but only if a semester was added, removed or changed since the last load.
+    pub async fn reload_if_changed(&self) -> Option<LoadStats> {
+        let _reloading = self.reloading.lock().await;
         let semesters = self.discover_semesters();
-        let mut total = 0usize;
-        // Build the flat course map: iterate oldest-first so newer semesters overwrite
-        let mut flat_courses: HashMap<CourseId, Course> = HashMap::new();
-        for sem in semesters.iter().rev() {
-            let sem_dir = self.cache_dir.join(&sem.year).join(&sem.semester);
-            let Ok(entries) = fs::read_dir(&sem_dir) else {
-                continue;
-            };
-            for entry in entries.flatten() {
-                let name = entry.file_name().to_string_lossy().to_string();
-                if !name.ends_with(".json") || name.starts_with('_') {
-                    continue;
-                }
-                let course_id = name.trim_end_matches(".json");
-                let key = format!("{}/{}/{}", sem.year, sem.semester, course_id);
-                if let Ok(data) = fs::read_to_string(entry.path()) {
-                    if let Ok(details) = serde_json::from_str::<CourseDetails>(&data) {
-                        flat_courses.insert(details.id.clone(), Course::from(&details));
-                        self.courses.insert(key, Arc::new(details)).await;
-                        total += 1;
-                    }
-                }
-            }
-            // Also load the index
-            let _ = self.get_index(&sem.year, &sem.semester).await;
+        if self.fingerprint(&semesters) == self.snapshot().await.fingerprint {
+            return None;
+        }
+        Some(self.load_snapshot(semesters).await)
+    }
+
+    async fn load_snapshot(&self, semesters: Vec<DiskSemester>) -> LoadStats {
+        // Thousands of files to read and parse, and the search index to build
+        let cache_dir = self.cache_dir.clone();
+        let loaded = tokio::task::spawn_blocking(move || read_snapshot(&cache_dir, &semesters))
+            .await
+            .expect("reading the course cache panicked");
+        let stats = loaded.stats.clone();
+        let snapshot = Snapshot {
+            all_courses: loaded.all_courses,
+            search_index: loaded.search_index,
+            fingerprint: loaded.fingerprint,
+            ..Snapshot::empty()
+        };
+        for (key, cached) in loaded.courses {
+            snapshot.courses.insert(key, cached).await;
+        }
+        for (key, index) in loaded.indexes {
+            snapshot.indexes.insert(key, index).await;
         }
-        *self.all_courses.write().await = flat_courses;
+        *self.snapshot.write().await = Arc::new(snapshot);
         log::info!(
             target: "sogrim_server",
             "Loaded {} courses from {} semesters into memory ({} unique courses in flat list)",
-            total,
-            semesters.len(),
-            self.all_courses.read().await.len(),
+            stats.courses,
+            stats.semesters,
+            stats.unique_courses,
         );
+        stats
     }
 
     /// Return all courses from the flat deduplicated list as a map keyed by course ID.
     pub async fn get_all_courses(&self) -> HashMap<CourseId, Course> {
-        self.all_courses.read().await.clone()
+        self.snapshot().await.all_courses.clone()
+    }
+
+    /// Full-text search over all courses, see `CourseSearchIndex::search`.
+    pub async fn search(&self, query: &SearchQuery) -> SearchResults {
+        self.snapshot().await.search_index.search(query)
     }
 
     /// Create a cache pre-populated with the given courses (for tests only).
     #[cfg(test)]
     pub fn with_courses(courses: HashMap<CourseId, Course>) -> Self {
-        let ttl = Duration::from_secs(CACHE_TTL_HOURS * 3600);
+        let snapshot = Snapshot {
+            search_index: CourseSearchIndex::new(courses.values().map(|course| SearchEntry {
+                course: course.clone(),
+                ..Default::default()
+            })),
+            all_courses: courses,
+            ..Snapshot::empty()
+        };
         Self {
             cache_dir: PathBuf::new(),
-            courses: Cache::builder()
-                .time_to_live(ttl)
-                .max_capacity(20_000)
-                .build(),
-            indexes: Cache::builder().time_to_live(ttl).max_capacity(20).build(),
-            all_courses: RwLock::new(courses),
+            snapshot: RwLock::new(Arc::new(snapshot)),
+            reloading: Mutex::new(()),
+        }
+    }
+}
+
+// Falls back to the epoch, which only makes clients revalidate more often than needed.
+fn fingerprint(cache_dir: &Path, semesters: &[DiskSemester]) -> Fingerprint {
+    semesters
+        .iter()
+        .map(|sem| {
+            let sem_dir = cache_dir.join(&sem.year).join(&sem.semester);
+            let modified = fs::metadata(sem_dir).and_then(|m| m.modified()).ok();
+            (format!("{}/{}", sem.year, sem.semester), modified)
+        })
+        .collect()
+}
+
+// What a snapshot is made of, as read from disk.
+struct LoadedSnapshot {
+    courses: Vec<(String, CachedCourse)>,
+    indexes: Vec<(String, Arc<Vec<CourseIndexEntry>>)>,
+    all_courses: HashMap<CourseId, Course>,
+    search_index: CourseSearchIndex,
+    fingerprint: Fingerprint,
+    stats: LoadStats,
+}
+
+// Reads every course and index of the semesters. Blocks on the disk, so it runs off the runtime.
+fn read_snapshot(cache_dir: &Path, semesters: &[DiskSemester]) -> LoadedSnapshot {
+    // Taken before reading, so that files written during the load trigger another reload
+    let fingerprint = fingerprint(cache_dir, semesters);
+    let mut courses = Vec::new();
+    let mut indexes = Vec::new();
+    // Build the flat course map: iterate oldest-first so newer semesters overwrite
+    let mut flat_courses: HashMap<CourseId, Course> = HashMap::new();
+    let mut search_entries: HashMap<CourseId, SearchEntry> = HashMap::new();
+    for sem in semesters.iter().rev() {
+        let sem_dir = cache_dir.join(&sem.year).join(&sem.semester);
+        let Ok(entries) = fs::read_dir(&sem_dir) else {
+            continue;
+        };
+        for entry in entries.flatten() {
+            let name = entry.file_name().to_string_lossy().to_string();
+            if !name.ends_with(".json") || name.starts_with('_') {
+                continue;
+            }
+            let course_id = name.trim_end_matches(".json");
+            let key = format!("{}/{}/{}", sem.year, sem.semester, course_id);
+            let modified = modified_time(&entry.path());
+            if let Ok(data) = fs::read_to_string(entry.path()) {
+                if let Ok(details) = serde_json::from_str::<CourseDetails>(&data) {
+                    flat_courses.insert(details.id.clone(), Course::from(&details));
+                    let mut semesters = search_entries
+                        .remove(&details.id)
+                        .map(|entry| entry.semesters)
+                        .unwrap_or_default();
+                    semesters.push(format!("{}/{}", sem.year, sem.semester));
+                    search_entries.insert(
+                        details.id.clone(),
+                        SearchEntry {
+                            semesters,
+                            ..SearchEntry::from(&details)
+                        },
+                    );
+                    let cached = CachedCourse {
+                        details: Arc::new(details),
+                        modified,
+                    };
+                    courses.push((key, cached));
+                }
+            }
         }
+        // Also load the index
+        if let Some(index) = fs::read_to_string(sem_dir.join("_index.json"))
+            .ok()
+            .and_then(|data| serde_json::from_str::<Vec<CourseIndexEntry>>(&data).ok())
+        {
+            indexes.push((format!("{}/{}", sem.year, sem.semester), Arc::new(index)));
+        }
+    }
+    let stats = LoadStats {
+        semesters: semesters.len(),
+        courses: courses.len(),
+        unique_courses: flat_courses.len(),
+    };
+    LoadedSnapshot {
+        courses,
+        indexes,
+        all_courses: flat_courses,
+        search_index: CourseSearchIndex::new(search_entries.into_values()),
+        fingerprint,
+        stats,
     }
 }
 
+fn modified_time(path: &Path) -> SystemTime {
+    fs::metadata(path)
+        .and_then(|metadata| metadata.modified())
+        .unwrap_or(SystemTime::UNIX_EPOCH)
+}
+
 fn semester_display_name(year: &str, semester: &str) -> String {
     let y: i32 = year.parse().unwrap_or(0);
     match semester {
@@ -215,3 +420,7 @@ fn semester_display_name(year: &str, semester: &str) -> String {
         other => format!("{year}/{other}"),
     }
 }
+
+#[cfg(test)]
+#[path = "disk_cache_tests.rs"]
+pub(crate) mod disk_cache_tests;
@@ -0,0 +1,68 @@
+use super::*;
+
+fn course_json(id: &str, name: &str) -> String {
+    serde_json::json!({
+        "id": id,
+        "name": name,
+        "credits": 3.0,
+        "faculty": null,
+        "syllabus": null,
+        "academic_level": null,
+        "is_english": false,
+        "is_malag": false,
+        "is_sport": false,
+        "semester_note": null,
+        "exams": [],
+        "relations": [],
+        "prerequisites": [],
+        "corequisites": [],
+        "responsible": [],
+        "offered_periods": [],
+        "schedule": [],
+    })
+    .to_string()
+}
+
+/// Write a course file, and an empty index for its semester, as the fetcher would.
+pub(crate) fn write_course(cache_dir: &std::path::Path, semester: &str, id: &str, name: &str) {
+    let sem_dir = cache_dir.join("2024").join(semester);
+    fs::create_dir_all(&sem_dir).unwrap();
+    fs::write(sem_dir.join("_index.json"), "[]").unwrap();
+    fs::write(sem_dir.join(format!("{id}.json")), course_json(id, name)).unwrap();
+}
+
+#[tokio::test]
+async fn reload_swaps_in_new_courses() {
+    let cache_dir = std::env::temp_dir().join(format!("sogrim_disk_cache_{}", std::process::id()));
+    let _ = fs::remove_dir_all(&cache_dir);
+    write_course(&cache_dir, "200", "02340114", "מבוא למדעי המחשב");
+    let cache = DiskCourseCache::new(cache_dir.clone());
+
+    let stats = cache.load_all().await;
+    assert_eq!((stats.semesters, stats.unique_courses), (1, 1));
+    let before = cache.get_course("2024", "200", "02340114").await.unwrap();
+    assert!(cache.reload_if_changed().await.is_none());
+
+    // A newer semester renames the course
+    write_course(&cache_dir, "201", "02340114", "מבוא למדעי המחשב מ'");
+    let stats = cache.reload_if_changed().await.unwrap();
+    assert_eq!((stats.semesters, stats.courses), (2, 2));
+
+    let courses = cache.get_all_courses().await;
+    assert_eq!(
+        courses[&CourseId::new("02340114")].name,
+        "מבוא למדעי המחשב מ'"
+    );
+    let results = cache
+        .search(&SearchQuery {
+            text: "מדעי".to_string(),
+            limit: 10,
+            ..Default::default()
+        })
+        .await;
+    assert_eq!(results.courses[0].name, "מבוא למדעי המחשב מ'");
+    // Whoever held on to the old details keeps them
+    assert_eq!(before.name, "מבוא למדעי המחשב");
+
+    fs::remove_dir_all(&cache_dir).unwrap();
+}
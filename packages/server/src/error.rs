@@ -10,9 +10,11 @@ pub enum AppError {
     Parser(String),            // 400
     Unauthorized(String),      // 401
     NotFound(String),          // 404
+    Conflict(String),          // 409
     InternalServer(String),    // 500
     Middleware(String),        // 500
     MongoDriver(String),       // 500
+    Storage(String),           // 500
     GoogleKeyProvider(String), // 500
 }
 
@@ -22,6 +24,12 @@ impl From<mongodb::error::Error> for AppError {
     }
 }
 
+impl From<redb::Error> for AppError {
+    fn from(err: redb::Error) -> Self {
+        AppError::Storage(err.to_string())
+    }
+}
+
 impl From<bson::error::Error> for AppError {
     fn from(err: bson::error::Error) -> Self {
         AppError::Bson(err.to_string())
@@ -54,9 +62,11 @@ impl std::fmt::Display for AppError {
             AppError::Parser(e) => format!("Parser error: {e}"),
             AppError::Unauthorized(e) => format!("Permission denied: {e}"),
             AppError::NotFound(e) => format!("{e} not found"),
+            AppError::Conflict(e) => e.to_owned(),
             AppError::InternalServer(e) => e.to_owned(),
             AppError::Middleware(e) => format!("Middleware error: {e}"),
             AppError::MongoDriver(e) => format!("MongoDB driver error: {e}"),
+            AppError::Storage(e) => format!("Storage error: {e}"),
             AppError::GoogleKeyProvider(e) => format!("Google key provider error: {e}"),
         };
         write!(f, "{error}")
@@ -73,6 +83,7 @@ impl IntoResponse for AppError {
                 (StatusCode::UNAUTHORIZED, format!("Permission denied: {e}"))
             }
             AppError::NotFound(e) => (StatusCode::NOT_FOUND, format!("{e} not found")),
+            AppError::Conflict(e) => (StatusCode::CONFLICT, e.to_owned()),
             AppError::InternalServer(e) => (StatusCode::INTERNAL_SERVER_ERROR, e.to_owned()),
             AppError::Middleware(e) => (
                 StatusCode::INTERNAL_SERVER_ERROR,
@@ -82,6 +93,10 @@ impl IntoResponse for AppError {
                 StatusCode::INTERNAL_SERVER_ERROR,
                 format!("MongoDB driver error: {e}"),
             ),
+            AppError::Storage(e) => (
+                StatusCode::INTERNAL_SERVER_ERROR,
+                format!("Storage error: {e}"),
+            ),
             AppError::GoogleKeyProvider(e) => (
                 StatusCode::INTERNAL_SERVER_ERROR,
                 format!("Google key provider error: {e}"),
@@ -31,3 +31,23 @@ fn response_error_maps_mongo_driver_to_internal_server() {
         Some("MongoDB driver error: driver failed".to_string())
     );
 }
+
+#[test]
+fn response_error_maps_storage_to_internal_server() {
+    let response = AppError::Storage("disk full".to_string()).into_response();
+    assert_eq!(response.status(), StatusCode::INTERNAL_SERVER_ERROR);
+    assert_eq!(
+        response.extensions().get::<String>().cloned(),
+        Some("Storage error: disk full".to_string())
+    );
+}
+
+#[test]
+fn response_error_maps_conflict() {
+    let response = AppError::Conflict("stale".to_string()).into_response();
+    assert_eq!(response.status(), StatusCode::CONFLICT);
+    assert_eq!(
+        response.extensions().get::<String>().cloned(),
+        Some("stale".to_string())
+    );
+}
@@ -12,7 +12,7 @@ use sogrim_server::config::Config;
 use tower_http::services::{ServeDir, ServeFile};
 
 // Re-export library modules so server-specific submodules can use crate:: paths
-pub use sogrim_server::{consts, core, db, error, resources};
+pub use sogrim_server::{consts, core, db, error, resources, sap};
 
 mod api;
 mod disk_cache;
@@ -26,6 +26,9 @@ use resources::user::Permissions;
 
 const CACHE_DIR_ENV: &str = "SOGRIM_CACHE_DIR";
 const DEFAULT_CACHE_DIR: &str = "/home/opc/cache";
+// How often to check the cache directory for changes written by the fetcher. Unset disables the
+// timer, the owner endpoint can still reload on demand.
+const CACHE_RELOAD_SECS_ENV: &str = "SOGRIM_CACHE_RELOAD_SECS";
 
 #[tokio::main]
 async fn main() -> anyhow::Result<()> {
@@ -43,23 +46,13 @@ async fn main() -> anyhow::Result<()> {
     // Initialize DB client
     let db = Db::connect(&config.uri, &config.profile)
         .await
-        .context("failed to connect to MongoDB")?;
+        .context("failed to connect to the database")?;
     info!(target: "server", "Initialized DB client in {}ms", now.elapsed().as_millis());
 
     // Idempotently create the `last_seen` index on Users. The admin BI dashboard's
     // DAU/WAU/MAU windows filter on this field. `create_index` is a no-op if an
     // equivalent index already exists, so this is safe to run on every startup.
-    match db
-        .client()
-        .database(db.profile())
-        .collection::<bson::Document>("Users")
-        .create_index(
-            mongodb::IndexModel::builder()
-                .keys(bson::doc! { "last_seen": 1 })
-                .build(),
-        )
-        .await
-    {
+    match db.create_index("Users", "last_seen").await {
         Ok(_) => info!(target: "server", "Ensured last_seen index on Users"),
         Err(e) => log::warn!(target: "server", "Failed to create last_seen index on Users: {e}"),
     }
@@ -77,9 +70,29 @@ async fn main() -> anyhow::Result<()> {
     course_cache.load_all().await;
     info!(target: "server", "Loaded disk cache in {}ms", cache_start.elapsed().as_millis());
 
+    // Periodically pick up whatever the fetcher wrote since
+    if let Some(reload_secs) = std::env::var(CACHE_RELOAD_SECS_ENV)
+        .ok()
+        .and_then(|secs| secs.parse::<u64>().ok())
+        .filter(|secs| *secs > 0)
+    {
+        let course_cache = course_cache.clone();
+        tokio::spawn(async move {
+            let mut interval = tokio::time::interval(std::time::Duration::from_secs(reload_secs));
+            // The first tick completes immediately, right after the startup load
+            interval.tick().await;
+            loop {
+                interval.tick().await;
+                course_cache.reload_if_changed().await;
+            }
+        });
+        info!(target: "server", "Checking the disk cache for changes every {reload_secs}s");
+    }
+
     // Public routes (no auth required)
     let public_routes = Router::new()
         .route("/semesters", get(api::courses::get_semesters))
+        .route("/courses/batch", get(api::courses::get_courses_batch))
         .route(
             "/courses/{year}/{semester}/index",
             get(api::courses::get_course_index),
@@ -94,13 +107,53 @@ async fn main() -> anyhow::Result<()> {
         .route("/catalogs", get(api::students::get_catalogs))
         .route("/login", get(api::students::login))
         .route("/catalog", put(api::students::update_catalog))
+        .route(
+            "/secondary-catalogs",
+            put(api::students::update_secondary_catalogs),
+        )
         .route("/courses", get(api::students::get_courses_by_filter))
         .route("/courses", post(api::students::add_courses))
+        .route("/courses/pdf", post(api::students::add_courses_from_pdf))
+        .route("/courses/preview", post(api::students::preview_courses))
+        .route(
+            "/courses/eligible",
+            get(api::students::get_eligible_courses),
+        )
         .route("/degree-status", get(api::students::compute_degree_status))
+        .route(
+            "/degree-status/simulate",
+            post(api::students::simulate_degree_status),
+        )
+        .route("/degree-status/plan", get(api::students::get_degree_plan))
+        .route(
+            "/degree-status/timeline",
+            get(api::students::get_degree_timeline),
+        )
         .route("/details", put(api::students::update_details))
         .route("/settings", put(api::students::update_settings))
         .route("/timetable", get(api::students::get_timetable))
         .route("/timetable", put(api::students::update_timetable))
+        .route(
+            "/timetable/check",
+            post(api::students::check_timetable_draft),
+        )
+        .route(
+            "/timetable/generate",
+            post(api::students::generate_timetables),
+        )
+        .route(
+            "/timetable/drafts/{id}/ics",
+            get(api::students::export_timetable_ics),
+        )
+        .route("/notifications", get(api::students::get_notifications))
+        .route(
+            "/notifications/read",
+            put(api::students::acknowledge_all_notifications),
+        )
+        .route(
+            "/notifications/{id}/read",
+            put(api::students::acknowledge_notification),
+        )
         .layer(Extension(Permissions::Student));
 
     // Admin routes
@@ -115,11 +168,16 @@ async fn main() -> anyhow::Result<()> {
     // Owner routes
     let owner_routes = Router::new()
         .route("/courses", get(api::owners::get_all_courses))
+        .route("/courses/reload", post(api::owners::reload_course_cache))
         .route("/courses/{id}", get(api::owners::get_course_by_id))
         .route("/courses/{id}", put(api::owners::create_or_update_course))
         .route("/courses/{id}", delete(api::owners::delete_course))
         .route("/catalogs/{id}", get(api::owners::get_catalog_by_id))
         .route("/catalogs/{id}", put(api::owners::create_or_update_catalog))
+        .route(
+            "/catalogs/{id}/diff/{other_id}",
+            get(api::owners::diff_catalogs),
+        )
         .layer(Extension(Permissions::Owner));
 
     // Auth-protected routes
@@ -22,15 +22,6 @@ use super::key_provider::RsaKey;
 
 const TEST_CLIENT_ID: &str = "test-client-id-for-jwt-validation";
 
-fn test_uri() -> String {
-    let _ = dotenvy::dotenv();
-    std::env::var("SOGRIM_URI").expect("SOGRIM_URI must be set for tests")
-}
-
-fn test_profile() -> String {
-    std::env::var("SOGRIM_PROFILE").unwrap_or_else(|_| "debug".into())
-}
-
 /// Generate a fake RSA keypair for testing.
 /// Well.. it's not really fake, it's just randomly generated and not stored anywhere.
 /// Also, the key id is always "test".
@@ -132,9 +123,7 @@ async fn test_from_request_no_db_client() {
 
 #[tokio::test]
 async fn test_from_request_no_auth_mw() {
-    let db = Db::connect(&test_uri(), &test_profile())
-        .await
-        .expect("Failed to connect to MongoDB");
+    let db = Db::from_test_env().await;
     let app = Router::new()
         .route("/", get(|_: User| async { "Shouldn't get here" }))
         .layer(Extension(Permissions::Student))
@@ -163,9 +152,7 @@ async fn test_from_request_no_auth_mw() {
 
 #[tokio::test]
 async fn test_auth_mw_no_jwt_decoder() {
-    let db = Db::connect(&test_uri(), &test_profile())
-        .await
-        .expect("Failed to connect to MongoDB");
+    let db = Db::from_test_env().await;
     let app = Router::new()
         .route("/", get(|| async { "Shouldn't get here" }))
         .layer(axum::middleware::from_fn(middleware::auth::authenticate))
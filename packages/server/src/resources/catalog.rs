@@ -40,6 +40,10 @@ pub struct Catalog {
     pub course_to_bank: HashMap<CourseId, String>,
     pub catalog_replacements: HashMap<CourseId, OptionalReplacements>, // All replacements which are mentioned in the catalog
     pub common_replacements: HashMap<CourseId, OptionalReplacements>, // Common replacement which usually approved by the coordinators
+    /// Courses `enrich_with_prefix_courses` put in the default accumulate bank for want of a
+    /// catalog bank. Wildcard banks may still take them.
+    #[serde(skip)]
+    pub prefix_courses: HashSet<CourseId>,
 }
 
 impl Faculty {
@@ -141,7 +145,12 @@ impl Catalog {
     /// Enrich this catalog with courses from sibling catalogs (same track, different years).
     /// Only courses from AccumulateCredit and AccumulateCourses banks are merged.
     /// The current catalog always takes priority — existing course_to_bank entries are never overridden.
-    pub fn enrich_with_sibling_courses(&mut self, siblings: &[Catalog]) {
+    /// Returns the merged courses along with the name of the sibling each came from.
+    pub fn enrich_with_sibling_courses(
+        &mut self,
+        siblings: &[Catalog],
+    ) -> HashMap<CourseId, String> {
+        let mut merged = HashMap::new();
         let accumulate_bank_names: HashSet<&str> = self
             .course_banks
             .iter()
@@ -177,21 +186,27 @@ impl Catalog {
                 }
                 self.course_to_bank
                     .insert(course_id.clone(), sibling_bank_name.clone());
+                merged.insert(course_id.clone(), sibling.name.clone());
             }
         }
+        merged
     }
 
     /// Enrich this catalog with student courses that match the faculty's course prefixes
     /// but are not already in `course_to_bank`. These courses are assigned to the
-    /// accumulate bank with the most courses (the "default" accumulate bank).
-    pub fn enrich_with_prefix_courses(&mut self, student_courses: &[CourseStatus]) {
+    /// accumulate bank with the most courses (the "default" accumulate bank). Returns the added courses.
+    pub fn enrich_with_prefix_courses(
+        &mut self,
+        student_courses: &[CourseStatus],
+    ) -> Vec<CourseId> {
+        let mut added = Vec::new();
         let prefixes = self.course_prefixes();
         if prefixes.is_empty() {
-            return;
+            return added;
         }
         let prefixes: Vec<String> = prefixes.into_iter().map(String::from).collect();
         let Some(default_bank) = self.default_accumulate_bank() else {
-            return;
+            return added;
         };
 
         for course_status in student_courses {
@@ -205,8 +220,11 @@ impl Catalog {
             {
                 self.course_to_bank
                     .insert(course_id.clone(), default_bank.clone());
+                self.prefix_courses.insert(course_id.clone());
+                added.push(course_id.clone());
             }
         }
+        added
     }
 }
 
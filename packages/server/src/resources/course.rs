@@ -11,9 +11,9 @@ use std::iter::FromIterator;
 use std::ops::Deref;
 use std::str::FromStr;
 
-use crate::core::types::Rule;
+use crate::core::types::{BankConstraints, Rule};
 use crate::db::Resource;
-use crate::sap::CourseDetails;
+use crate::sap::{CourseDetails, Relation, RelationType};
 
 const NON_STANDARD_PREFIXES: [&str; 4] = ["51", "52", "61", "97"];
 
@@ -39,6 +39,15 @@ impl SemesterSeason {
             SemesterSeason::Summer => 2,
         }
     }
+
+    /// The semester code SAP (and the disk cache) uses for this season.
+    pub fn sap_code(self) -> &'static str {
+        match self {
+            SemesterSeason::Winter => "200",
+            SemesterSeason::Spring => "201",
+            SemesterSeason::Summer => "202",
+        }
+    }
 }
 
 impl FromStr for SemesterSeason {
@@ -306,6 +315,9 @@ pub struct Course {
     pub name: String,
     #[serde(skip_serializing_if = "Option::is_none")]
     pub tags: Option<Vec<Tag>>, // All tags for the course, for example "english" and "malag"
+    // Overlaps with other courses as SAP reports them, used to avoid counting the same material twice
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub relations: Vec<Relation>,
 }
 
 impl Resource for Course {
@@ -327,6 +339,23 @@ pub enum Tag {
     MedicineClinical,
 }
 
+impl FromStr for Tag {
+    type Err = ();
+
+    // Accepts the serialized names as well as snake_case, e.g. "SportTeam" and "sport_team"
+    fn from_str(value: &str) -> Result<Self, Self::Err> {
+        match value.replace('_', "").to_lowercase().as_str() {
+            "english" => Ok(Tag::English),
+            "malag" => Ok(Tag::Malag),
+            "sport" => Ok(Tag::Sport),
+            "sportteam" => Ok(Tag::SportTeam),
+            "medicinepreclinical" => Ok(Tag::MedicinePreclinical),
+            "medicineclinical" => Ok(Tag::MedicineClinical),
+            _ => Err(()),
+        }
+    }
+}
+
 impl From<&CourseDetails> for Course {
     fn from(details: &CourseDetails) -> Self {
         let mut tags = Vec::new();
@@ -344,6 +373,7 @@ impl From<&CourseDetails> for Course {
             credit: details.credits,
             name: details.name.clone(),
             tags: if tags.is_empty() { None } else { Some(tags) },
+            relations: details.relations.clone(),
         }
     }
 }
@@ -383,6 +413,30 @@ impl Course {
                 .iter()
                 .any(|&prefix| self.id.starts_with(prefix))
     }
+
+    fn has_relation(&self, course_id: &CourseId, relation_type: RelationType) -> bool {
+        self.relations.iter().any(|relation| {
+            relation.course_id == *course_id && relation.relation_type == relation_type
+        })
+    }
+
+    /// True if this course covers all of `other`'s material. SAP doesn't always list a relation on
+    /// both courses, so both sides are checked.
+    pub fn contains(&self, other: &Course) -> bool {
+        self.has_relation(&other.id, RelationType::Contains)
+            || other.has_relation(&self.id, RelationType::ContainedIn)
+    }
+
+    /// True if the two courses overlap, so that taking both grants no additional credit.
+    pub fn overlaps(&self, other: &Course) -> bool {
+        self.relations
+            .iter()
+            .any(|relation| relation.course_id == other.id)
+            || other
+                .relations
+                .iter()
+                .any(|relation| relation.course_id == self.id)
+    }
 }
 
 #[derive(Clone, Debug, PartialEq, Eq)]
@@ -455,6 +509,9 @@ pub struct CourseStatus {
     pub times_repeated: usize,
     #[serde(default)]
     pub is_repetition: bool,
+    // Overlaps a course the student already got credit for, see `extract_overlapping_courses`
+    #[serde(default)]
+    pub no_additional_credit: bool,
 }
 
 impl CourseStatus {
@@ -524,11 +581,13 @@ impl CourseStatus {
     }
 }
 
-#[derive(Clone, Debug, Deserialize, Serialize)]
+#[derive(Clone, Debug, PartialEq, Deserialize, Serialize)]
 pub struct CourseBank {
     pub name: String, // for example, Hova, Reshima A.
     pub rule: Rule,
     pub credit: Option<f32>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub constraints: Option<BankConstraints>,
 }
 
 impl CourseBank {
@@ -568,6 +627,11 @@ impl CourseBank {
             }
             _ => {}
         }
+        if let Some(constraints) = &mut self.constraints {
+            constraints.min_courses.iter_mut().for_each(|constraint| {
+                replace_occurrences(&mut constraint.course_list, &course, &replacement)
+            });
+        }
     }
 }
 
@@ -2,7 +2,10 @@ use serde_json::json;
 
 use super::catalog::{Catalog, Faculty};
 use super::course::*;
+use super::user::{User, UserField};
 use crate::core::types::Rule;
+use crate::db::Db;
+use crate::error::AppError;
 
 #[tokio::test]
 async fn test_course_state_serde() {
@@ -92,6 +95,7 @@ fn make_catalog(name: &str, banks: Vec<(&str, Rule)>, courses: Vec<(&str, &str)>
                 name: bank_name.to_string(),
                 rule,
                 credit: Some(10.0),
+                constraints: None,
             })
             .collect(),
         course_to_bank: courses
@@ -308,6 +312,7 @@ fn make_course_status(course_id: &str, credit: f32) -> CourseStatus {
             credit,
             name: format!("Course {course_id}"),
             tags: None,
+            relations: Vec::new(),
         },
         state: Some(CourseState::Complete),
         grade: Some(Grade::Numeric(85)),
@@ -501,6 +506,7 @@ fn course_with(id: &str, tags: Option<Vec<Tag>>) -> Course {
         credit: 2.0,
         name: id.to_string(),
         tags,
+        relations: Vec::new(),
     }
 }
 
@@ -530,7 +536,47 @@ fn test_is_repeatable() {
         credit: 1.0,
         name: "פעילות חברתית".to_string(),
         tags: None,
+        relations: Vec::new(),
     };
     assert!(social.is_social());
     assert!(social.is_repeatable());
 }
+
+#[tokio::test]
+async fn test_user_save_is_field_scoped() {
+    let db = Db::in_memory("test").unwrap();
+    let mut user = db
+        .create_or_update(User {
+            sub: "two-tabs".to_string(),
+            ..Default::default()
+        })
+        .await
+        .unwrap();
+    let mut other_tab = user.clone();
+
+    // Saving the timetable keeps a concurrent change to the details
+    user.details.modified = true;
+    user.save(&db, &[UserField::Details], Some(0))
+        .await
+        .unwrap();
+    other_tab.timetable.active_draft_id = Some("draft".to_string());
+    other_tab
+        .save(&db, &[UserField::Timetable], None)
+        .await
+        .unwrap();
+    assert_eq!(other_tab.revision, 2);
+    let stored = db.get::<User>("two-tabs").await.unwrap();
+    assert!(stored.details.modified);
+    assert_eq!(stored.timetable.active_draft_id.as_deref(), Some("draft"));
+
+    // A stale writer is refused
+    user.details.modified = false;
+    assert!(matches!(
+        user.save(&db, &[UserField::Details], Some(1)).await,
+        Err(AppError::Conflict(_))
+    ));
+
+    // Bookkeeping doesn't bump the revision
+    user.save(&db, &[UserField::LastSeen], None).await.unwrap();
+    assert_eq!(user.revision, 2);
+}
@@ -1,9 +1,14 @@
 use super::catalog::DisplayCatalog;
 use crate::{
-    core::degree_status::DegreeStatus,
+    core::{
+        degree_status::{secondary::SharedCourse, DegreeStatus},
+        parser_v2::ParseReport,
+        timetable::notifications::MAX_NOTIFICATIONS,
+    },
     db::{Db, Resource},
     error::AppError,
     resources::course::AcademicSemester,
+    sap::changes::CourseChange,
 };
 
 /// Google JWT subject identifier (unique user ID).
@@ -25,6 +30,34 @@ pub struct UserDetails {
     /// String keys keep BSON happy and round-trip cleanly through JSON.
     #[serde(default)]
     pub timeline_annotations: std::collections::HashMap<String, String>,
+    /// What the parser dropped or doubted when the courses were last imported.
+    #[serde(default)]
+    pub parse_report: Option<ParseReport>,
+    /// Minors and second degrees, evaluated against the same transcript as `catalog`.
+    #[serde(default)]
+    pub secondary_catalogs: Vec<SecondaryCatalog>,
+}
+
+#[derive(Default, Clone, Copy, Debug, Deserialize, Serialize, PartialEq)]
+#[serde(rename_all = "snake_case")]
+pub enum SecondaryRole {
+    #[default]
+    Minor,
+    DoubleMajor,
+}
+
+#[derive(Clone, Debug, Deserialize, Serialize)]
+pub struct SecondaryCatalog {
+    pub catalog: DisplayCatalog,
+    pub role: SecondaryRole,
+    /// How much credit counted by the primary catalog may count here too. None means none of it.
+    #[serde(default)]
+    pub max_shared_credit: Option<f32>,
+    /// Computed along with the primary degree status.
+    #[serde(default)]
+    pub degree_status: DegreeStatus,
+    #[serde(default)]
+    pub shared_courses: Vec<SharedCourse>,
 }
 
 #[derive(Default, Clone, Debug, Deserialize, Serialize)]
@@ -83,6 +116,21 @@ pub struct CustomEvent {
     pub color: Option<String>,
 }
 
+/// A change to a course on one of the student's published timetables, found by the fetcher.
+#[derive(Clone, Debug, Deserialize, Serialize)]
+pub struct Notification {
+    /// "{year}-{semester}-{course_id}@{fetched_at}", so that delivering a changelog twice is harmless.
+    pub id: String,
+    /// When the fetcher found the change (RFC 3339).
+    pub created_at: String,
+    pub semester: AcademicSemester,
+    pub draft_id: String,
+    /// Only the parts of the change that affect the timetable.
+    pub change: CourseChange,
+    #[serde(default)]
+    pub read: bool,
+}
+
 #[derive(Default, Clone, Copy, Debug, Deserialize, Serialize, PartialEq, PartialOrd)]
 pub enum Permissions {
     #[default]
@@ -100,8 +148,40 @@ pub struct User {
     pub settings: UserSettings,
     #[serde(default)]
     pub timetable: TimetableState,
+    /// Newest first, see `core::timetable::notifications`.
+    #[serde(default)]
+    pub notifications: Vec<Notification>,
     #[serde(skip_serializing_if = "Option::is_none")]
     pub last_seen: Option<DateTime>,
+    /// Bumped by every save of the details, settings or timetable, see `User::save`.
+    #[serde(default)]
+    pub revision: u64,
+}
+
+/// The parts of a user which are saved separately, so that saving one never overwrites a
+/// concurrent change to another.
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub enum UserField {
+    Details,
+    Settings,
+    Timetable,
+    LastSeen,
+}
+
+impl UserField {
+    fn name(self) -> &'static str {
+        match self {
+            UserField::Details => "details",
+            UserField::Settings => "settings",
+            UserField::Timetable => "timetable",
+            UserField::LastSeen => "last_seen",
+        }
+    }
+
+    // Bookkeeping fields don't bump the revision, or clients would conflict with themselves
+    fn is_revised(self) -> bool {
+        self != UserField::LastSeen
+    }
 }
 
 impl User {
@@ -143,6 +223,66 @@ impl User {
         }
         changed
     }
+
+    /// Saves the given fields alone. With `revision`, only if nobody saved the user since that
+    /// revision, failing with `AppError::Conflict` otherwise. On success `self.revision` is the
+    /// stored one.
+    pub async fn save(
+        &mut self,
+        db: &Db,
+        fields: &[UserField],
+        revision: Option<u64>,
+    ) -> Result<(), AppError> {
+        let mut document = Document::new();
+        for field in fields {
+            let value = match field {
+                UserField::Details => bson::serialize_to_bson(&self.details)?,
+                UserField::Settings => bson::serialize_to_bson(&self.settings)?,
+                UserField::Timetable => bson::serialize_to_bson(&self.timetable)?,
+                UserField::LastSeen => bson::serialize_to_bson(&self.last_seen)?,
+            };
+            document.insert(field.name(), value);
+        }
+        let bump_revision = fields.iter().any(|field| field.is_revised());
+        let stored = db
+            .update_fields::<User>(&self.sub, revision, document, bump_revision)
+            .await?;
+        self.revision = stored.revision;
+        Ok(())
+    }
+
+    /// Adds notifications to the stored inbox, which keeps the newest `MAX_NOTIFICATIONS`. Unlike
+    /// saving the whole inbox, this can't undo a notification being read meanwhile.
+    pub async fn push_notifications(
+        &self,
+        db: &Db,
+        notifications: Vec<Notification>,
+    ) -> Result<(), AppError> {
+        let update = doc! {"$push": {"notifications": {
+            "$each": bson::serialize_to_bson(&notifications)?,
+            "$sort": {"created_at": -1},
+            "$slice": MAX_NOTIFICATIONS as i64,
+        }}};
+        db.update_matching::<User>(&self.sub, Document::new(), update)
+            .await?
+            .ok_or_else(|| AppError::NotFound(format!("User: {}", self.sub)))?;
+        Ok(())
+    }
+
+    /// Marks the stored notification with `id` read, or all of them without it. Returns whether
+    /// there was an unread one to mark.
+    pub async fn mark_read(&self, db: &Db, id: Option<&str>) -> Result<bool, AppError> {
+        let (filter, path) = match id {
+            Some(id) => (doc! {"notifications.id": id}, "notifications.$.read"),
+            // Matching an unread one also makes sure the array exists, which `$[]` requires
+            None => (doc! {"notifications.read": false}, "notifications.$[].read"),
+        };
+        let update = doc! {"$set": {path: true}};
+        let updated = db
+            .update_matching::<User>(&self.sub, filter, update)
+            .await?;
+        Ok(updated.is_some())
+    }
 }
 
 impl Resource for User {
@@ -0,0 +1,236 @@
+//! What changed in the course data between two fetches.
+//!
+//! The incremental fetcher compares every course it fetched with the copy on disk, and writes the
+//! differences of each run to `{cache_dir}/_changes/{timestamp}.json`.
+
+use std::collections::{BTreeMap, BTreeSet, HashSet};
+
+use serde::{Deserialize, Serialize};
+
+use crate::resources::course::CourseId;
+
+use super::{CourseDetails, Exam, ScheduleEvent, ScheduleGroup};
+
+/// Directory under the cache directory which holds the changelogs.
+pub const CHANGES_DIR: &str = "_changes";
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(tag = "field", rename_all = "snake_case")]
+pub enum FieldChange {
+    Name {
+        from: String,
+        to: String,
+    },
+    Credits {
+        from: f32,
+        to: f32,
+    },
+    Exams {
+        added: Vec<Exam>,
+        removed: Vec<Exam>,
+    },
+    Groups {
+        added: Vec<String>,
+        removed: Vec<String>,
+    },
+    // The group's meetings, e.g. "הרצאה ב' 10:30-12:30"
+    Times {
+        group: String,
+        from: Vec<String>,
+        to: Vec<String>,
+    },
+    // The group's rooms, e.g. "טאוב 2"
+    Rooms {
+        group: String,
+        from: Vec<String>,
+        to: Vec<String>,
+    },
+    Lecturers {
+        group: String,
+        from: Vec<String>,
+        to: Vec<String>,
+    },
+    // Anything else, e.g. the syllabus or the prerequisites
+    Other,
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(tag = "type", rename_all = "snake_case")]
+pub enum CourseChange {
+    Added {
+        course_id: CourseId,
+        name: String,
+    },
+    Removed {
+        course_id: CourseId,
+        name: String,
+    },
+    Changed {
+        course_id: CourseId,
+        name: String,
+        changes: Vec<FieldChange>,
+    },
+}
+
+impl CourseChange {
+    pub fn course_id(&self) -> &CourseId {
+        match self {
+            CourseChange::Added { course_id, .. }
+            | CourseChange::Removed { course_id, .. }
+            | CourseChange::Changed { course_id, .. } => course_id,
+        }
+    }
+
+    /// The changes between two versions of a course, or None if they're the same.
+    pub fn between(old: &CourseDetails, new: &CourseDetails) -> Option<CourseChange> {
+        if serde_json::to_value(old).ok() == serde_json::to_value(new).ok() {
+            return None;
+        }
+
+        let mut changes = Vec::new();
+        if old.name != new.name {
+            changes.push(FieldChange::Name {
+                from: old.name.clone(),
+                to: new.name.clone(),
+            });
+        }
+        if old.credits != new.credits {
+            changes.push(FieldChange::Credits {
+                from: old.credits,
+                to: new.credits,
+            });
+        }
+
+        let old_exams = old.exams.iter().collect::<HashSet<_>>();
+        let new_exams = new.exams.iter().collect::<HashSet<_>>();
+        if old_exams != new_exams {
+            changes.push(FieldChange::Exams {
+                added: new
+                    .exams
+                    .iter()
+                    .filter(|exam| !old_exams.contains(exam))
+                    .cloned()
+                    .collect(),
+                removed: old
+                    .exams
+                    .iter()
+                    .filter(|exam| !new_exams.contains(exam))
+                    .cloned()
+                    .collect(),
+            });
+        }
+
+        let old_groups = groups(&old.schedule);
+        let new_groups = groups(&new.schedule);
+        let added = new_groups
+            .keys()
+            .filter(|group| !old_groups.contains_key(*group))
+            .map(|group| group.to_string())
+            .collect::<Vec<_>>();
+        let removed = old_groups
+            .keys()
+            .filter(|group| !new_groups.contains_key(*group))
+            .map(|group| group.to_string())
+            .collect::<Vec<_>>();
+        if !added.is_empty() || !removed.is_empty() {
+            changes.push(FieldChange::Groups { added, removed });
+        }
+        for (group, old_events) in &old_groups {
+            let Some(new_events) = new_groups.get(group) else {
+                continue;
+            };
+            let group = group.to_string();
+            if let Some((from, to)) = differs(old_events, new_events, meeting) {
+                changes.push(FieldChange::Times {
+                    group: group.clone(),
+                    from,
+                    to,
+                });
+            }
+            if let Some((from, to)) = differs(old_events, new_events, room) {
+                changes.push(FieldChange::Rooms {
+                    group: group.clone(),
+                    from,
+                    to,
+                });
+            }
+            if let Some((from, to)) =
+                differs(old_events, new_events, |event| event.lecturer.clone())
+            {
+                changes.push(FieldChange::Lecturers { group, from, to });
+            }
+        }
+
+        if changes.is_empty() {
+            changes.push(FieldChange::Other);
+        }
+        Some(CourseChange::Changed {
+            course_id: new.id.clone(),
+            name: new.name.clone(),
+            changes,
+        })
+    }
+}
+
+fn groups(schedule: &[ScheduleGroup]) -> BTreeMap<&str, &[ScheduleEvent]> {
+    schedule
+        .iter()
+        .map(|group| (group.group.as_str(), group.events.as_slice()))
+        .collect()
+}
+
+// The distinct values of an event attribute in each version, if they differ.
+fn differs(
+    old: &[ScheduleEvent],
+    new: &[ScheduleEvent],
+    attribute: impl Fn(&ScheduleEvent) -> Option<String>,
+) -> Option<(Vec<String>, Vec<String>)> {
+    let old = old.iter().filter_map(&attribute).collect::<BTreeSet<_>>();
+    let new = new.iter().filter_map(&attribute).collect::<BTreeSet<_>>();
+    (old != new).then(|| (old.into_iter().collect(), new.into_iter().collect()))
+}
+
+const DAYS: [&str; 6] = ["א'", "ב'", "ג'", "ד'", "ה'", "ו'"];
+
+fn meeting(event: &ScheduleEvent) -> Option<String> {
+    let day = event.day.and_then(|day| DAYS.get(day as usize))?;
+    let (Some(start), Some(end)) = (&event.start_time, &event.end_time) else {
+        return Some(format!("{} {day}", event.kind));
+    };
+    Some(format!("{} {day} {start}-{end}", event.kind))
+}
+
+fn room(event: &ScheduleEvent) -> Option<String> {
+    match (&event.building, &event.room) {
+        (Some(building), Some(room)) => Some(format!("{building} {room}")),
+        (building, room) => building.clone().or_else(|| room.clone()),
+    }
+}
+
+/// The changes found in one semester.
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct SemesterChanges {
+    pub year: String,
+    pub semester: String,
+    pub changes: Vec<CourseChange>,
+}
+
+/// The changes found by one run of the fetcher.
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct Changelog {
+    // RFC 3339
+    pub fetched_at: String,
+    pub semesters: Vec<SemesterChanges>,
+}
+
+impl Changelog {
+    pub fn is_empty(&self) -> bool {
+        self.semesters
+            .iter()
+            .all(|semester| semester.changes.is_empty())
+    }
+}
+
+#[cfg(test)]
+#[path = "changes_tests.rs"]
+mod changes_tests;
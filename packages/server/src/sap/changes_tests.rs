@@ -0,0 +1,124 @@
+use super::*;
+
+fn event(kind: &str, day: u8, start: &str, room: &str, lecturer: Option<&str>) -> ScheduleEvent {
+    ScheduleEvent {
+        kind: kind.to_string(),
+        day: Some(day),
+        start_time: Some(start.to_string()),
+        end_time: Some("12:30".to_string()),
+        schedule_text: String::new(),
+        building: Some("טאוב".to_string()),
+        room: Some(room.to_string()),
+        lecturer: lecturer.map(str::to_string),
+    }
+}
+
+fn exam(code: &str, date: &str) -> Exam {
+    Exam {
+        category: code.to_string(),
+        category_code: code.to_string(),
+        date: Some(date.to_string()),
+        begin_time: Some("09:00".to_string()),
+        end_time: Some("12:00".to_string()),
+        note: None,
+    }
+}
+
+fn course() -> CourseDetails {
+    CourseDetails {
+        id: CourseId::new("02340114"),
+        name: "מבוא למדעי המחשב מ'".to_string(),
+        credits: 4.0,
+        faculty: None,
+        syllabus: None,
+        academic_level: None,
+        is_english: false,
+        is_malag: false,
+        is_sport: false,
+        semester_note: None,
+        exams: vec![exam("FI", "10-02-2026"), exam("FB", "15-03-2026")],
+        relations: Vec::new(),
+        prerequisites: Vec::new(),
+        corequisites: Vec::new(),
+        responsible: Vec::new(),
+        offered_periods: Vec::new(),
+        schedule: vec![
+            ScheduleGroup {
+                group: "10".to_string(),
+                name: None,
+                events: vec![event("הרצאה", 1, "10:30", "2", Some("פרופ' יוסי כהן"))],
+            },
+            ScheduleGroup {
+                group: "11".to_string(),
+                name: None,
+                events: vec![event("תרגול", 3, "10:30", "5", None)],
+            },
+        ],
+    }
+}
+
+#[test]
+fn same_course_has_no_changes() {
+    assert_eq!(CourseChange::between(&course(), &course()), None);
+}
+
+#[test]
+fn describes_what_changed() {
+    let old = course();
+    let mut new = course();
+    new.credits = 3.5;
+    new.exams[1] = exam("FB", "20-03-2026");
+    new.schedule[0].events[0] = event("הרצאה", 2, "10:30", "6", Some("ד\"ר דנה לוי"));
+    new.schedule[1].group = "12".to_string();
+
+    let Some(CourseChange::Changed { changes, .. }) = CourseChange::between(&old, &new) else {
+        panic!("expected the course to change");
+    };
+    assert_eq!(
+        changes,
+        vec![
+            FieldChange::Credits { from: 4.0, to: 3.5 },
+            FieldChange::Exams {
+                added: vec![exam("FB", "20-03-2026")],
+                removed: vec![exam("FB", "15-03-2026")],
+            },
+            FieldChange::Groups {
+                added: vec!["12".to_string()],
+                removed: vec!["11".to_string()],
+            },
+            FieldChange::Times {
+                group: "10".to_string(),
+                from: vec!["הרצאה ב' 10:30-12:30".to_string()],
+                to: vec!["הרצאה ג' 10:30-12:30".to_string()],
+            },
+            FieldChange::Rooms {
+                group: "10".to_string(),
+                from: vec!["טאוב 2".to_string()],
+                to: vec!["טאוב 6".to_string()],
+            },
+            FieldChange::Lecturers {
+                group: "10".to_string(),
+                from: vec!["פרופ' יוסי כהן".to_string()],
+                to: vec!["ד\"ר דנה לוי".to_string()],
+            },
+        ]
+    );
+}
+
+#[test]
+fn untracked_fields_are_other_changes() {
+    let mut new = course();
+    new.syllabus = Some("תכנות בשפת C".to_string());
+    // Exams are compared as a set
+    new.exams.reverse();
+    let change = CourseChange::between(&course(), &new).unwrap();
+    assert_eq!(
+        serde_json::to_value(&change).unwrap(),
+        serde_json::json!({
+            "type": "changed",
+            "course_id": "02340114",
+            "name": "מבוא למדעי המחשב מ'",
+            "changes": [{"field": "other"}],
+        })
+    );
+}
@@ -0,0 +1,178 @@
+//! A local stand-in for SAP's OData `$batch` endpoint, which replays canned responses.
+//!
+//! A fixture maps every GET query, exactly as the client packs it into a batch, to the JSON SAP
+//! answers it with. Queries missing from the fixture are answered the way SAP answers unknown
+//! entities, with a 404 part. `MockSap::record` forwards to SAP instead, and keeps its answers, to
+//! see what SAP really returns for a query.
+
+use std::collections::BTreeMap;
+use std::sync::{Arc, Mutex};
+
+use axum::{extract::State, http::StatusCode, routing::post, Router};
+use serde_json::{json, Value};
+use tokio::task::JoinHandle;
+
+use super::{CachedSapClient, SapError, SAP_BASE_URL};
+
+/// The fixture the offline tests replay, relative to the crate root. It is written by hand in the
+/// shape of SAP's answers, and its courses, lecturers and buildings are made up, so edit it rather
+/// than replacing it with a recording.
+pub(crate) const FIXTURE_PATH: &str = "../docs/sap_fixture.json";
+
+const RESPONSE_BOUNDARY: &str = "ejjeeffe0";
+
+enum Mode {
+    Replay(BTreeMap<String, Value>),
+    Record(Box<CachedSapClient>),
+}
+
+struct MockState {
+    mode: Mode,
+    // Every query answered so far, and the answer
+    served: Mutex<BTreeMap<String, Value>>,
+}
+
+pub(crate) struct MockSap {
+    url: String,
+    state: Arc<MockState>,
+    server: JoinHandle<()>,
+}
+
+impl MockSap {
+    /// Serves the fixture at `FIXTURE_PATH`.
+    pub(crate) async fn start() -> Self {
+        let data = std::fs::read_to_string(FIXTURE_PATH).expect("failed to read the SAP fixture");
+        let fixture = serde_json::from_str(&data).expect("failed to parse the SAP fixture");
+        Self::replay(fixture).await
+    }
+
+    pub(crate) async fn replay(fixture: BTreeMap<String, Value>) -> Self {
+        Self::serve(Mode::Replay(fixture)).await
+    }
+
+    /// Forwards every batch to SAP, see `recorded`.
+    pub(crate) async fn record() -> Self {
+        Self::serve(Mode::Record(Box::default())).await
+    }
+
+    async fn serve(mode: Mode) -> Self {
+        let state = Arc::new(MockState {
+            mode,
+            served: Mutex::new(BTreeMap::new()),
+        });
+        let listener = tokio::net::TcpListener::bind("127.0.0.1:0")
+            .await
+            .expect("failed to bind the SAP stand-in");
+        let url = format!("http://{}", listener.local_addr().unwrap());
+        let app = Router::new()
+            .route("/$batch", post(batch))
+            .with_state(state.clone());
+        let server = tokio::spawn(async move {
+            axum::serve(listener, app).await.unwrap();
+        });
+        MockSap { url, state, server }
+    }
+
+    /// A client which talks to this stand-in instead of SAP.
+    pub(crate) fn client(&self) -> CachedSapClient {
+        CachedSapClient::with_urls(self.url.clone(), None)
+    }
+
+    /// Every query answered so far. After `record`, these are SAP's answers.
+    pub(crate) fn recorded(&self) -> BTreeMap<String, Value> {
+        self.state.served.lock().unwrap().clone()
+    }
+}
+
+impl Drop for MockSap {
+    fn drop(&mut self) {
+        self.server.abort();
+    }
+}
+
+// The GET queries packed into a batch request body, in order.
+fn batch_queries(body: &str) -> Vec<String> {
+    body.lines()
+        .filter_map(|line| line.strip_prefix("GET "))
+        .map(|request| request.trim_end_matches(" HTTP/1.1").to_string())
+        .collect()
+}
+
+// A multipart batch response in SAP's format: one part per query, each holding the HTTP response
+// to it, with a compact JSON body on a single line.
+fn batch_response(answers: &[Option<&Value>]) -> String {
+    let mut text = String::new();
+    for answer in answers {
+        let (status, body) = match answer {
+            Some(value) => ("200 OK", value.to_string()),
+            None => {
+                let error = json!({
+                    "error": {
+                        "code": "/IWBEP/CM_MGW_RT/020",
+                        "message": {"lang": "en", "value": "Resource not found"},
+                    }
+                });
+                ("404 Not Found", error.to_string())
+            }
+        };
+        text.push_str(&format!(
+            "--{RESPONSE_BOUNDARY}\r\n\
+             Content-Type: application/http\r\n\
+             Content-Length: {}\r\n\
+             content-transfer-encoding: binary\r\n\
+             \r\n\
+             HTTP/1.1 {status}\r\n\
+             Content-Type: application/json\r\n\
+             \r\n\
+             {body}\r\n",
+            body.len()
+        ));
+    }
+    text.push_str(&format!("--{RESPONSE_BOUNDARY}--\r\n"));
+    text
+}
+
+async fn batch(State(state): State<Arc<MockState>>, body: String) -> (StatusCode, String) {
+    let queries = batch_queries(&body);
+    match &state.mode {
+        Mode::Replay(fixture) => {
+            let answers = queries
+                .iter()
+                .map(|query| fixture.get(query))
+                .collect::<Vec<_>>();
+            let mut served = state.served.lock().unwrap();
+            for (query, answer) in queries.iter().zip(&answers) {
+                if let Some(answer) = answer {
+                    served.insert(query.clone(), (*answer).clone());
+                }
+            }
+            (StatusCode::ACCEPTED, batch_response(&answers))
+        }
+        Mode::Record(client) => {
+            let url = format!("{SAP_BASE_URL}/$batch?sap-client=700");
+            let text = match forward(client, &url, body, queries.len()).await {
+                Ok((text, answers)) => {
+                    state
+                        .served
+                        .lock()
+                        .unwrap()
+                        .extend(queries.into_iter().zip(answers));
+                    text
+                }
+                Err(e) => return (StatusCode::BAD_GATEWAY, e.to_string()),
+            };
+            (StatusCode::ACCEPTED, text)
+        }
+    }
+}
+
+async fn forward(
+    client: &CachedSapClient,
+    url: &str,
+    body: String,
+    expected: usize,
+) -> Result<(String, Vec<Value>), SapError> {
+    let text = client.send_batch_post(url, body).await?.text().await?;
+    let answers = CachedSapClient::parse_multi_batch_response(&text, expected)?;
+    Ok((text, answers))
+}
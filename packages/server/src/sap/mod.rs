@@ -18,6 +18,13 @@ use serde_json::Value;
 
 use crate::resources::course::CourseId;
 
+pub mod changes;
+mod source;
+pub use source::CourseSource;
+
+#[cfg(test)]
+pub(crate) mod mock;
+
 const SAP_BASE_URL: &str =
     "https://portalex.technion.ac.il/sap/opu/odata/sap/Z_CM_EV_CDIR_DATA_SRV";
 const BATCH_BOUNDARY: &str = "batch_1d12-afbf-e3c7";
@@ -410,6 +417,7 @@ pub struct FetchStats {
 /// SAP client with in-memory caching at every level.
 pub struct CachedSapClient {
     http: Client,
+    base_url: String,
     proxy_url: Option<String>,
     courses: Cache<String, Arc<CourseDetails>>,
     semesters: Cache<String, Arc<Vec<Semester>>>,
@@ -437,12 +445,18 @@ impl CachedSapClient {
     }
 
     pub fn with_proxy_url(proxy_url: Option<String>) -> Self {
+        Self::with_urls(SAP_BASE_URL.to_string(), proxy_url)
+    }
+
+    /// A client for an OData service other than SAP's, e.g. a local stand-in for tests.
+    pub fn with_urls(base_url: String, proxy_url: Option<String>) -> Self {
         let ttl = Duration::from_secs(CACHE_TTL_HOURS * 3600);
         Self {
             http: Client::builder()
                 .timeout(Duration::from_secs(60))
                 .build()
                 .expect("failed to build HTTP client"),
+            base_url,
             proxy_url,
             courses: Cache::builder()
                 .time_to_live(ttl)
@@ -1281,7 +1295,7 @@ impl CachedSapClient {
     /// Send a single GET query via the OData $batch endpoint.
     /// This is the proven format that SAP accepts.
     async fn batch_get(&self, query: &str) -> Result<Value, SapError> {
-        let url = format!("{SAP_BASE_URL}/$batch?sap-client=700");
+        let url = format!("{}/$batch?sap-client=700", self.base_url);
         let body = format!(
             "\r\n--{BATCH_BOUNDARY}\r\n\
              Content-Type: application/http\r\n\
@@ -1325,7 +1339,7 @@ impl CachedSapClient {
     /// Send multiple GET queries packed into a single OData $batch POST.
     /// Returns one `Value` per query, in the same order.
     async fn batch_get_multi(&self, queries: &[String]) -> Result<Vec<Value>, SapError> {
-        let url = format!("{SAP_BASE_URL}/$batch?sap-client=700");
+        let url = format!("{}/$batch?sap-client=700", self.base_url);
 
         // Build body using the same indented format that works for single GETs
         let mut body = String::new();
@@ -1869,6 +1883,7 @@ fn parse_sap_time(time_str: &str) -> Option<String> {
 
 #[cfg(test)]
 mod tests {
+    use super::mock::MockSap;
     use super::*;
 
     fn client() -> CachedSapClient {
@@ -1988,60 +2003,83 @@ mod tests {
         assert_eq!(parse_corequisites("no coreqs here"), Vec::<CourseId>::new());
     }
 
-    // Integration tests below require SAP API access.
-    // Run with: cargo test -- --ignored
+    // The tests below run against a local stand-in replaying the hand-written `mock::FIXTURE_PATH`.
 
     #[tokio::test]
-    #[ignore]
     async fn test_multi_batch() {
-        let c = client();
+        let sap = MockSap::start().await;
         let queries = vec![
-            "SemesterSet?sap-client=700&$select=PiqYear,PiqSession".to_string(),
-            "SemesterSet?sap-client=700&$select=PiqYear".to_string(),
+            "SemesterSet?sap-client=700&$select=PiqYear,PiqSession,Begda,Endda".to_string(),
+            "SmObjectSet?sap-client=700&$filter=unknown".to_string(),
         ];
-        let results = c.batch_get_multi(&queries).await.unwrap();
+        let results = sap.client().batch_get_multi(&queries).await.unwrap();
         assert_eq!(results.len(), 2);
-        // Both should contain semester data
         assert!(!results[0]["d"]["results"].as_array().unwrap().is_empty());
-        assert!(!results[1]["d"]["results"].as_array().unwrap().is_empty());
+        assert!(results[1]["error"].is_object());
     }
 
     #[tokio::test]
-    #[ignore]
     async fn test_fetch_semesters() {
-        let semesters = client().get_semesters().await.unwrap();
-        assert!(!semesters.is_empty());
+        let sap = MockSap::start().await;
+        let semesters = sap.client().get_semesters().await.unwrap();
+        assert_eq!(semesters.len(), 2);
+        assert_eq!(semesters[0].year, "2025");
+        assert_eq!(semesters[0].semester, "200");
+        assert_eq!(semesters[0].begin_date, "19-10-2025");
     }
 
     #[tokio::test]
-    #[ignore]
     async fn test_fetch_course_ids() {
-        let semesters = client().get_semesters().await.unwrap();
-        let s = &semesters[0];
-        let ids = client().get_course_ids(&s.year, &s.semester).await.unwrap();
-        assert!(ids.len() > 100);
+        let sap = MockSap::start().await;
+        let client = sap.client();
+        let ids = client.get_course_ids("2025", "200").await.unwrap();
+        assert_eq!(ids.len(), 3);
         // IDs should be 8-digit course numbers without SM prefix
         assert!(ids.iter().all(|id| !id.starts_with("SM")));
+        let index = client.get_course_index("2025", "200").await.unwrap();
+        assert_eq!(index[1].name, "חשבון אינפיניטסימלי 1מ'");
+        assert_eq!(index[1].credits, 5.5);
     }
 
     #[tokio::test]
-    #[ignore]
     async fn test_cached_course_details() {
-        let c = client();
+        let sap = MockSap::start().await;
+        let c = sap.client();
 
-        // First fetch — hits SAP
+        // First fetch — hits the stand-in
         let d1 = c
             .get_course_details("2025", "200", "02340114")
             .await
             .unwrap();
         assert_eq!(d1.name, "מבוא למדעי המחשב מ'");
         assert_eq!(*d1.id, *"02340114");
-        assert!(d1.credits > 0.0);
-        assert!(!d1.exams.is_empty());
-        assert!(d1.exams.len() < 10); // deduped
-        assert!(!d1.relations.is_empty());
-        assert!(!d1.schedule.is_empty());
-        assert!(!d1.offered_periods.is_empty());
+        assert_eq!(d1.credits, 4.0);
+        assert!(!d1.is_english);
+        assert_eq!(d1.responsible[0].name, "יוסי כהן");
+        assert_eq!(d1.offered_periods[0].semester, "200");
+
+        // The timeless parent of the first exam is dropped in favor of its timed child
+        let exams = d1
+            .exams
+            .iter()
+            .map(|e| {
+                (
+                    e.category_code.as_str(),
+                    e.date.as_deref(),
+                    e.begin_time.as_deref(),
+                )
+            })
+            .collect::<Vec<_>>();
+        assert_eq!(
+            exams,
+            vec![
+                ("FI", Some("10-02-2026"), Some("09:00")),
+                ("FB", Some("15-03-2026"), None),
+            ]
+        );
+        // Duplicate relations collapse, unknown relation types are skipped
+        assert_eq!(d1.relations.len(), 1);
+        assert_eq!(*d1.relations[0].course_id, *"02340117");
 
         // Second fetch — should be instant (cached)
         let d2 = c
@@ -2050,13 +2088,77 @@ mod tests {
             .unwrap();
         assert!(Arc::ptr_eq(&d1, &d2)); // same Arc = same cache entry
 
-        // Verify JSON serialization works
-        let json = serde_json::to_string_pretty(&*d1).unwrap();
-        assert!(json.contains("02340114"));
-        assert!(json.contains("מבוא למדעי המחשב"));
-        println!("{json}");
+        let d3 = c
+            .get_course_details("2025", "200", "01040031")
+            .await
+            .unwrap();
+        assert_eq!(d3.corequisites, vec![CourseId::new("01040166")]);
+        assert!(d3.prerequisites.is_empty());
+
+        assert!(c
+            .get_course_details("2025", "200", "00000000")
+            .await
+            .is_err());
     }
 
+    #[tokio::test]
+    async fn test_parse_schedule() {
+        let sap = MockSap::start().await;
+        let details = sap
+            .client()
+            .get_course_details("2025", "200", "02340114")
+            .await
+            .unwrap();
+
+        // Group 077 is filtered out
+        let groups = details
+            .schedule
+            .iter()
+            .map(|g| g.group.as_str())
+            .collect::<Vec<_>>();
+        assert_eq!(groups, vec!["10", "11"]);
+
+        // The one-off makeup session is dropped, leaving the weekly Monday lecture
+        let lecture = &details.schedule[0].events;
+        assert_eq!(lecture.len(), 1);
+        assert_eq!(lecture[0].kind, "הרצאה");
+        assert_eq!(lecture[0].day, Some(1));
+        assert_eq!(lecture[0].start_time.as_deref(), Some("10:30"));
+        assert_eq!(lecture[0].end_time.as_deref(), Some("12:30"));
+        assert_eq!(lecture[0].building.as_deref(), Some("טאוב"));
+        assert_eq!(lecture[0].room.as_deref(), Some("2"));
+        assert_eq!(lecture[0].lecturer.as_deref(), Some("פרופ' יוסי כהן"));
+
+        let tutorial = &details.schedule[1].events;
+        assert_eq!(tutorial[0].day, Some(3));
+        assert_eq!(tutorial[0].building, None);
+        assert_eq!(tutorial[0].room, None);
+        assert_eq!(tutorial[0].lecturer, None);
+    }
+
+    // Records SAP's answers to the queries the fixture covers into a file in the temp directory,
+    // to check the fixture's shape against. The fixture itself is left alone. Requires SAP API
+    // access.
+    #[tokio::test]
+    #[ignore]
+    async fn record_sap_responses() {
+        let sap = MockSap::record().await;
+        let c = sap.client();
+        c.get_semesters().await.unwrap();
+        c.get_course_ids("2025", "200").await.unwrap();
+        c.get_course_index("2025", "200").await.unwrap();
+        for course in ["02340114", "01040031"] {
+            c.get_course_details("2025", "200", course).await.unwrap();
+        }
+        let recording = serde_json::to_string_pretty(&sap.recorded()).unwrap();
+        let path = std::env::temp_dir().join("sap_recording.json");
+        std::fs::write(&path, recording + "\n").unwrap();
+        println!("Recorded SAP's answers to {}", path.display());
+    }
+
+    // Integration tests below require SAP API access.
+    // Run with: cargo test -- --ignored
+
     #[tokio::test]
     #[ignore]
     async fn test_investigate_all_semesters_with_dates() {
@@ -0,0 +1,75 @@
+use std::future::Future;
+use std::sync::Arc;
+
+use super::{CachedSapClient, CourseDetails, CourseIndexEntry, SapError, Semester};
+
+/// Where the fetcher gets course data from. `CachedSapClient` is the real thing; pointed at a local
+/// stand-in (see `mock::MockSap`), it lets the fetcher run without reaching SAP.
+pub trait CourseSource: Send + Sync + 'static {
+    /// All semesters, newest first.
+    fn get_semesters(&self) -> impl Future<Output = Result<Arc<Vec<Semester>>, SapError>> + Send;
+
+    fn get_course_index(
+        &self,
+        year: &str,
+        semester: &str,
+    ) -> impl Future<Output = Result<Arc<Vec<CourseIndexEntry>>, SapError>> + Send;
+
+    fn get_course_details(
+        &self,
+        year: &str,
+        semester: &str,
+        course_number: &str,
+    ) -> impl Future<Output = Result<Arc<CourseDetails>, SapError>> + Send;
+
+    /// Fetches every course of the semester ahead of time, so that `get_course_details` is served
+    /// from memory. Sources which are cheap to query may do nothing.
+    fn prefetch_semester(
+        self: &Arc<Self>,
+        year: &str,
+        semester: &str,
+        concurrency: usize,
+    ) -> impl Future<Output = ()> + Send;
+
+    /// (total, done) course counts of the prefetches so far.
+    fn fetch_progress_counts(&self) -> (usize, usize);
+
+    /// Total bytes (sent, received) so far.
+    fn transfer_stats(&self) -> (usize, usize);
+}
+
+impl CourseSource for CachedSapClient {
+    async fn get_semesters(&self) -> Result<Arc<Vec<Semester>>, SapError> {
+        CachedSapClient::get_semesters(self).await
+    }
+
+    async fn get_course_index(
+        &self,
+        year: &str,
+        semester: &str,
+    ) -> Result<Arc<Vec<CourseIndexEntry>>, SapError> {
+        CachedSapClient::get_course_index(self, year, semester).await
+    }
+
+    async fn get_course_details(
+        &self,
+        year: &str,
+        semester: &str,
+        course_number: &str,
+    ) -> Result<Arc<CourseDetails>, SapError> {
+        CachedSapClient::get_course_details(self, year, semester, course_number).await
+    }
+
+    async fn prefetch_semester(self: &Arc<Self>, year: &str, semester: &str, concurrency: usize) {
+        self.fetch_semester_with_concurrency(year, semester, concurrency)
+            .await
+    }
+
+    fn fetch_progress_counts(&self) -> (usize, usize) {
+        CachedSapClient::fetch_progress_counts(self)
+    }
+
+    fn transfer_stats(&self) -> (usize, usize) {
+        CachedSapClient::transfer_stats(self)
+    }
+}
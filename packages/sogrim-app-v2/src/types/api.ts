@@ -56,6 +56,7 @@ export interface CourseStatus {
   additional_msg?: string;
   times_repeated: number;
   is_repetition?: boolean;
+  no_additional_credit?: boolean;
 }
 
 export interface CourseBankReq {